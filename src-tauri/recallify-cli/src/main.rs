@@ -0,0 +1,712 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use recallify_core::error::AppError;
+use recallify_core::models::*;
+use recallify_core::data_dir;
+use recallify_core::profiles;
+use recallify_core::load_balance::LoadBalancing;
+use recallify_core::{catch_up, clock, db, forecast, queries, review_log, scheduler, search};
+use rusqlite::Connection;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+// Scriptable access to the same recallify.db the app uses. Every command
+// prints its result as JSON on stdout; failures print the AppError as JSON
+// on stderr and exit with status 1. Safe to run while the app is open: the
+// database is in WAL mode and writes wait for the app's lock.
+
+#[derive(Parser)]
+#[command(name = "recallify-cli", version, about = "Read and update Recallify study data")]
+struct Cli {
+    /// Directory holding recallify.db (defaults to the app's data directory)
+    #[arg(long, global = true, env = "RECALLIFY_DATA_DIR")]
+    data_dir: Option<PathBuf>,
+
+    /// Profile to use (defaults to the active profile)
+    #[arg(long, global = true, env = "RECALLIFY_PROFILE")]
+    profile: Option<String>,
+
+    /// Passphrase of an encrypted database
+    #[arg(long, global = true, env = "RECALLIFY_PASSPHRASE", hide_env_values = true)]
+    passphrase: Option<String>,
+
+    /// Pretty-print the JSON output
+    #[arg(long, global = true)]
+    pretty: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List, add, rename and delete subjects
+    #[command(subcommand)]
+    Subjects(SubjectsCommand),
+    /// List, show, add and delete study entries
+    #[command(subcommand)]
+    Entries(EntriesCommand),
+    /// Due revisions and completing or rescheduling them
+    #[command(subcommand)]
+    Revisions(RevisionsCommand),
+    /// Tags and the tags of an entry
+    #[command(subcommand)]
+    Tags(TagsCommand),
+    /// Choose between SM-2 and FSRS, tune FSRS and spread revisions out
+    #[command(subcommand)]
+    Scheduler(SchedulerCommand),
+    /// The time zone dates and day boundaries are computed in
+    #[command(subcommand)]
+    Timezone(TimezoneCommand),
+    /// The log of completed revisions, retention and undoing a grade
+    #[command(subcommand)]
+    Reviews(ReviewsCommand),
+    /// Log and list pomodoro sessions
+    #[command(subcommand)]
+    Pomodoro(PomodoroCommand),
+    /// Study report: subjects, activity, study time and pomodoro totals
+    Report(ReportArgs),
+    /// Full-text search over notes, recall notes, topics and tags
+    Search(SearchArgs),
+    /// Where the data directory is, and moving it
+    #[command(subcommand)]
+    Data(DataCommand),
+    /// List, create, rename, delete and switch study profiles
+    #[command(subcommand)]
+    Profiles(ProfilesCommand),
+}
+
+#[derive(Subcommand)]
+enum ProfilesCommand {
+    List,
+    Create { name: String },
+    Rename { id: String, name: String },
+    /// Deletes the profile with its database, PDFs and backups
+    Delete { id: String },
+    /// Makes the profile active for the app and the CLI. Quit the app first.
+    Switch { id: String },
+}
+
+#[derive(Subcommand)]
+enum DataCommand {
+    /// The data directory in use and whether it is portable, custom or the default
+    Location,
+    /// Moves recallify.db, pdfs and backups into an empty folder and uses it
+    /// from then on. Quit the app first.
+    Move { path: PathBuf },
+}
+
+#[derive(Subcommand)]
+enum SubjectsCommand {
+    List,
+    Add { name: String },
+    Rename { id: i64, name: String },
+    /// Deletes the subject with all of its entries and revisions
+    Delete { id: i64 },
+}
+
+#[derive(Subcommand)]
+enum EntriesCommand {
+    List {
+        #[arg(long)]
+        subject: i64,
+    },
+    Show { id: i64 },
+    Add(AddEntryArgs),
+    Delete { id: i64 },
+}
+
+#[derive(Args)]
+struct AddEntryArgs {
+    #[arg(long)]
+    subject: i64,
+    /// What was studied
+    #[arg(long)]
+    notes: String,
+    /// Study date as YYYY-MM-DD (defaults to today)
+    #[arg(long)]
+    date: Option<String>,
+    /// Revision intervals in days, e.g. 1,3,7 (defaults to the app setting)
+    #[arg(long, value_delimiter = ',')]
+    intervals: Option<Vec<i64>>,
+    #[arg(long)]
+    topics: Option<String>,
+    /// Tags to attach to the entry
+    #[arg(long, value_delimiter = ',')]
+    tags: Vec<String>,
+}
+
+#[derive(Subcommand)]
+enum RevisionsCommand {
+    /// Revisions due today, including overdue ones
+    Due,
+    /// Revisions due on a date (YYYY-MM-DD)
+    On { date: String },
+    Complete { id: i64 },
+    /// Completes a revision with a 0-5 grade (0 = blackout, 5 = perfect) and
+    /// schedules the next one with the selected scheduler
+    Grade {
+        id: i64,
+        quality: u8,
+        /// How long the review took
+        #[arg(long)]
+        seconds: Option<u32>,
+    },
+    Uncomplete { id: i64 },
+    Reschedule { id: i64, date: String },
+    /// Reviews expected per day from today, with estimated minutes
+    Forecast {
+        /// How many days ahead, up to 365
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+        #[arg(long)]
+        subject: Option<i64>,
+    },
+    /// Moves every overdue revision to a day from today on, filling each
+    /// day up to a maximum
+    CatchUp {
+        #[arg(long)]
+        max_per_day: i64,
+        /// Which revisions get the first days
+        #[arg(long, value_enum, default_value_t = Strategy::Interval)]
+        strategy: Strategy,
+        /// Shows the plan without moving anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Strategy {
+    /// Shortest intervals first
+    Interval,
+    /// One subject at a time, the most overdue first
+    Subject,
+    /// Nearest syllabus due date first
+    ExamDate,
+}
+
+impl From<Strategy> for CatchUpStrategy {
+    fn from(strategy: Strategy) -> Self {
+        match strategy {
+            Strategy::Interval => CatchUpStrategy::Interval,
+            Strategy::Subject => CatchUpStrategy::Subject,
+            Strategy::ExamDate => CatchUpStrategy::ExamDate,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum ReviewsCommand {
+    /// Completed revisions, newest first
+    List(ReviewFilterArgs),
+    /// Share of graded reviews that were recalled (graded 3 or more)
+    Retention(ReviewFilterArgs),
+    /// Takes back the latest grade of an entry and reopens its revision
+    Undo { id: i64 },
+}
+
+#[derive(Args)]
+struct ReviewFilterArgs {
+    #[arg(long)]
+    entry: Option<i64>,
+    #[arg(long)]
+    subject: Option<i64>,
+    /// Earliest review date (YYYY-MM-DD)
+    #[arg(long)]
+    from: Option<String>,
+    /// Latest review date (YYYY-MM-DD)
+    #[arg(long)]
+    to: Option<String>,
+    #[arg(long)]
+    limit: Option<i64>,
+}
+
+impl From<ReviewFilterArgs> for ReviewLogFilters {
+    fn from(args: ReviewFilterArgs) -> Self {
+        ReviewLogFilters {
+            entry_id: args.entry,
+            subject_id: args.subject,
+            start_date: args.from,
+            end_date: args.to,
+            limit: args.limit,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum SchedulerCommand {
+    /// The scheduler in use and the FSRS settings
+    Show,
+    /// Schedules revisions graded from now on with this algorithm
+    Use {
+        #[arg(value_enum)]
+        algorithm: AlgorithmArg,
+    },
+    /// Fits the FSRS parameters to the revision history and saves them
+    Optimize,
+    /// Goes back to the default FSRS parameters
+    Reset,
+    /// Spreads new revisions over lighter days; options left out keep
+    /// their current value
+    Balance {
+        #[arg(value_enum)]
+        mode: Option<Toggle>,
+        /// Open revisions per day to stay under, 0 for no limit
+        #[arg(long)]
+        max_per_day: Option<i64>,
+        /// How far a revision may move, in percent of its interval
+        #[arg(long)]
+        tolerance: Option<i64>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Toggle {
+    On,
+    Off,
+}
+
+#[derive(Subcommand)]
+enum TimezoneCommand {
+    /// The time zone setting, the zone in effect and today's date there
+    Show,
+    /// Uses this IANA time zone (e.g. Europe/Paris), or the system's
+    /// without one
+    Set { timezone: Option<String> },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum AlgorithmArg {
+    Sm2,
+    Fsrs,
+}
+
+impl From<AlgorithmArg> for scheduler::Algorithm {
+    fn from(algorithm: AlgorithmArg) -> Self {
+        match algorithm {
+            AlgorithmArg::Sm2 => scheduler::Algorithm::Sm2,
+            AlgorithmArg::Fsrs => scheduler::Algorithm::Fsrs,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum TagsCommand {
+    List,
+    /// Tags of an entry
+    Entry { id: i64 },
+    /// Replaces the tags of an entry
+    Set {
+        id: i64,
+        #[arg(value_delimiter = ',')]
+        tags: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum PomodoroCommand {
+    /// Records a finished session
+    Log {
+        #[arg(long, default_value_t = 25)]
+        minutes: i64,
+        #[arg(long, value_enum, default_value_t = SessionType::Work)]
+        kind: SessionType,
+        #[arg(long)]
+        subject: Option<i64>,
+    },
+    List,
+    Stats {
+        #[arg(long, value_enum, default_value_t = Range::Week)]
+        range: Range,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SessionType {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+impl SessionType {
+    fn as_str(self) -> &'static str {
+        match self {
+            SessionType::Work => "work",
+            SessionType::ShortBreak => "short_break",
+            SessionType::LongBreak => "long_break",
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Range {
+    Today,
+    Week,
+    Month,
+    All,
+}
+
+impl From<Range> for StatsRange {
+    fn from(range: Range) -> Self {
+        match range {
+            Range::Today => StatsRange::Today,
+            Range::Week => StatsRange::Week,
+            Range::Month => StatsRange::Month,
+            Range::All => StatsRange::All,
+        }
+    }
+}
+
+#[derive(Args)]
+struct ReportArgs {
+    /// First day of the activity log (YYYY-MM-DD)
+    #[arg(long)]
+    from: Option<String>,
+    /// Last day of the activity log (YYYY-MM-DD)
+    #[arg(long)]
+    to: Option<String>,
+    /// Number of days the study time totals cover
+    #[arg(long, default_value_t = 30)]
+    days: i64,
+}
+
+#[derive(Args)]
+struct SearchArgs {
+    /// Words to find; matches word prefixes, "quoted text" matches a phrase
+    query: String,
+    #[arg(long)]
+    subject: Option<i64>,
+    #[arg(long)]
+    tag: Option<String>,
+    /// Earliest study date (YYYY-MM-DD)
+    #[arg(long)]
+    from: Option<String>,
+    /// Latest study date (YYYY-MM-DD)
+    #[arg(long)]
+    to: Option<String>,
+    #[arg(long, default_value_t = search::DEFAULT_LIMIT)]
+    limit: i64,
+}
+
+fn default_intervals(conn: &Connection) -> rusqlite::Result<Vec<i64>> {
+    let setting = queries::get_setting(conn, "default_intervals")?;
+    let intervals: Vec<i64> = setting
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|part| part.trim().parse().ok())
+        .collect();
+    // Same fallback as the entry form
+    Ok(if intervals.is_empty() { vec![3, 7] } else { intervals })
+}
+
+fn to_json<T: serde::Serialize>(value: T) -> Result<Value, AppError> {
+    serde_json::to_value(value).map_err(|e| AppError::internal(e.to_string()))
+}
+
+fn subjects(conn: &Connection, command: SubjectsCommand) -> Result<Value, AppError> {
+    match command {
+        SubjectsCommand::List => to_json(queries::get_subjects(conn)?),
+        SubjectsCommand::Add { name } => to_json(queries::create_subject(conn, &name)?),
+        SubjectsCommand::Rename { id, name } => to_json(queries::update_subject(conn, id, &name)?),
+        SubjectsCommand::Delete { id } => {
+            queries::delete_subject(conn, id)?;
+            Ok(json!({ "deleted": id }))
+        }
+    }
+}
+
+fn add_entry(conn: &Connection, args: AddEntryArgs) -> Result<Value, AppError> {
+    let study_date = match args.date {
+        Some(date) => date,
+        None => clock::today(conn)?,
+    };
+    let intervals = match args.intervals {
+        Some(intervals) => intervals,
+        None => default_intervals(conn)?,
+    };
+    if intervals.iter().any(|days| *days < 1) {
+        return Err(AppError::validation("Intervals must be at least 1 day"));
+    }
+
+    let entry = queries::create_entry(
+        conn,
+        &NewEntry {
+            subject_id: args.subject,
+            study_date: study_date.clone(),
+            study_notes: args.notes,
+            intervals,
+            topics: args.topics,
+        },
+    )?;
+    if !args.tags.is_empty() {
+        queries::link_tags_to_entry(conn, entry.entry.id, &args.tags)?;
+    }
+    queries::update_daily_activity(conn, &study_date)?;
+    to_json(queries::get_entry(conn, entry.entry.id)?)
+}
+
+fn entries(conn: &Connection, command: EntriesCommand) -> Result<Value, AppError> {
+    match command {
+        EntriesCommand::List { subject } => to_json(queries::get_entries_by_subject(conn, subject)?),
+        EntriesCommand::Show { id } => to_json(queries::get_entry(conn, id)?),
+        EntriesCommand::Add(args) => add_entry(conn, args),
+        EntriesCommand::Delete { id } => {
+            queries::delete_entry(conn, id)?;
+            Ok(json!({ "deleted": id }))
+        }
+    }
+}
+
+fn revisions(conn: &Connection, command: RevisionsCommand) -> Result<Value, AppError> {
+    match command {
+        RevisionsCommand::Due => {
+            // Same as the app does on launch, so overdue revisions show as such
+            queries::update_overdue_revisions(conn)?;
+            to_json(queries::get_revisions_due_today(conn)?)
+        }
+        RevisionsCommand::On { date } => to_json(queries::get_revisions_by_date(conn, &date)?),
+        RevisionsCommand::Complete { id } => {
+            queries::complete_revision(conn, id)?;
+            to_json(queries::get_revision(conn, id)?)
+        }
+        RevisionsCommand::Grade { id, quality, seconds } => {
+            to_json(scheduler::grade_revision(conn, id, quality, seconds)?)
+        }
+        RevisionsCommand::Uncomplete { id } => to_json(scheduler::uncomplete_revision(conn, id)?),
+        RevisionsCommand::Reschedule { id, date } => to_json(queries::reschedule_revision(conn, id, &date)?),
+        RevisionsCommand::Forecast { days, subject } => to_json(forecast::forecast_reviews(conn, days, subject)?),
+        RevisionsCommand::CatchUp { max_per_day, strategy, dry_run } => {
+            to_json(catch_up::plan_catch_up(conn, max_per_day, strategy.into(), dry_run)?)
+        }
+    }
+}
+
+fn tags(conn: &Connection, command: TagsCommand) -> Result<Value, AppError> {
+    match command {
+        TagsCommand::List => to_json(queries::get_all_tags(conn)?),
+        TagsCommand::Entry { id } => to_json(queries::get_entry_tags(conn, id)?),
+        TagsCommand::Set { id, tags } => {
+            // Fail on a missing entry instead of silently linking nothing
+            queries::get_entry(conn, id)?;
+            queries::link_tags_to_entry(conn, id, &tags)?;
+            to_json(queries::get_entry_tags(conn, id)?)
+        }
+    }
+}
+
+fn reviews(conn: &Connection, command: ReviewsCommand) -> Result<Value, AppError> {
+    match command {
+        ReviewsCommand::List(args) => to_json(review_log::get_review_log(conn, &args.into())?),
+        ReviewsCommand::Retention(args) => to_json(review_log::retention_stats(conn, &args.into())?),
+        ReviewsCommand::Undo { id } => to_json(scheduler::undo_review(conn, id)?),
+    }
+}
+
+fn scheduler_settings(conn: &Connection) -> Result<Value, AppError> {
+    Ok(json!({
+        "scheduler": scheduler::Algorithm::from_settings(conn)?.as_str(),
+        "desiredRetention": scheduler::desired_retention(conn)?,
+        "fsrsParameters": scheduler::fsrs_from_settings(conn)?.w,
+        "loadBalancing": LoadBalancing::from_settings(conn)?,
+    }))
+}
+
+fn scheduler(conn: &Connection, command: SchedulerCommand) -> Result<Value, AppError> {
+    match command {
+        SchedulerCommand::Show => {}
+        SchedulerCommand::Use { algorithm } => {
+            let algorithm = scheduler::Algorithm::from(algorithm);
+            queries::update_setting(conn, scheduler::SCHEDULER_SETTING, algorithm.as_str())?;
+        }
+        SchedulerCommand::Optimize => return to_json(scheduler::optimize_fsrs(conn)?),
+        SchedulerCommand::Reset => scheduler::reset_fsrs(conn)?,
+        SchedulerCommand::Balance { mode, max_per_day, tolerance } => {
+            let mut settings = LoadBalancing::from_settings(conn)?;
+            if let Some(mode) = mode {
+                settings.enabled = matches!(mode, Toggle::On);
+            }
+            if let Some(max) = max_per_day {
+                settings.max_daily_reviews = (max != 0).then_some(max);
+            }
+            if let Some(tolerance) = tolerance {
+                settings.tolerance_percent = tolerance;
+            }
+            settings.save(conn)?;
+        }
+    }
+    scheduler_settings(conn)
+}
+
+fn timezone(conn: &Connection, command: TimezoneCommand) -> Result<Value, AppError> {
+    match command {
+        TimezoneCommand::Show => to_json(clock::info(conn)?),
+        TimezoneCommand::Set { timezone } => to_json(clock::set_timezone(conn, timezone.as_deref())?),
+    }
+}
+
+fn pomodoro(conn: &Connection, command: PomodoroCommand) -> Result<Value, AppError> {
+    match command {
+        PomodoroCommand::Log { minutes, kind, subject } => {
+            if minutes < 1 {
+                return Err(AppError::validation("Minutes must be at least 1"));
+            }
+            let id = queries::record_pomodoro_session(
+                conn,
+                &NewPomodoroSession {
+                    session_type: kind.as_str().to_string(),
+                    duration_minutes: minutes,
+                    subject_id: subject,
+                    syllabus_item_id: None,
+                },
+            )?;
+            queries::update_daily_activity(conn, &clock::today(conn)?)?;
+            Ok(json!({ "id": id }))
+        }
+        PomodoroCommand::List => to_json(queries::get_pomodoro_sessions(conn)?),
+        PomodoroCommand::Stats { range } => to_json(queries::get_pomodoro_stats(conn, range.into())?),
+    }
+}
+
+fn report(conn: &Connection, args: ReportArgs) -> Result<Value, AppError> {
+    let filters = ActivityLogFilters {
+        start_date: args.from.clone(),
+        end_date: args.to.clone(),
+        ..Default::default()
+    };
+    Ok(json!({
+        "generatedOn": clock::today(conn)?,
+        "from": args.from,
+        "to": args.to,
+        "subjects": to_json(queries::get_subjects(conn)?)?,
+        "activity": to_json(queries::get_activity_log(conn, &filters)?)?,
+        "studyTime": to_json(queries::get_all_subjects_study_time(conn, args.days)?)?,
+        "pomodoro": to_json(queries::get_pomodoro_stats(conn, StatsRange::All)?)?,
+        "activeDates": to_json(queries::get_active_dates(conn)?)?,
+    }))
+}
+
+fn search(conn: &Connection, args: SearchArgs) -> Result<Value, AppError> {
+    let filters = SearchFilters {
+        subject_id: args.subject,
+        tag: args.tag,
+        start_date: args.from,
+        end_date: args.to,
+        limit: Some(args.limit),
+    };
+    to_json(search::search_entries(conn, &args.query, &filters)?)
+}
+
+fn data_dir(cli: &Cli) -> PathBuf {
+    cli.data_dir.clone().unwrap_or_else(data_dir::app_data_dir)
+}
+
+// Where the database of the selected profile lives
+fn profile_dir(cli: &Cli) -> Result<PathBuf, AppError> {
+    let root = data_dir(cli);
+    match &cli.profile {
+        Some(id) => {
+            profiles::load(&root)?.find(id)?;
+            Ok(profiles::profile_dir(&root, id))
+        }
+        None => Ok(profiles::active_dir(&root)),
+    }
+}
+
+fn open(cli: &Cli) -> Result<Connection, AppError> {
+    let data_dir = profile_dir(cli)?;
+    let path = db::db_path(&data_dir);
+    if cli.passphrase.is_none() && db::is_encrypted(&path)? {
+        return Err(AppError::locked(
+            "Database is encrypted; pass --passphrase or set RECALLIFY_PASSPHRASE",
+        ));
+    }
+    db::open(&data_dir, cli.passphrase.as_deref())
+}
+
+// Only a move of the app's own data directory is remembered in
+// location.json; with --data-dir the files are moved and nothing else.
+fn move_data(cli: &Cli, to: &Path) -> Result<Value, AppError> {
+    if cli.data_dir.is_none() {
+        data_dir::check_movable(&data_dir::locate())?;
+    }
+    let from = data_dir(cli);
+    let active = profile_dir(cli)?;
+    let conn = open(cli)?;
+    data_dir::copy_data(&conn, cli.passphrase.as_deref(), &active, &from, to)?;
+    drop(conn);
+
+    if cli.data_dir.is_none() {
+        data_dir::set_custom_dir(Some(to))?;
+    }
+    let leftovers = data_dir::remove_data(&from);
+    Ok(json!({ "path": to, "leftovers": leftovers }))
+}
+
+fn data(cli: &Cli, command: &DataCommand) -> Result<Value, AppError> {
+    match command {
+        DataCommand::Location => match &cli.data_dir {
+            Some(path) => Ok(json!({ "path": path, "source": "argument" })),
+            None => to_json(data_dir::locate()),
+        },
+        DataCommand::Move { path } => move_data(cli, path),
+    }
+}
+
+fn profiles(cli: &Cli, command: &ProfilesCommand) -> Result<Value, AppError> {
+    let root = data_dir(cli);
+    match command {
+        ProfilesCommand::List => {}
+        ProfilesCommand::Create { name } => return to_json(profiles::create(&root, name)?),
+        ProfilesCommand::Rename { id, name } => return to_json(profiles::rename(&root, id, name)?),
+        ProfilesCommand::Delete { id } => profiles::delete(&root, id)?,
+        ProfilesCommand::Switch { id } => profiles::set_active(&root, id)?,
+    }
+    to_json(profiles::load(&root)?)
+}
+
+fn run(cli: Cli) -> Result<Value, AppError> {
+    match &cli.command {
+        Command::Data(command) => return data(&cli, command),
+        Command::Profiles(command) => return profiles(&cli, command),
+        _ => {}
+    }
+    let conn = open(&cli)?;
+    match cli.command {
+        Command::Subjects(command) => subjects(&conn, command),
+        Command::Entries(command) => entries(&conn, command),
+        Command::Revisions(command) => revisions(&conn, command),
+        Command::Tags(command) => tags(&conn, command),
+        Command::Scheduler(command) => scheduler(&conn, command),
+        Command::Timezone(command) => timezone(&conn, command),
+        Command::Reviews(command) => reviews(&conn, command),
+        Command::Pomodoro(command) => pomodoro(&conn, command),
+        Command::Report(args) => report(&conn, args),
+        Command::Search(args) => search(&conn, args),
+        Command::Data(_) | Command::Profiles(_) => unreachable!("handled before opening the database"),
+    }
+}
+
+fn print(value: &impl serde::Serialize, pretty: bool) -> String {
+    let printed = if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    };
+    printed.unwrap_or_else(|e| format!("{{\"code\":\"internal\",\"message\":\"{}\"}}", e))
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let pretty = cli.pretty;
+    match run(cli) {
+        Ok(value) => {
+            println!("{}", print(&value, pretty));
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", print(&e, pretty));
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use serde_json::Value;
+use std::path::Path;
+use std::process::Command;
+
+fn cli(data_dir: &Path, args: &[&str]) -> (bool, Value) {
+    let output = Command::new(env!("CARGO_BIN_EXE_recallify-cli"))
+        .arg("--data-dir")
+        .arg(data_dir)
+        .args(args)
+        .env_remove("RECALLIFY_PASSPHRASE")
+        .env_remove("RECALLIFY_PROFILE")
+        .output()
+        .unwrap();
+    // Migration progress also goes to stderr; the error is the last line
+    let printed = if output.status.success() {
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        stderr.lines().last().unwrap_or_default().to_string()
+    };
+    let value = serde_json::from_str(&printed).unwrap_or_else(|e| panic!("{}: {}", e, printed));
+    (output.status.success(), value)
+}
+
+fn ok(data_dir: &Path, args: &[&str]) -> Value {
+    let (success, value) = cli(data_dir, args);
+    assert!(success, "{:?} failed: {}", args, value);
+    value
+}
+
+#[test]
+fn study_session_from_the_terminal() {
+    let dir = tempfile::tempdir().unwrap();
+    let dir = dir.path();
+
+    let subject = ok(dir, &["subjects", "add", "Statistics"]);
+    let subject_id = subject["id"].as_i64().unwrap().to_string();
+
+    let entry = ok(
+        dir,
+        &[
+            "entries", "add", "--subject", &subject_id, "--notes", "Bayes", "--date", "2024-03-01",
+            "--intervals", "1,3", "--tags", "probability,exam",
+        ],
+    );
+    assert_eq!(entry["revisions"].as_array().unwrap().len(), 2);
+    assert_eq!(entry["revisions"][0]["due_date"], "2024-03-02");
+    let entry_id = entry["id"].as_i64().unwrap().to_string();
+
+    let tags = ok(dir, &["tags", "entry", &entry_id]);
+    assert_eq!(tags.as_array().unwrap().len(), 2);
+
+    // Both revisions are long overdue
+    let due = ok(dir, &["revisions", "due"]);
+    assert_eq!(due.as_array().unwrap().len(), 2);
+    let revision_id = due[0]["id"].as_i64().unwrap().to_string();
+    let completed = ok(dir, &["revisions", "complete", &revision_id]);
+    assert_eq!(completed["status"], "completed");
+
+    // Grading the other one hands the entry over to SM-2
+    let revision_id = due[1]["id"].as_i64().unwrap().to_string();
+    let graded = ok(dir, &["revisions", "grade", &revision_id, "4"]);
+    assert_eq!(graded["revision"]["quality"], 4);
+    assert_eq!(graded["entry"]["repetitions"], 1);
+    assert_eq!(graded["next_revision"]["status"], "pending");
+    let (success, error) = cli(dir, &["revisions", "grade", &revision_id, "4"]);
+    assert!(!success);
+    assert_eq!(error["code"], "validation");
+    assert!(graded["entry"]["stability"].as_f64().unwrap() > 0.0);
+
+    let review_id = graded["review"]["id"].as_i64().unwrap().to_string();
+    let retention = ok(dir, &["reviews", "retention"]);
+    assert_eq!(retention["reviews"], 1);
+    assert_eq!(retention["recalled"], 1);
+    assert!(retention["averageDaysLate"].as_f64().unwrap() > 0.0);
+    let reopened = ok(dir, &["reviews", "undo", &review_id]);
+    assert_eq!(reopened["id"].as_i64().unwrap().to_string(), revision_id);
+    ok(dir, &["revisions", "grade", &revision_id, "4", "--seconds", "30"]);
+    let log = ok(dir, &["reviews", "list", "--limit", "1"]);
+    assert_eq!(log[0]["duration_seconds"], 30);
+
+    let settings = ok(dir, &["scheduler", "use", "fsrs"]);
+    assert_eq!(settings["scheduler"], "fsrs");
+    assert_eq!(settings["fsrsParameters"].as_array().unwrap().len(), 17);
+    let (success, error) = cli(dir, &["scheduler", "optimize"]);
+    assert!(!success);
+    assert_eq!(error["code"], "validation");
+
+    let settings = ok(dir, &["scheduler", "balance", "on", "--max-per-day", "20"]);
+    assert_eq!(settings["loadBalancing"]["enabled"], true);
+    assert_eq!(settings["loadBalancing"]["maxDailyReviews"], 20);
+    let (success, error) = cli(dir, &["scheduler", "balance", "--tolerance", "90"]);
+    assert!(!success);
+    assert_eq!(error["code"], "validation");
+
+    let forecast = ok(dir, &["revisions", "forecast", "--days", "90", "--subject", &subject_id]);
+    assert_eq!(forecast["days"].as_array().unwrap().len(), 90);
+    assert!(forecast["totalReviews"].as_i64().unwrap() > 0);
+
+    let plan = ok(dir, &["revisions", "catch-up", "--max-per-day", "5", "--strategy", "exam-date", "--dry-run"]);
+    assert_eq!(plan["dryRun"], true);
+    assert_eq!(plan["strategy"], "exam_date");
+
+    let zone = ok(dir, &["timezone", "set", "Asia/Kolkata"]);
+    assert_eq!(zone["effective"], "Asia/Kolkata");
+    assert_eq!(ok(dir, &["timezone", "show"])["timezone"], "Asia/Kolkata");
+    let (success, error) = cli(dir, &["timezone", "set", "Atlantis/Capital"]);
+    assert!(!success);
+    assert_eq!(error["code"], "validation");
+
+    ok(dir, &["pomodoro", "log", "--minutes", "25", "--subject", &subject_id]);
+    let stats = ok(dir, &["pomodoro", "stats", "--range", "all"]);
+    assert_eq!(stats["total_minutes"], 25);
+
+    let hits = ok(dir, &["search", "bay", "--tag", "Exam"]);
+    assert_eq!(hits[0]["entry_id"].as_i64().unwrap().to_string(), entry_id);
+    assert_eq!(hits[0]["snippet"], "<mark>Bayes</mark>");
+
+    let report = ok(dir, &["report", "--from", "2024-01-01"]);
+    assert_eq!(report["subjects"][0]["name"], "Statistics");
+    assert!(!report["activity"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn errors_are_reported_as_json() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let (success, error) = cli(dir.path(), &["entries", "show", "42"]);
+    assert!(!success);
+    assert_eq!(error["code"], "not_found");
+
+    let (success, error) = cli(
+        dir.path(),
+        &["entries", "add", "--subject", "42", "--notes", "x", "--intervals", "1"],
+    );
+    assert!(!success);
+    assert_eq!(error["code"], "constraint");
+}
+
+#[test]
+fn data_can_be_moved_to_an_empty_folder() {
+    let root = tempfile::tempdir().unwrap();
+    let from = root.path().join("old");
+    let to = root.path().join("new");
+    ok(&from, &["subjects", "add", "Geology"]);
+
+    let moved = ok(&from, &["data", "move", to.to_str().unwrap()]);
+    assert_eq!(moved["leftovers"].as_array().unwrap().len(), 0);
+    assert!(!from.join("recallify.db").exists());
+
+    let subjects = ok(&to, &["subjects", "list"]);
+    assert_eq!(subjects[0]["name"], "Geology");
+
+    let (success, error) = cli(&to, &["data", "move", from.to_str().unwrap()]);
+    assert!(success, "{}", error);
+}
+
+#[test]
+fn profiles_have_their_own_data() {
+    let dir = tempfile::tempdir().unwrap();
+    let dir = dir.path();
+    ok(dir, &["subjects", "add", "Default subject"]);
+
+    let profile = ok(dir, &["profiles", "create", "Certification"]);
+    let id = profile["id"].as_str().unwrap();
+    let listed = ok(dir, &["profiles", "switch", id]);
+    assert_eq!(listed["active"], id);
+    assert_eq!(listed["profiles"].as_array().unwrap().len(), 2);
+
+    assert!(ok(dir, &["subjects", "list"]).as_array().unwrap().is_empty());
+    let default = ok(dir, &["--profile", "default", "subjects", "list"]);
+    assert_eq!(default[0]["name"], "Default subject");
+
+    let (success, error) = cli(dir, &["--profile", "missing", "subjects", "list"]);
+    assert!(!success);
+    assert_eq!(error["code"], "not_found");
+}
@@ -0,0 +1,159 @@
+use crate::error::AppError;
+use crate::{row_json, search, sql_guard};
+use rusqlite::Connection;
+use serde::Deserialize;
+
+// The raw SQL bridge behind db_execute, db_select and db_transaction. Every
+// statement runs under a SchemaGuard, so schema changes, ATTACH, pragma
+// assignments and transaction control are refused whichever connection it
+// is given.
+
+// Convert JSON values to rusqlite parameters
+fn json_to_sql_params(params: &[serde_json::Value]) -> Vec<Box<dyn rusqlite::ToSql>> {
+    params
+        .iter()
+        .map(|v| -> Box<dyn rusqlite::ToSql> {
+            match v {
+                serde_json::Value::String(s) => Box::new(s.clone()),
+                serde_json::Value::Number(n) => {
+                    if let Some(i) = n.as_i64() {
+                        Box::new(i)
+                    } else if let Some(f) = n.as_f64() {
+                        Box::new(f)
+                    } else {
+                        Box::new(n.to_string())
+                    }
+                }
+                serde_json::Value::Bool(b) => Box::new(*b),
+                serde_json::Value::Null => Box::new(None::<String>),
+                _ => Box::new(v.to_string()),
+            }
+        })
+        .collect()
+}
+
+pub fn execute(conn: &Connection, sql: &str, params: &[serde_json::Value]) -> Result<serde_json::Value, AppError> {
+    let guard = sql_guard::SchemaGuard::install(conn);
+
+    let sql_params = json_to_sql_params(params);
+    let sql_params_refs: Vec<&dyn rusqlite::ToSql> = sql_params.iter().map(|b| b.as_ref()).collect();
+
+    let rows_affected = conn
+        .execute(sql, sql_params_refs.as_slice())
+        .map_err(|e| guard.explain(e))?;
+    let last_id = conn.last_insert_rowid();
+    search::index_missing(conn)?;
+
+    Ok(serde_json::json!({
+        "lastInsertId": last_id,
+        "rowsAffected": rows_affected
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchStatement {
+    pub sql: String,
+    #[serde(default)]
+    pub params: Vec<serde_json::Value>,
+}
+
+// A parameter of the form `{ "$lastInsertId": n }` stands for the rowid
+// inserted by statement `n` of the same batch.
+fn resolve_batch_params(
+    index: usize,
+    params: &[serde_json::Value],
+    insert_ids: &[i64],
+) -> Result<Vec<serde_json::Value>, AppError> {
+    params
+        .iter()
+        .map(|v| match v.get("$lastInsertId") {
+            Some(reference) => {
+                let target = reference.as_u64().ok_or_else(|| {
+                    AppError::validation("$lastInsertId must be a statement index")
+                        .context(format!("Statement {}", index))
+                })? as usize;
+                insert_ids
+                    .get(target)
+                    .map(|id| serde_json::Value::from(*id))
+                    .ok_or_else(|| {
+                        AppError::validation(format!(
+                            "$lastInsertId refers to statement {}, which has not run yet",
+                            target
+                        ))
+                        .context(format!("Statement {}", index))
+                    })
+            }
+            None => Ok(v.clone()),
+        })
+        .collect()
+}
+
+// Runs all statements atomically: either every statement is applied or, on
+// the first failure, none are. The guard is only installed between BEGIN
+// and COMMIT, since it refuses transaction control in the statements.
+pub fn transaction(conn: &Connection, statements: &[BatchStatement]) -> Result<Vec<serde_json::Value>, AppError> {
+    let tx = conn.unchecked_transaction()?;
+    let guard = sql_guard::SchemaGuard::install(&tx);
+
+    let mut insert_ids = Vec::with_capacity(statements.len());
+    let mut results = Vec::with_capacity(statements.len());
+
+    for (index, statement) in statements.iter().enumerate() {
+        let params = resolve_batch_params(index, &statement.params, &insert_ids)?;
+        let sql_params = json_to_sql_params(&params);
+        let sql_params_refs: Vec<&dyn rusqlite::ToSql> = sql_params.iter().map(|b| b.as_ref()).collect();
+
+        let rows_affected = tx
+            .execute(&statement.sql, sql_params_refs.as_slice())
+            .map_err(|e| guard.explain(e).context(format!("Statement {} failed", index)))?;
+
+        let last_id = tx.last_insert_rowid();
+        insert_ids.push(last_id);
+        results.push(serde_json::json!({
+            "lastInsertId": last_id,
+            "rowsAffected": rows_affected
+        }));
+    }
+
+    search::index_missing(&tx)?;
+    drop(guard);
+    tx.commit()?;
+
+    Ok(results)
+}
+
+// Rows come back as objects keyed by column name. With `with_columns` set the
+// result is `{ columns, rows }` instead, where `columns` lists each column's
+// declared type and the kind of value that type stands for.
+pub fn select(
+    conn: &Connection,
+    sql: &str,
+    params: &[serde_json::Value],
+    with_columns: bool,
+) -> Result<serde_json::Value, AppError> {
+    let guard = sql_guard::SchemaGuard::install(conn);
+    let mut stmt = conn.prepare(sql).map_err(|e| guard.explain(e))?;
+    sql_guard::ensure_read_only(&stmt)?;
+
+    let columns = row_json::describe_columns(&stmt);
+
+    let sql_params = json_to_sql_params(params);
+    let sql_params_refs: Vec<&dyn rusqlite::ToSql> = sql_params.iter().map(|b| b.as_ref()).collect();
+
+    let rows = stmt
+        .query_map(sql_params_refs.as_slice(), |row| {
+            let mut obj = serde_json::Map::new();
+            for (i, column) in columns.iter().enumerate() {
+                let value = row_json::value_to_json(row.get_ref(i)?);
+                obj.insert(column.name.clone(), value);
+            }
+            Ok(serde_json::Value::Object(obj))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    if with_columns {
+        Ok(serde_json::json!({ "columns": columns, "rows": rows }))
+    } else {
+        Ok(serde_json::Value::Array(rows))
+    }
+}
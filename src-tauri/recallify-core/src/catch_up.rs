@@ -0,0 +1,198 @@
+use crate::{clock, db};
+use crate::error::AppError;
+use crate::models::{CatchUpDay, CatchUpMove, CatchUpPlan, CatchUpStrategy};
+use jiff::civil::Date;
+use jiff::ToSpan;
+use rusqlite::{params, Connection};
+use std::collections::BTreeMap;
+
+// Spreading a backlog of overdue revisions over the coming days. Each one
+// gets a day from today on, in the order of the strategy, filling every day
+// up to `max_per_day` open revisions, those already due that day included.
+// Applying the plan does what queries::reschedule_revision does for each of
+// them, in one transaction: the overdue revision is marked 'rescheduled'
+// and a pending one on the new day takes its place.
+//
+// Only an entry's earliest open revision is moved: grading it replaces the
+// entry's other open revisions, so they would take up room for nothing.
+//
+// The exam date of a revision is the nearest upcoming due date of an
+// unfinished syllabus item linked to its entry, or else of any unfinished
+// item of its subject.
+
+pub const MAX_PER_DAY_LIMIT: i64 = 500;
+
+struct Overdue {
+    revision_id: i64,
+    entry_id: i64,
+    subject_id: i64,
+    subject_name: String,
+    interval_days: i64,
+    due_date: String,
+    exam_date: Option<String>,
+    // Overdue revisions of the same subject
+    subject_backlog: i64,
+}
+
+fn overdue_revisions(conn: &Connection) -> rusqlite::Result<Vec<Overdue>> {
+    let mut stmt = conn.prepare(
+        "WITH open AS (
+             SELECT id, entry_id, interval_days, due_date,
+                    ROW_NUMBER() OVER (PARTITION BY entry_id ORDER BY due_date, id) AS position
+             FROM revisions
+             WHERE status IN ('pending', 'overdue')
+         )
+         SELECT r.id, r.entry_id, e.subject_id, s.name, r.interval_days, r.due_date,
+                COALESCE(
+                    (SELECT MIN(si.due_date) FROM entry_syllabus_links l
+                     JOIN syllabus_items si ON si.id = l.syllabus_item_id
+                     WHERE l.entry_id = e.id AND si.is_completed = 0 AND si.due_date >= local_date()),
+                    (SELECT MIN(si.due_date) FROM syllabus_items si
+                     WHERE si.subject_id = e.subject_id AND si.is_completed = 0 AND si.due_date >= local_date())
+                ) AS exam_date,
+                COUNT(*) OVER (PARTITION BY e.subject_id) AS subject_backlog
+         FROM open r
+         JOIN entries e ON e.id = r.entry_id
+         JOIN subjects s ON s.id = e.subject_id
+         WHERE r.position = 1 AND r.due_date < local_date()",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(Overdue {
+            revision_id: row.get(0)?,
+            entry_id: row.get(1)?,
+            subject_id: row.get(2)?,
+            subject_name: row.get(3)?,
+            interval_days: row.get(4)?,
+            due_date: row.get(5)?,
+            exam_date: row.get(6)?,
+            subject_backlog: row.get(7)?,
+        })
+    })?;
+    rows.collect()
+}
+
+fn prioritize(overdue: &mut [Overdue], strategy: CatchUpStrategy) {
+    match strategy {
+        CatchUpStrategy::Interval => {
+            overdue.sort_by(|a, b| {
+                (a.interval_days, &a.due_date, a.revision_id).cmp(&(b.interval_days, &b.due_date, b.revision_id))
+            });
+        }
+        CatchUpStrategy::Subject => {
+            overdue.sort_by(|a, b| {
+                b.subject_backlog
+                    .cmp(&a.subject_backlog)
+                    .then_with(|| (&a.subject_name, a.subject_id).cmp(&(&b.subject_name, b.subject_id)))
+                    .then_with(|| (&a.due_date, a.revision_id).cmp(&(&b.due_date, b.revision_id)))
+            });
+        }
+        CatchUpStrategy::ExamDate => {
+            // Revisions without an exam date go last
+            overdue.sort_by(|a, b| {
+                (a.exam_date.is_none(), &a.exam_date, a.interval_days, &a.due_date, a.revision_id).cmp(&(
+                    b.exam_date.is_none(),
+                    &b.exam_date,
+                    b.interval_days,
+                    &b.due_date,
+                    b.revision_id,
+                ))
+            });
+        }
+    }
+}
+
+// Open revisions already due on each day from today on
+fn upcoming_loads(conn: &Connection) -> rusqlite::Result<BTreeMap<String, i64>> {
+    let mut stmt = conn.prepare(
+        "SELECT due_date, COUNT(*) FROM revisions
+         WHERE status IN ('pending', 'overdue') AND due_date >= local_date()
+         GROUP BY due_date",
+    )?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+// Plans moving every overdue revision to a day from today on, at most
+// `max_per_day` open revisions a day, and applies it unless `dry_run`.
+pub fn plan_catch_up(
+    conn: &Connection,
+    max_per_day: i64,
+    strategy: CatchUpStrategy,
+    dry_run: bool,
+) -> Result<CatchUpPlan, AppError> {
+    if !(1..=MAX_PER_DAY_LIMIT).contains(&max_per_day) {
+        return Err(AppError::validation(format!(
+            "Revisions per day must be between 1 and {}",
+            MAX_PER_DAY_LIMIT
+        )));
+    }
+    // Applying reads the backlog with the write lock held, so nothing graded
+    // or moved from elsewhere in the meantime is moved again
+    let tx = if dry_run {
+        conn.unchecked_transaction()?
+    } else {
+        db::write_transaction(conn)?
+    };
+    let mut overdue = overdue_revisions(&tx)?;
+    prioritize(&mut overdue, strategy);
+
+    let mut loads = upcoming_loads(&tx)?;
+    let mut moved: BTreeMap<String, i64> = BTreeMap::new();
+    let mut day: Date = clock::today_date(&tx)?;
+    let mut moves = Vec::with_capacity(overdue.len());
+    for revision in overdue {
+        // Days already over the limit are skipped; those days are finite
+        while loads.get(&day.to_string()).is_some_and(|load| *load >= max_per_day) {
+            day = day.checked_add(1.day()).map_err(|e| AppError::internal(e.to_string()))?;
+        }
+        let date = day.to_string();
+        *loads.entry(date.clone()).or_default() += 1;
+        *moved.entry(date.clone()).or_default() += 1;
+        moves.push(CatchUpMove {
+            revision_id: revision.revision_id,
+            new_revision_id: None,
+            entry_id: revision.entry_id,
+            subject_id: revision.subject_id,
+            subject_name: revision.subject_name,
+            interval_days: revision.interval_days,
+            from_date: revision.due_date,
+            to_date: date,
+            exam_date: revision.exam_date,
+        });
+    }
+
+    if !dry_run {
+        for planned in &mut moves {
+            let changed = tx.execute(
+                "UPDATE revisions SET status = 'rescheduled', updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?1 AND status IN ('pending', 'overdue')",
+                [planned.revision_id],
+            )?;
+            if changed == 0 {
+                continue;
+            }
+            tx.execute(
+                "INSERT INTO revisions (entry_id, interval_days, due_date, status) VALUES (?1, ?2, ?3, 'pending')",
+                params![planned.entry_id, planned.interval_days, planned.to_date],
+            )?;
+            planned.new_revision_id = Some(tx.last_insert_rowid());
+        }
+    }
+    tx.commit()?;
+
+    let days = moved
+        .into_iter()
+        .map(|(date, moved)| CatchUpDay {
+            total: loads.get(&date).copied().unwrap_or(moved),
+            date,
+            moved,
+        })
+        .collect();
+    Ok(CatchUpPlan {
+        strategy,
+        max_per_day,
+        dry_run,
+        moves,
+        days,
+    })
+}
@@ -0,0 +1,156 @@
+use crate::error::AppError;
+use crate::queries;
+use jiff::civil::{Date, DateTime};
+use jiff::tz::TimeZone;
+use jiff::Timestamp;
+use rusqlite::functions::FunctionFlags;
+use rusqlite::types::Type;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::time::Duration;
+
+// Dates and day boundaries in the user's time zone.
+//
+// Instants (completed_at, created_at, ...) are stored in UTC through
+// CURRENT_TIMESTAMP. Date columns (due_date, activity_date, reviewed_date,
+// ...) and every "today" are in the IANA time zone of the `timezone`
+// setting, or the system's when it is empty. SQL gets them through the
+// functions from `register_functions`, never through SQLite's 'localtime',
+// which always follows the system:
+//
+//   local_date()           today
+//   local_date(timestamp)  the date of a UTC timestamp, or a date as is
+//
+// Each connection captures the zone of its own database when the functions
+// are registered, so databases open side by side keep their own "today".
+// A connection only sees a changed setting once they are registered again,
+// which set_timezone does for the connection it saves through.
+
+pub const TIMEZONE_SETTING: &str = "timezone";
+
+pub fn system_timezone() -> TimeZone {
+    TimeZone::system()
+}
+
+// Anything jiff does not know is reported rather than silently read as UTC
+fn parse_timezone(name: &str) -> Result<TimeZone, AppError> {
+    TimeZone::get(name).map_err(|_| AppError::validation(format!("Unknown time zone \"{}\"", name)))
+}
+
+// The zone named by the setting, None when following the system. Before
+// migrations have created the settings there is no setting to read.
+fn configured(conn: &Connection) -> rusqlite::Result<Option<Result<TimeZone, AppError>>> {
+    let has_settings: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'settings')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_settings {
+        return Ok(None);
+    }
+    let setting = queries::get_setting(conn, TIMEZONE_SETTING)?;
+    let name = setting.as_deref().map(str::trim).filter(|name| !name.is_empty());
+    Ok(name.map(parse_timezone))
+}
+
+// The zone of the database's setting. An unknown zone falls back to the
+// system's rather than failing.
+pub fn timezone(conn: &Connection) -> rusqlite::Result<TimeZone> {
+    Ok(configured(conn)?.and_then(Result::ok).unwrap_or_else(system_timezone))
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimezoneInfo {
+    // The setting; None when following the system
+    pub timezone: Option<String>,
+    // IANA name of the zone in effect, when it has one
+    pub effective: Option<String>,
+    pub system: Option<String>,
+    pub today: String,
+}
+
+pub fn info(conn: &Connection) -> rusqlite::Result<TimezoneInfo> {
+    let setting = queries::get_setting(conn, TIMEZONE_SETTING)?;
+    Ok(TimezoneInfo {
+        timezone: setting.filter(|name| !name.trim().is_empty()),
+        effective: timezone(conn)?.iana_name().map(str::to_string),
+        system: system_timezone().iana_name().map(str::to_string),
+        today: today(conn)?,
+    })
+}
+
+// Saves the IANA zone `name`, or follows the system again for None or an
+// empty name. Other connections to the database keep the zone they were
+// registered with until register_functions runs on them again.
+pub fn set_timezone(conn: &Connection, name: Option<&str>) -> Result<TimezoneInfo, AppError> {
+    let name = name.map(str::trim).filter(|name| !name.is_empty());
+    if let Some(name) = name {
+        parse_timezone(name)?;
+    }
+    queries::update_setting(conn, TIMEZONE_SETTING, name.unwrap_or(""))?;
+    register_functions(conn)?;
+    Ok(info(conn)?)
+}
+
+// Today in the connection's zone, YYYY-MM-DD
+pub fn today(conn: &Connection) -> rusqlite::Result<String> {
+    conn.query_row("SELECT local_date()", [], |row| row.get(0))
+}
+
+pub fn today_date(conn: &Connection) -> rusqlite::Result<Date> {
+    today(conn)?
+        .parse()
+        .map_err(|e: jiff::Error| rusqlite::Error::FromSqlConversionFailure(0, Type::Text, Box::new(e)))
+}
+
+// How long until the next midnight in `timezone`
+pub fn until_midnight(timezone: &TimeZone) -> Duration {
+    let now = Timestamp::now().to_zoned(timezone.clone());
+    let midnight = now
+        .date()
+        .tomorrow()
+        .and_then(|tomorrow| tomorrow.to_zoned(now.time_zone().clone()));
+    match midnight {
+        Ok(midnight) => midnight.timestamp().duration_since(now.timestamp()).unsigned_abs(),
+        Err(_) => Duration::from_secs(60 * 60),
+    }
+}
+
+// The local date of a UTC timestamp as SQLite writes them
+// ("YYYY-MM-DD HH:MM:SS", optionally with a 'T', fractional seconds or an
+// offset) in `timezone`. Plain dates are already local and returned as
+// they are.
+pub fn local_date(value: &str, timezone: &TimeZone) -> Option<String> {
+    let value = value.trim();
+    if let Ok(timestamp) = value.parse::<Timestamp>() {
+        return Some(timestamp.to_zoned(timezone.clone()).date().to_string());
+    }
+    if let Ok(datetime) = value.replacen(' ', "T", 1).parse::<DateTime>() {
+        let utc = datetime.to_zoned(TimeZone::UTC).ok()?;
+        return Some(utc.with_time_zone(timezone.clone()).date().to_string());
+    }
+    value.parse::<Date>().ok().map(|date| date.to_string())
+}
+
+// Every connection gets these, see db::open_connection, with the zone of
+// its database's setting; db::open registers them again once migrations
+// have created the settings. Not deterministic: the answer changes with
+// the clock.
+pub fn register_functions(conn: &Connection) -> rusqlite::Result<()> {
+    let timezone = match configured(conn)? {
+        Some(Ok(timezone)) => timezone,
+        Some(Err(e)) => {
+            eprintln!("{}; using the system time zone", e.message);
+            system_timezone()
+        }
+        None => system_timezone(),
+    };
+    let zone = timezone.clone();
+    conn.create_scalar_function("local_date", 0, FunctionFlags::SQLITE_UTF8, move |_| {
+        Ok(Timestamp::now().to_zoned(zone.clone()).date().to_string())
+    })?;
+    conn.create_scalar_function("local_date", 1, FunctionFlags::SQLITE_UTF8, move |ctx| {
+        Ok(ctx.get::<Option<String>>(0)?.and_then(|value| local_date(&value, &timezone)))
+    })
+}
@@ -0,0 +1,313 @@
+use crate::db;
+use crate::error::{AppError, ResultExt};
+use crate::files;
+use crate::profiles;
+use crate::queries;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Where the data directory lives. In order of precedence:
+//
+//   portable   a `recallify.portable` file next to the executable keeps all
+//              data in `RecallifyData/` beside it, e.g. on a USB stick
+//   custom     a location chosen by the user, remembered in location.json
+//              in the platform directory
+//   default    the platform directory (data_local_dir on Windows, the
+//              ProjectDirs data dir elsewhere)
+
+pub const PORTABLE_MARKER: &str = "recallify.portable";
+const PORTABLE_DIR: &str = "RecallifyData";
+const LOCATION_FILE: &str = "location.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataDirSource {
+    Portable,
+    Custom,
+    Default,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataLocation {
+    pub path: PathBuf,
+    pub source: DataDirSource,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LocationConfig {
+    data_dir: PathBuf,
+}
+
+// The per-user platform directory. Also where location.json is kept, so it
+// is found again whatever the data directory is.
+pub fn platform_data_dir() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        use directories::BaseDirs;
+        if let Some(base_dirs) = BaseDirs::new() {
+            return base_dirs.data_local_dir().join("Recallify");
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        use directories::ProjectDirs;
+        if let Some(proj_dirs) = ProjectDirs::from("com", "Recallify", "Recallify") {
+            return proj_dirs.data_dir().to_path_buf();
+        }
+    }
+
+    // Fallback to current directory if all else fails
+    PathBuf::from(".")
+}
+
+fn portable_dir() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let exe_dir = exe.parent()?;
+    exe_dir.join(PORTABLE_MARKER).is_file().then(|| exe_dir.join(PORTABLE_DIR))
+}
+
+fn custom_dir() -> Option<PathBuf> {
+    let path = platform_data_dir().join(LOCATION_FILE);
+    let text = fs::read_to_string(&path).ok()?;
+    match serde_json::from_str::<LocationConfig>(&text) {
+        Ok(config) => Some(config.data_dir),
+        Err(e) => {
+            eprintln!("Ignoring {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+// Resolves the data directory and creates it if missing. A custom location
+// that cannot be created (an unplugged drive, say) is still returned, so
+// opening the database fails visibly instead of silently starting empty in
+// another directory.
+pub fn locate() -> DataLocation {
+    let location = if let Some(path) = portable_dir() {
+        DataLocation { path, source: DataDirSource::Portable }
+    } else if let Some(path) = custom_dir() {
+        DataLocation { path, source: DataDirSource::Custom }
+    } else {
+        DataLocation { path: platform_data_dir(), source: DataDirSource::Default }
+    };
+
+    if let Err(e) = fs::create_dir_all(&location.path) {
+        eprintln!("Failed to create data directory {}: {}", location.path.display(), e);
+    }
+    location
+}
+
+pub fn app_data_dir() -> PathBuf {
+    locate().path
+}
+
+// Remembers `path` as the data directory, or goes back to the platform
+// directory for None. Has no effect in portable mode, where the marker wins.
+pub fn set_custom_dir(path: Option<&Path>) -> Result<(), AppError> {
+    let platform_dir = platform_data_dir();
+    let config_path = platform_dir.join(LOCATION_FILE);
+
+    match path {
+        Some(path) if path != platform_dir => {
+            if !path.is_absolute() {
+                return Err(AppError::validation("Data directory must be an absolute path"));
+            }
+            fs::create_dir_all(&platform_dir).context("Failed to create app data directory")?;
+            let config = LocationConfig { data_dir: path.to_path_buf() };
+            let text = serde_json::to_string_pretty(&config).map_err(|e| AppError::internal(e.to_string()))?;
+            fs::write(&config_path, text).context(format!("Failed to write {}", config_path.display()))
+        }
+        _ => match fs::remove_file(&config_path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(AppError::from(e).context(format!("Failed to remove {}", config_path.display())))
+            }
+            _ => Ok(()),
+        },
+    }
+}
+
+pub fn check_movable(location: &DataLocation) -> Result<(), AppError> {
+    if location.source == DataDirSource::Portable {
+        return Err(AppError::validation(format!(
+            "Portable mode keeps data next to the executable; remove {} to choose another location",
+            PORTABLE_MARKER
+        )));
+    }
+    Ok(())
+}
+
+// Checks that the data in `from` can be moved to `to`: the target must be
+// empty (or not exist yet) and must not be inside the current directory,
+// which is cleaned up once the move is done.
+pub fn check_move_target(from: &Path, to: &Path) -> Result<(), AppError> {
+    if !to.is_absolute() {
+        return Err(AppError::validation("Data directory must be an absolute path"));
+    }
+    let from = fs::canonicalize(from).unwrap_or_else(|_| from.to_path_buf());
+    let resolved = to
+        .parent()
+        .and_then(|parent| fs::canonicalize(parent).ok())
+        .and_then(|parent| to.file_name().map(|name| parent.join(name)))
+        .unwrap_or_else(|| to.to_path_buf());
+    if resolved.starts_with(&from) {
+        return Err(AppError::validation("Choose a folder outside the current data directory"));
+    }
+
+    match fs::read_dir(to) {
+        Ok(mut entries) => {
+            if entries.next().is_some() {
+                return Err(AppError::validation(format!("{} is not empty", to.display())));
+            }
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(AppError::from(e).context(to.display().to_string())),
+    }
+}
+
+fn sidecar(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn db_files(dir: &Path) -> [PathBuf; 3] {
+    let db_path = db::db_path(dir);
+    [sidecar(&db_path, "-wal"), sidecar(&db_path, "-shm"), db_path]
+}
+
+// Everything Recallify keeps in a data directory: the default profile's
+// database and folders, plus the other profiles (see profiles).
+fn managed_entries(dir: &Path) -> Vec<PathBuf> {
+    let mut entries = db_files(dir).to_vec();
+    entries.extend([
+        files::pdfs_dir(dir),
+        files::backups_dir(dir),
+        profiles::profiles_root(dir),
+        dir.join(profiles::PROFILES_FILE),
+    ]);
+    entries
+}
+
+fn copy_entry(from: &Path, to: &Path, skip: &[PathBuf]) -> std::io::Result<()> {
+    if skip.iter().any(|s| s == from) {
+        return Ok(());
+    }
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_entry(&entry.path(), &to.join(entry.file_name()), skip)?;
+        }
+    } else if from.exists() {
+        fs::copy(from, to)?;
+    }
+    Ok(())
+}
+
+// Attachment and backup paths are stored absolute; repoint the ones that
+// were inside `from` to the same file under `to`.
+pub fn relocate_paths(conn: &Connection, from: &Path, to: &Path) -> rusqlite::Result<usize> {
+    let from = format!("{}{}", from.display(), std::path::MAIN_SEPARATOR);
+    let to = format!("{}{}", to.display(), std::path::MAIN_SEPARATOR);
+    let mut changed = 0;
+    for (table, column) in [
+        ("pdf_attachments", "file_path"),
+        ("backup_runs", "file_path"),
+        ("backup_runs", "pdfs_path"),
+    ] {
+        changed += conn.execute(
+            &format!(
+                "UPDATE {table} SET {column} = ?2 || substr({column}, length(?1) + 1)
+                 WHERE substr({column}, 1, length(?1)) = ?1"
+            ),
+            [&from, &to],
+        )?;
+    }
+    Ok(changed)
+}
+
+// The directory a database was last opened from, kept so it can tell when
+// it has been moved.
+const LOCATION_SETTING: &str = "data_location";
+
+// Repoints stored paths when the database in `dir` was last opened from
+// somewhere else: after a move, from a synced folder on another computer,
+// or as a restored backup. Called whenever a database is opened.
+pub fn sync_paths(conn: &Connection, dir: &Path) -> rusqlite::Result<usize> {
+    let current = dir.display().to_string();
+    let recorded = queries::get_setting(conn, LOCATION_SETTING)?;
+    if recorded.as_deref() == Some(current.as_str()) {
+        return Ok(0);
+    }
+    let changed = match &recorded {
+        Some(recorded) => relocate_paths(conn, Path::new(recorded), dir)?,
+        None => 0,
+    };
+    queries::update_setting(conn, LOCATION_SETTING, &current)?;
+    Ok(changed)
+}
+
+// Copies everything in the data directory `from` into `to`. The database
+// behind `conn`, which belongs to the profile in `active`, goes through the
+// backup API; everything else is copied as it is, which is safe because
+// only one profile's database is open at a time. The copied database gets
+// its paths repointed and is checked. The original is left untouched; on
+// failure whatever was copied is removed.
+pub fn copy_data(conn: &Connection, key: Option<&str>, active: &Path, from: &Path, to: &Path) -> Result<(), AppError> {
+    check_move_target(from, to)?;
+    let copied = copy_data_unchecked(conn, key, active, from, to);
+    if copied.is_err() {
+        remove_data(to);
+    }
+    copied
+}
+
+fn copy_data_unchecked(conn: &Connection, key: Option<&str>, active: &Path, from: &Path, to: &Path) -> Result<(), AppError> {
+    let relative = active
+        .strip_prefix(from)
+        .map_err(|_| AppError::internal(format!("{} is not inside {}", active.display(), from.display())))?;
+    let target_active = to.join(relative);
+    fs::create_dir_all(&target_active).context(format!("Failed to create {}", target_active.display()))?;
+
+    let skip = db_files(active);
+    for entry in managed_entries(from) {
+        let target = to.join(entry.strip_prefix(from).unwrap_or(&entry));
+        copy_entry(&entry, &target, &skip).context(format!("Failed to copy {}", entry.display()))?;
+    }
+
+    let target_db = db::db_path(&target_active);
+    db::copy_database(conn, &target_db, key)?;
+    let copy = db::open_connection(&target_db, key).context("Failed to open copied database")?;
+    sync_paths(&copy, &target_active).context("Failed to update file paths")?;
+    let problems = db::integrity_problems(&copy, false)?;
+    if !problems.is_empty() {
+        return Err(AppError::validation(problems.join("; ")).context("Copied database is damaged"));
+    }
+    Ok(())
+}
+
+// Removes everything Recallify keeps in `dir` (every profile's database,
+// pdfs and backups), leaving anything else (location.json, unrelated
+// files) in place. Returns the paths that could not be removed.
+pub fn remove_data(dir: &Path) -> Vec<String> {
+    let mut leftovers = Vec::new();
+    for path in managed_entries(dir) {
+        let removed = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        if let Err(e) = removed {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                leftovers.push(format!("{}: {}", path.display(), e));
+            }
+        }
+    }
+    leftovers
+}
@@ -0,0 +1,181 @@
+use crate::clock;
+use crate::data_dir;
+use crate::error::{AppError, ResultExt};
+use crate::migrations;
+use crate::search;
+use rusqlite::backup::Backup;
+use rusqlite::{Connection, OpenFlags, Transaction, TransactionBehavior};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// Opening and keying connections. Nothing here keeps state: callers own
+// their connections, so the app can hold a writer plus a read pool while
+// tests and scripts just open one connection.
+
+// How long a connection waits on a lock held by another connection before
+// giving up with SQLITE_BUSY.
+pub const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Pacing for the online backup API, so a copy does not hold the source
+// connection for long stretches while the app keeps writing.
+const PAGES_PER_STEP: i32 = 256;
+const STEP_PAUSE: Duration = Duration::from_millis(10);
+
+pub fn db_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("recallify.db")
+}
+
+// Every connection must go through here so foreign keys are enforced and
+// the SQL functions of the search triggers and local dates are registered.
+// The foreign key setting is per connection and its default depends on how
+// SQLite was built, so it is set explicitly rather than relying on the
+// bundled build.
+pub fn open_connection(path: &Path, key: Option<&str>) -> rusqlite::Result<Connection> {
+    let conn = Connection::open(path)?;
+    apply_key(&conn, key)?;
+    conn.pragma_update(None, "foreign_keys", "ON")?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    search::register_functions(&conn)?;
+    clock::register_functions(&conn)?;
+    Ok(conn)
+}
+
+pub fn open_read_connection(path: &Path, key: Option<&str>) -> rusqlite::Result<Connection> {
+    let conn = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX | OpenFlags::SQLITE_OPEN_URI,
+    )?;
+    apply_key(&conn, key)?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    search::register_functions(&conn)?;
+    clock::register_functions(&conn)?;
+    Ok(conn)
+}
+
+// Opens the database in `data_dir` ready for use: keyed, in WAL mode and
+// migrated to the latest schema. Creates `data_dir` if needed.
+pub fn open(data_dir: &Path, key: Option<&str>) -> Result<Connection, AppError> {
+    fs::create_dir_all(data_dir).context("Failed to create data directory")?;
+    let conn = open_connection(&db_path(data_dir), key).map_err(|e| match key {
+        Some(_) => key_error(e),
+        None => AppError::from(e).context("Failed to open database"),
+    })?;
+    enable_wal(&conn).context("Failed to enable WAL mode")?;
+    migrations::run(&conn).context("Failed to initialize database")?;
+    // Again, now that there are settings to take the time zone from
+    clock::register_functions(&conn).context("Failed to read the time zone")?;
+    data_dir::sync_paths(&conn, data_dir).context("Failed to update file paths")?;
+    search::index_missing(&conn).context("Failed to update the search index")?;
+    Ok(conn)
+}
+
+// For writes that depend on what they read first: the write lock is taken
+// up front, so no other connection can change those rows in between. A
+// deferred transaction would only notice at its first write.
+pub fn write_transaction(conn: &Connection) -> rusqlite::Result<Transaction<'_>> {
+    Transaction::new_unchecked(conn, TransactionBehavior::Immediate)
+}
+
+// The key has to be the first thing run on a SQLCipher connection. A wrong
+// key only shows up once a page is read, so read the schema to check it.
+pub fn apply_key(conn: &Connection, key: Option<&str>) -> rusqlite::Result<()> {
+    if let Some(key) = key {
+        conn.pragma_update(None, "key", key)?;
+    }
+    conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| row.get::<_, i64>(0))?;
+    Ok(())
+}
+
+// WAL lets readers work alongside the single writer. The mode is stored in
+// the database file, so this only has to succeed once.
+pub fn enable_wal(conn: &Connection) -> rusqlite::Result<()> {
+    let mode: String = conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get(0))?;
+    if !mode.eq_ignore_ascii_case("wal") {
+        eprintln!("Database journal mode is {}, expected WAL", mode);
+    }
+    Ok(())
+}
+
+// A plaintext SQLite file starts with this header; a SQLCipher file starts
+// with random salt instead. Missing or empty files count as plaintext.
+pub fn is_encrypted(path: &Path) -> std::io::Result<bool> {
+    let mut header = [0u8; 16];
+    match fs::File::open(path) {
+        Ok(mut file) => match file.read_exact(&mut header) {
+            Ok(()) => Ok(&header != b"SQLite format 3\0"),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+            Err(e) => Err(e),
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+pub fn key_error(e: rusqlite::Error) -> AppError {
+    match e.sqlite_error_code() {
+        Some(rusqlite::ErrorCode::NotADatabase) => AppError::validation("Incorrect passphrase"),
+        _ => e.into(),
+    }
+}
+
+// Empty when the database is healthy. `full` runs integrity_check, which
+// also cross-checks indexes against their tables; quick_check is O(N) and
+// fast enough to run on every open.
+pub fn integrity_problems(conn: &Connection, full: bool) -> rusqlite::Result<Vec<String>> {
+    let pragma = if full { "integrity_check" } else { "quick_check" };
+    let mut stmt = conn.prepare(&format!("PRAGMA {}", pragma))?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let messages = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(messages.into_iter().filter(|m| m != "ok").collect())
+}
+
+// Copies the whole database into a new file encrypted with `key` (or
+// plaintext for an empty key). SQLCipher cannot rekey between plaintext
+// and encrypted in place, so enabling and removing encryption go through
+// an export followed by a file swap.
+pub fn export(conn: &Connection, target: &Path, key: &str) -> rusqlite::Result<()> {
+    let version = migrations::current_version(conn)?;
+    conn.execute(
+        "ATTACH DATABASE ?1 AS rekeyed KEY ?2",
+        rusqlite::params![target.to_string_lossy(), key],
+    )?;
+    let exported = conn
+        .query_row("SELECT sqlcipher_export('rekeyed')", [], |_| Ok(()))
+        // user_version is not part of the export
+        .and_then(|()| conn.execute_batch(&format!("PRAGMA rekeyed.user_version = {}", version)));
+    conn.execute_batch("DETACH DATABASE rekeyed")?;
+    exported
+}
+
+fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".partial");
+    PathBuf::from(name)
+}
+
+fn write_copy(source: &Connection, target: &Path, key: Option<&str>) -> rusqlite::Result<()> {
+    let mut target = Connection::open(target)?;
+    apply_key(&target, key)?;
+    let backup = Backup::new(source, &mut target)?;
+    backup.run_to_completion(PAGES_PER_STEP, STEP_PAUSE, None)
+}
+
+// Writes a complete copy of `source` to `dest` with SQLite's online backup
+// API, so the copy is consistent even while the source keeps being written
+// and includes pages still in its WAL. Goes through a temporary file so an
+// interrupted copy never leaves a truncated database behind.
+pub fn copy_database(source: &Connection, dest: &Path, key: Option<&str>) -> Result<(), AppError> {
+    let partial = partial_path(dest);
+    if partial.exists() {
+        fs::remove_file(&partial)?;
+    }
+
+    if let Err(e) = write_copy(source, &partial, key) {
+        let _ = fs::remove_file(&partial);
+        return Err(AppError::from(e).context("Failed to copy database"));
+    }
+
+    fs::rename(&partial, dest).context(format!("Failed to write {}", dest.display()))
+}
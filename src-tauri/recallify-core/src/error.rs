@@ -0,0 +1,149 @@
+use crate::migrations::MigrationError;
+use rusqlite::ErrorCode as SqliteCode;
+use serde::Serialize;
+use std::fmt;
+
+// Error type returned by every Tauri command. It reaches the frontend as
+// `{ code, message, context }` so the UI can react to the kind of failure
+// (a duplicate name, a locked database, a missing file) instead of parsing
+// message text.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    // The requested row or file does not exist
+    NotFound,
+    // A UNIQUE, FOREIGN KEY, CHECK or NOT NULL constraint was violated
+    Constraint,
+    // Another connection held a lock for longer than the busy timeout
+    Busy,
+    // The database is encrypted and not unlocked yet, or failed to open
+    Locked,
+    // Reading or writing a file failed
+    Io,
+    // The request itself was rejected (bad input, refused SQL, wrong passphrase)
+    Validation,
+    // Any other SQLite or migration failure
+    Database,
+    // A bug or poisoned lock in the backend
+    Internal,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppError {
+    pub code: ErrorCode,
+    pub message: String,
+    // What was being done when the error happened, e.g. a file path or
+    // "Statement 2" of a batch
+    pub context: Option<String>,
+}
+
+impl AppError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        AppError {
+            code,
+            message: message.into(),
+            context: None,
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn locked(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Locked, message)
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Io, message)
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Validation, message)
+    }
+
+    pub fn database(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Database, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    // Prefixes any existing context, so the outermost caller reads first.
+    pub fn context(mut self, context: impl Into<String>) -> Self {
+        let context = context.into();
+        self.context = Some(match self.context.take() {
+            Some(inner) => format!("{}: {}", context, inner),
+            None => context,
+        });
+        self
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.context {
+            Some(context) => write!(f, "{}: {}", context, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+// Adds context to the error of any result whose error converts to AppError.
+pub trait ResultExt<T> {
+    fn context(self, context: impl Into<String>) -> Result<T, AppError>;
+}
+
+impl<T, E: Into<AppError>> ResultExt<T> for Result<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T, AppError> {
+        self.map_err(|e| e.into().context(context))
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(e: rusqlite::Error) -> Self {
+        let code = match &e {
+            rusqlite::Error::QueryReturnedNoRows => ErrorCode::NotFound,
+            _ => match e.sqlite_error_code() {
+                Some(SqliteCode::ConstraintViolation) => ErrorCode::Constraint,
+                Some(SqliteCode::DatabaseBusy | SqliteCode::DatabaseLocked) => ErrorCode::Busy,
+                Some(
+                    SqliteCode::CannotOpen
+                    | SqliteCode::SystemIoFailure
+                    | SqliteCode::DiskFull
+                    | SqliteCode::ReadOnly
+                    | SqliteCode::PermissionDenied,
+                ) => ErrorCode::Io,
+                _ => ErrorCode::Database,
+            },
+        };
+        AppError::new(code, e.to_string())
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        let code = match e.kind() {
+            std::io::ErrorKind::NotFound => ErrorCode::NotFound,
+            _ => ErrorCode::Io,
+        };
+        AppError::new(code, e.to_string())
+    }
+}
+
+impl From<MigrationError> for AppError {
+    fn from(e: MigrationError) -> Self {
+        AppError::database(e.to_string())
+    }
+}
+
+impl<T> From<std::sync::PoisonError<T>> for AppError {
+    fn from(e: std::sync::PoisonError<T>) -> Self {
+        AppError::internal(e.to_string())
+    }
+}
@@ -0,0 +1,81 @@
+use crate::error::{AppError, ResultExt};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Layout of the data directory (see data_dir for where it lives) and the
+// PDF attachments stored in it:
+//
+//   recallify.db   the database (see db::db_path)
+//   pdfs/          attachment files, referenced by pdf_attachments.file_path
+//   backups/       automatic and manual backups
+
+pub fn pdfs_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("pdfs")
+}
+
+pub fn backups_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("backups")
+}
+
+// Resolves `file_path` to a file directly inside the pdfs directory. Paths
+// come from the webview, so anything else, including links and `..` that
+// lead out of it, is refused.
+fn stored_pdf(data_dir: &Path, file_path: &Path) -> Result<PathBuf, AppError> {
+    let resolved = fs::canonicalize(file_path)?;
+    let pdfs_dir = fs::canonicalize(pdfs_dir(data_dir))?;
+    if resolved.parent() != Some(pdfs_dir.as_path()) {
+        return Err(AppError::validation("Not a stored PDF attachment"));
+    }
+    Ok(resolved)
+}
+
+pub fn read_pdf(data_dir: &Path, file_path: &Path) -> Result<Vec<u8>, AppError> {
+    stored_pdf(data_dir, file_path)
+        .and_then(|path| Ok(fs::read(path)?))
+        .context(format!("Failed to read PDF file {}", file_path.display()))
+}
+
+// Writes `file_data` into the pdfs directory and returns the absolute path
+// to store in pdf_attachments. An existing file with the same name is
+// overwritten, so callers make names unique.
+pub fn save_pdf(data_dir: &Path, file_name: &str, file_data: &[u8]) -> Result<PathBuf, AppError> {
+    // Only a bare file name is accepted; anything else could write outside pdfs/
+    if file_name.is_empty() || Path::new(file_name).file_name() != Some(file_name.as_ref()) {
+        return Err(AppError::validation(format!("Invalid PDF file name: {}", file_name)));
+    }
+
+    let pdfs_dir = pdfs_dir(data_dir);
+    fs::create_dir_all(&pdfs_dir).context("Failed to create pdfs directory")?;
+
+    let file_path = pdfs_dir.join(file_name);
+    fs::write(&file_path, file_data).context(format!("Failed to write PDF file {}", file_path.display()))?;
+    Ok(file_path)
+}
+
+pub fn delete_pdf(data_dir: &Path, file_path: &Path) -> Result<(), AppError> {
+    stored_pdf(data_dir, file_path)
+        .and_then(|path| Ok(fs::remove_file(path)?))
+        .context(format!("Failed to delete PDF file {}", file_path.display()))
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedPdf {
+    pub file_path: String,
+    pub file_size: i64,
+}
+
+// Copies a PDF the user picked into the pdfs directory as `file_name` (see
+// save_pdf). Only files that start like a PDF are taken.
+pub fn import_pdf(data_dir: &Path, source: &Path, file_name: &str) -> Result<ImportedPdf, AppError> {
+    let file_data = fs::read(source).context(format!("Failed to read PDF file {}", source.display()))?;
+    if !file_data.starts_with(b"%PDF-") {
+        return Err(AppError::validation(format!("{} is not a PDF file", source.display())));
+    }
+    let file_path = save_pdf(data_dir, file_name, &file_data)?;
+    Ok(ImportedPdf {
+        file_path: file_path.to_string_lossy().into_owned(),
+        file_size: file_data.len() as i64,
+    })
+}
@@ -0,0 +1,196 @@
+use crate::error::AppError;
+use crate::fsrs::{self, MemoryState};
+use crate::models::{Entry, ForecastDay, ForecastLoad, ForecastSubject, ReviewForecast};
+use crate::scheduler::{self, Algorithm, Sm2State};
+use jiff::civil::Date;
+use jiff::ToSpan;
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+
+// Review workload ahead. Every entry with an open revision is reviewed on
+// its earliest one (today when overdue); from then on the selected
+// scheduler sets the next review as if each were graded
+// PROJECTED_QUALITY. Grading supersedes the rest of a fixed plan, so an
+// entry's later open revisions are not counted.
+
+pub const MAX_FORECAST_DAYS: u32 = 365;
+// Time a review is assumed to take until some have been timed
+pub const DEFAULT_REVIEW_MINUTES: f64 = 5.0;
+const PROJECTED_QUALITY: u8 = 4;
+
+// Day numbers count from 1970-01-01, like scheduler::today_number
+fn date_of(day: i64) -> String {
+    Date::constant(1970, 1, 1)
+        .checked_add(day.days())
+        .map(|date| date.to_string())
+        .unwrap_or_default()
+}
+
+// Each subject's average review time in minutes, and the overall one
+fn review_minutes(conn: &Connection) -> rusqlite::Result<(BTreeMap<i64, f64>, f64)> {
+    let mut stmt = conn.prepare(
+        "SELECT e.subject_id, AVG(l.duration_seconds) / 60.0
+         FROM review_log l JOIN entries e ON e.id = l.entry_id
+         WHERE l.duration_seconds IS NOT NULL
+         GROUP BY e.subject_id",
+    )?;
+    let by_subject = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<BTreeMap<i64, f64>>>()?;
+    let overall: Option<f64> = conn.query_row(
+        "SELECT AVG(duration_seconds) / 60.0 FROM review_log WHERE duration_seconds IS NOT NULL",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok((by_subject, overall.unwrap_or(DEFAULT_REVIEW_MINUTES)))
+}
+
+struct Pending {
+    entry: Entry,
+    subject_name: String,
+    due_day: i64,
+    study_day: Option<i64>,
+    last_review_day: Option<i64>,
+}
+
+fn pending_entries(conn: &Connection, subject_id: Option<i64>) -> rusqlite::Result<Vec<Pending>> {
+    let mut stmt = conn.prepare(
+        "SELECT e.*, s.name AS subject_name,
+                STRFTIME('%s', MIN(r.due_date)) / 86400 AS due_day,
+                STRFTIME('%s', e.study_date) / 86400 AS study_day,
+                STRFTIME('%s', e.last_review_date) / 86400 AS last_review_day
+         FROM revisions r
+         JOIN entries e ON e.id = r.entry_id
+         JOIN subjects s ON s.id = e.subject_id
+         WHERE r.status IN ('pending', 'overdue') AND (?1 IS NULL OR e.subject_id = ?1)
+         GROUP BY e.id
+         HAVING due_day IS NOT NULL",
+    )?;
+    let rows = stmt.query_map([subject_id], |row| {
+        Ok(Pending {
+            entry: Entry::from_row(row)?,
+            subject_name: row.get("subject_name")?,
+            due_day: row.get("due_day")?,
+            study_day: row.get("study_day")?,
+            last_review_day: row.get("last_review_day")?,
+        })
+    })?;
+    rows.collect()
+}
+
+#[derive(Default)]
+struct DayCounts {
+    scheduled: i64,
+    overdue: i64,
+    projected: i64,
+    by_subject: BTreeMap<i64, i64>,
+}
+
+// Reviews per day for the `days` days from today, over every subject or
+// only `subject_id`.
+pub fn forecast_reviews(conn: &Connection, days: u32, subject_id: Option<i64>) -> Result<ReviewForecast, AppError> {
+    if days == 0 || days > MAX_FORECAST_DAYS {
+        return Err(AppError::validation(format!(
+            "Forecast length must be between 1 and {} days",
+            MAX_FORECAST_DAYS
+        )));
+    }
+    let today = scheduler::today_number(conn)?;
+    let end = today + i64::from(days);
+    let algorithm = Algorithm::from_settings(conn)?;
+    let fsrs = scheduler::fsrs_from_settings(conn)?;
+    let retention = scheduler::desired_retention(conn)?;
+    let rating = fsrs::rating(PROJECTED_QUALITY);
+
+    let mut counts: Vec<DayCounts> = (0..days).map(|_| DayCounts::default()).collect();
+    let mut names: BTreeMap<i64, String> = BTreeMap::new();
+    for pending in pending_entries(conn, subject_id)? {
+        let entry = &pending.entry;
+        names.entry(entry.subject_id).or_insert(pending.subject_name);
+
+        let mut sm2 = Sm2State::of(entry);
+        // Entries never replayed under FSRS start from their study
+        let (mut memory, mut last_day) = match (entry.stability, entry.difficulty, pending.last_review_day) {
+            (Some(stability), Some(difficulty), Some(last)) => (MemoryState { stability, difficulty }, last),
+            _ => (fsrs.initial_state(fsrs::Rating::Good), pending.study_day.unwrap_or(today)),
+        };
+
+        let mut day = pending.due_day.max(today);
+        let mut scheduled = true;
+        while day < end {
+            let counted = &mut counts[(day - today) as usize];
+            if scheduled {
+                counted.scheduled += 1;
+                if pending.due_day < today {
+                    counted.overdue += 1;
+                }
+            } else {
+                counted.projected += 1;
+            }
+            *counted.by_subject.entry(entry.subject_id).or_default() += 1;
+
+            let interval = match algorithm {
+                Algorithm::Sm2 => {
+                    sm2 = scheduler::sm2(sm2, PROJECTED_QUALITY);
+                    sm2.interval_days
+                }
+                Algorithm::Fsrs => {
+                    memory = fsrs.next_state(memory, day - last_day, rating);
+                    last_day = day;
+                    fsrs::interval(memory.stability, retention)
+                }
+            };
+            day += interval.max(1);
+            scheduled = false;
+        }
+    }
+
+    let (subject_minutes, overall_minutes) = review_minutes(conn)?;
+    let minutes_per_review = |subject_id: &i64| subject_minutes.get(subject_id).copied().unwrap_or(overall_minutes);
+
+    let mut subjects: BTreeMap<i64, ForecastSubject> = BTreeMap::new();
+    let days: Vec<ForecastDay> = counts
+        .into_iter()
+        .enumerate()
+        .map(|(offset, counted)| {
+            let loads: Vec<ForecastLoad> = counted
+                .by_subject
+                .iter()
+                .map(|(subject_id, reviews)| ForecastLoad {
+                    subject_id: *subject_id,
+                    reviews: *reviews,
+                    minutes: *reviews as f64 * minutes_per_review(subject_id),
+                })
+                .collect();
+            for load in &loads {
+                let subject = subjects.entry(load.subject_id).or_insert_with(|| ForecastSubject {
+                    subject_id: load.subject_id,
+                    subject_name: names.get(&load.subject_id).cloned().unwrap_or_default(),
+                    reviews: 0,
+                    minutes: 0.0,
+                    minutes_per_review: minutes_per_review(&load.subject_id),
+                });
+                subject.reviews += load.reviews;
+                subject.minutes += load.minutes;
+            }
+            ForecastDay {
+                date: date_of(today + offset as i64),
+                scheduled: counted.scheduled,
+                overdue: counted.overdue,
+                projected: counted.projected,
+                reviews: counted.scheduled + counted.projected,
+                minutes: loads.iter().map(|load| load.minutes).sum(),
+                subjects: loads,
+            }
+        })
+        .collect();
+
+    let mut subjects: Vec<ForecastSubject> = subjects.into_values().collect();
+    subjects.sort_by(|a, b| b.reviews.cmp(&a.reviews).then_with(|| a.subject_name.cmp(&b.subject_name)));
+    Ok(ReviewForecast {
+        total_reviews: days.iter().map(|day| day.reviews).sum(),
+        total_minutes: days.iter().map(|day| day.minutes).sum(),
+        days,
+        subjects,
+    })
+}
@@ -0,0 +1,275 @@
+use serde::Serialize;
+
+// FSRS (Free Spaced Repetition Scheduler), version 4.5. Memory is modelled
+// by stability S, the number of days until recall probability falls to 90%,
+// and difficulty D from 1 to 10. The retrievability R of an item t days
+// after its last review is (1 + FACTOR * t / S) ^ DECAY, and the next review
+// is scheduled when R would fall to the desired retention.
+//
+// FSRS grades on four ratings; revisions keep SM-2's 0-5 quality, mapped by
+// `rating`. The 17 weights w0..w16 default to the published FSRS-4.5 values
+// and can be fitted to a user's own history with `optimize`.
+
+pub const DECAY: f64 = -0.5;
+pub const FACTOR: f64 = 19.0 / 81.0;
+
+pub const DEFAULT_RETENTION: f64 = 0.9;
+pub const MAX_INTERVAL_DAYS: i64 = 36500;
+
+pub const PARAMETER_COUNT: usize = 17;
+pub const DEFAULT_PARAMETERS: [f64; PARAMETER_COUNT] = [
+    0.4872, 1.4003, 3.7145, 13.8206, 5.1618, 1.2298, 0.8975, 0.031, 1.6474, 0.1367, 1.0461, 2.1072, 0.0793,
+    0.3246, 1.587, 0.2272, 2.8755,
+];
+
+// Allowed range of each weight while optimizing, as in the reference
+// optimizer
+const BOUNDS: [(f64, f64); PARAMETER_COUNT] = [
+    (0.1, 100.0),
+    (0.1, 100.0),
+    (0.1, 100.0),
+    (0.1, 100.0),
+    (1.0, 10.0),
+    (0.001, 4.0),
+    (0.001, 4.0),
+    (0.001, 0.75),
+    (0.0, 4.5),
+    (0.0, 0.8),
+    (0.001, 3.5),
+    (0.001, 5.0),
+    (0.001, 0.25),
+    (0.001, 0.9),
+    (0.0, 4.0),
+    (0.0, 1.0),
+    (1.0, 6.0),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rating {
+    Again = 1,
+    Hard = 2,
+    Good = 3,
+    Easy = 4,
+}
+
+// SM-2 quality to FSRS rating: anything below 3 is a lapse
+pub fn rating(quality: u8) -> Rating {
+    match quality {
+        0..=2 => Rating::Again,
+        3 => Rating::Hard,
+        4 => Rating::Good,
+        _ => Rating::Easy,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MemoryState {
+    pub stability: f64,
+    pub difficulty: f64,
+}
+
+// One review of an item: its day number and rating. The first review of a
+// history is when the item was learned.
+#[derive(Debug, Clone, Copy)]
+pub struct Review {
+    pub day: i64,
+    pub rating: Rating,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fsrs {
+    pub w: [f64; PARAMETER_COUNT],
+}
+
+impl Default for Fsrs {
+    fn default() -> Self {
+        Fsrs { w: DEFAULT_PARAMETERS }
+    }
+}
+
+pub fn retrievability(elapsed_days: f64, stability: f64) -> f64 {
+    (1.0 + FACTOR * elapsed_days.max(0.0) / stability).powf(DECAY)
+}
+
+// Days until retrievability falls to `retention`
+pub fn interval(stability: f64, retention: f64) -> i64 {
+    let days = stability / FACTOR * (retention.powf(1.0 / DECAY) - 1.0);
+    (days.round() as i64).clamp(1, MAX_INTERVAL_DAYS)
+}
+
+impl Fsrs {
+    // Comma-separated weights as stored in the fsrs_parameters setting.
+    // Anything else, including an empty string, means the defaults.
+    pub fn from_setting(value: Option<&str>) -> Self {
+        let parsed: Option<Vec<f64>> = value.map(|value| {
+            value
+                .split(',')
+                .filter_map(|part| part.trim().parse().ok())
+                .filter(|w: &f64| w.is_finite())
+                .collect()
+        });
+        match parsed.and_then(|w| <[f64; PARAMETER_COUNT]>::try_from(w).ok()) {
+            Some(w) => Fsrs { w },
+            None => Fsrs::default(),
+        }
+    }
+
+    pub fn to_setting(&self) -> String {
+        self.w.iter().map(|w| format!("{:.4}", w)).collect::<Vec<_>>().join(",")
+    }
+
+    fn init_difficulty(&self, rating: Rating) -> f64 {
+        (self.w[4] - self.w[5] * (rating as i32 - 3) as f64).clamp(1.0, 10.0)
+    }
+
+    pub fn initial_state(&self, rating: Rating) -> MemoryState {
+        MemoryState {
+            stability: self.w[rating as usize - 1].max(0.1),
+            difficulty: self.init_difficulty(rating),
+        }
+    }
+
+    // The state after a review `elapsed_days` after the previous one
+    pub fn next_state(&self, state: MemoryState, elapsed_days: i64, rating: Rating) -> MemoryState {
+        let w = &self.w;
+        let r = retrievability(elapsed_days as f64, state.stability);
+        let d = state.difficulty;
+        let s = state.stability;
+
+        let stability = if rating == Rating::Again {
+            w[11] * d.powf(-w[12]) * ((s + 1.0).powf(w[13]) - 1.0) * (w[14] * (1.0 - r)).exp()
+        } else {
+            let hard_penalty = if rating == Rating::Hard { w[15] } else { 1.0 };
+            let easy_bonus = if rating == Rating::Easy { w[16] } else { 1.0 };
+            s * (1.0
+                + w[8].exp() * (11.0 - d) * s.powf(-w[9]) * ((w[10] * (1.0 - r)).exp() - 1.0) * hard_penalty * easy_bonus)
+        };
+
+        let difficulty = d - w[6] * (rating as i32 - 3) as f64;
+        // Mean reversion towards the difficulty of a first "good" review
+        let difficulty = (w[7] * w[4] + (1.0 - w[7]) * difficulty).clamp(1.0, 10.0);
+
+        MemoryState {
+            stability: stability.clamp(0.1, MAX_INTERVAL_DAYS as f64),
+            difficulty,
+        }
+    }
+
+    // The state after a whole history, oldest review first
+    pub fn replay(&self, reviews: &[Review]) -> Option<MemoryState> {
+        let (first, rest) = reviews.split_first()?;
+        let mut state = self.initial_state(first.rating);
+        let mut last_day = first.day;
+        for review in rest {
+            state = self.next_state(state, review.day - last_day, review.rating);
+            last_day = review.day;
+        }
+        Some(state)
+    }
+
+    // Mean log loss of the recall probabilities predicted for every review
+    // after the first of each history, and the number of reviews scored.
+    // Same-day reviews say nothing about forgetting and are skipped.
+    fn loss(&self, histories: &[Vec<Review>]) -> (f64, usize) {
+        let mut total = 0.0;
+        let mut count = 0;
+        for reviews in histories {
+            let Some((first, rest)) = reviews.split_first() else {
+                continue;
+            };
+            let mut state = self.initial_state(first.rating);
+            let mut last_day = first.day;
+            for review in rest {
+                let elapsed = review.day - last_day;
+                if elapsed > 0 {
+                    let p = retrievability(elapsed as f64, state.stability).clamp(0.0001, 0.9999);
+                    total -= if review.rating == Rating::Again { (1.0 - p).ln() } else { p.ln() };
+                    count += 1;
+                }
+                state = self.next_state(state, elapsed, review.rating);
+                last_day = review.day;
+            }
+        }
+        (if count == 0 { 0.0 } else { total / count as f64 }, count)
+    }
+}
+
+// Fewer scored reviews than this cannot be fitted meaningfully
+pub const MIN_REVIEWS_TO_OPTIMIZE: usize = 30;
+
+const ITERATIONS: usize = 300;
+const LEARNING_RATE: f64 = 0.002;
+// Pull towards the defaults, so sparse histories only nudge the weights
+const REGULARIZATION: f64 = 0.01;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Optimization {
+    pub parameters: Vec<f64>,
+    pub reviews: usize,
+    pub loss_before: f64,
+    pub loss_after: f64,
+}
+
+fn objective(fsrs: &Fsrs, histories: &[Vec<Review>]) -> f64 {
+    let penalty: f64 = fsrs
+        .w
+        .iter()
+        .zip(DEFAULT_PARAMETERS)
+        .zip(BOUNDS)
+        .map(|((w, default), (low, high))| ((w - default) / (high - low)).powi(2))
+        .sum();
+    fsrs.loss(histories).0 + REGULARIZATION * penalty
+}
+
+// Fits the weights to review histories with Adam on finite-difference
+// gradients, starting from the defaults. Returns None when there are too
+// few reviews to learn from.
+pub fn optimize(histories: &[Vec<Review>]) -> Option<Optimization> {
+    let mut fsrs = Fsrs::default();
+    let (loss_before, reviews) = fsrs.loss(histories);
+    if reviews < MIN_REVIEWS_TO_OPTIMIZE {
+        return None;
+    }
+
+    let mut best = (objective(&fsrs, histories), fsrs);
+    let (mut m, mut v) = ([0.0; PARAMETER_COUNT], [0.0; PARAMETER_COUNT]);
+    let (beta1, beta2) = (0.9_f64, 0.999_f64);
+
+    for step in 1..=ITERATIONS as i32 {
+        let mut gradient = [0.0; PARAMETER_COUNT];
+        for (i, g) in gradient.iter_mut().enumerate() {
+            let (low, high) = BOUNDS[i];
+            let h = (high - low) * 1e-4;
+            let mut up = fsrs;
+            up.w[i] = (fsrs.w[i] + h).min(high);
+            let mut down = fsrs;
+            down.w[i] = (fsrs.w[i] - h).max(low);
+            *g = (objective(&up, histories) - objective(&down, histories)) / (up.w[i] - down.w[i]);
+        }
+
+        for (i, g) in gradient.into_iter().enumerate() {
+            let (low, high) = BOUNDS[i];
+            m[i] = beta1 * m[i] + (1.0 - beta1) * g;
+            v[i] = beta2 * v[i] + (1.0 - beta2) * g * g;
+            let m_hat = m[i] / (1.0 - beta1.powi(step));
+            let v_hat = v[i] / (1.0 - beta2.powi(step));
+            // Steps are scaled to each weight's range
+            let delta = LEARNING_RATE * (high - low) * m_hat / (v_hat.sqrt() + 1e-8);
+            fsrs.w[i] = (fsrs.w[i] - delta).clamp(low, high);
+        }
+
+        let score = objective(&fsrs, histories);
+        if score < best.0 {
+            best = (score, fsrs);
+        }
+    }
+
+    let fitted = best.1;
+    Some(Optimization {
+        parameters: fitted.w.to_vec(),
+        reviews,
+        loss_before,
+        loss_after: fitted.loss(histories).0,
+    })
+}
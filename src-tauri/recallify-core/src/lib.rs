@@ -0,0 +1,30 @@
+// Everything Recallify does with its data, independent of the Tauri shell:
+// opening and migrating the database, the typed queries behind the app's
+// commands, revision scheduling and load balancing, the review log,
+// workload forecasts and catching up on overdue revisions, dates in the
+// user's time zone, full-text search, the raw SQL bridge, where the data
+// lives, study profiles, PDF storage and the Spotify login. Functions take a
+// data directory or a connection, so they can be used from tests and other
+// front ends as well as from the app, which owns the open database.
+
+pub mod bridge;
+pub mod catch_up;
+pub mod clock;
+pub mod data_dir;
+pub mod db;
+pub mod error;
+pub mod files;
+pub mod forecast;
+pub mod fsrs;
+pub mod load_balance;
+pub mod migrations;
+pub mod models;
+pub mod orphans;
+pub mod profiles;
+pub mod queries;
+pub mod review_log;
+pub mod row_json;
+pub mod scheduler;
+pub mod search;
+pub mod spotify;
+pub mod sql_guard;
@@ -0,0 +1,162 @@
+use crate::clock;
+use crate::error::AppError;
+use crate::queries;
+use jiff::civil::Date;
+use jiff::ToSpan;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Load balancing of new revisions. Off by default; when on, a revision the
+// fixed plan or the scheduler would put on a given day may move up to a
+// tolerance window either way, to the day with the fewest open revisions,
+// and days already at the maximum daily load are avoided. The window grows
+// with the interval, so short intervals only move for the maximum:
+//
+//   interval < 3 days   no window
+//   otherwise           tolerance % of the interval, at least one day
+//
+// When every day in the window is full the revision goes to the first day
+// after it with room, at most MAX_PUSH_DAYS later, or else to the least
+// loaded day in the window.
+
+pub const LOAD_BALANCING_SETTING: &str = "load_balancing";
+pub const MAX_DAILY_REVIEWS_SETTING: &str = "max_daily_reviews";
+pub const TOLERANCE_SETTING: &str = "load_balance_tolerance";
+
+pub const DEFAULT_TOLERANCE_PERCENT: i64 = 10;
+pub const MAX_TOLERANCE_PERCENT: i64 = 50;
+const MIN_BALANCED_INTERVAL: i64 = 3;
+const MAX_PUSH_DAYS: i64 = 14;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadBalancing {
+    pub enabled: bool,
+    // Open revisions per day to stay under; None for no limit
+    pub max_daily_reviews: Option<i64>,
+    pub tolerance_percent: i64,
+}
+
+impl Default for LoadBalancing {
+    fn default() -> Self {
+        LoadBalancing {
+            enabled: false,
+            max_daily_reviews: None,
+            tolerance_percent: DEFAULT_TOLERANCE_PERCENT,
+        }
+    }
+}
+
+impl LoadBalancing {
+    // Missing or unreadable settings fall back to the defaults
+    pub fn from_settings(conn: &Connection) -> rusqlite::Result<Self> {
+        let number = |key| -> rusqlite::Result<Option<i64>> {
+            Ok(queries::get_setting(conn, key)?.and_then(|value| value.trim().parse().ok()))
+        };
+        Ok(LoadBalancing {
+            enabled: queries::get_setting(conn, LOAD_BALANCING_SETTING)?.as_deref() == Some("true"),
+            max_daily_reviews: number(MAX_DAILY_REVIEWS_SETTING)?.filter(|max| *max > 0),
+            tolerance_percent: number(TOLERANCE_SETTING)?
+                .unwrap_or(DEFAULT_TOLERANCE_PERCENT)
+                .clamp(0, MAX_TOLERANCE_PERCENT),
+        })
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), AppError> {
+        if !(0..=MAX_TOLERANCE_PERCENT).contains(&self.tolerance_percent) {
+            return Err(AppError::validation(format!(
+                "Tolerance must be between 0 and {}%",
+                MAX_TOLERANCE_PERCENT
+            )));
+        }
+        if self.max_daily_reviews.is_some_and(|max| max < 1) {
+            return Err(AppError::validation("Maximum daily reviews must be at least 1"));
+        }
+        let tx = conn.unchecked_transaction()?;
+        queries::update_setting(&tx, LOAD_BALANCING_SETTING, if self.enabled { "true" } else { "false" })?;
+        queries::update_setting(
+            &tx,
+            MAX_DAILY_REVIEWS_SETTING,
+            &self.max_daily_reviews.unwrap_or(0).to_string(),
+        )?;
+        queries::update_setting(&tx, TOLERANCE_SETTING, &self.tolerance_percent.to_string())?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    // Days a revision `interval_days` out may move either way
+    pub fn window(&self, interval_days: i64) -> i64 {
+        if interval_days < MIN_BALANCED_INTERVAL {
+            return 0;
+        }
+        let days = (interval_days * self.tolerance_percent + 50) / 100;
+        if self.tolerance_percent > 0 {
+            days.max(1)
+        } else {
+            0
+        }
+    }
+}
+
+fn add_days(date: Date, days: i64) -> Date {
+    date.checked_add(days.days()).unwrap_or(date)
+}
+
+// Open revisions due on each day from `first` to `last`
+fn daily_loads(conn: &Connection, first: Date, last: Date) -> rusqlite::Result<BTreeMap<String, i64>> {
+    let mut stmt = conn.prepare(
+        "SELECT due_date, COUNT(*) FROM revisions
+         WHERE status IN ('pending', 'overdue') AND due_date BETWEEN ?1 AND ?2
+         GROUP BY due_date",
+    )?;
+    let rows = stmt.query_map(params![first.to_string(), last.to_string()], |row| {
+        Ok((row.get(0)?, row.get(1)?))
+    })?;
+    rows.collect()
+}
+
+// The due date of a new revision `interval_days` after `from`
+// (YYYY-MM-DD). Without load balancing, or for dates that are already
+// past, that is simply the ideal date; otherwise the least loaded day
+// around it, never before tomorrow or `from`'s next day.
+pub fn due_date(conn: &Connection, from: &str, interval_days: i64) -> rusqlite::Result<String> {
+    let settings = LoadBalancing::from_settings(conn)?;
+    let Ok(start) = from.parse::<Date>() else {
+        // Not a plain date; SQLite still makes sense of timestamps
+        return conn.query_row(
+            "SELECT DATE(?1, PRINTF('%+d days', ?2))",
+            params![from, interval_days],
+            |row| row.get(0),
+        );
+    };
+    let ideal = add_days(start, interval_days);
+    let tomorrow = add_days(clock::today_date(conn)?, 1);
+    if !settings.enabled || ideal < tomorrow {
+        return Ok(ideal.to_string());
+    }
+
+    let window = settings.window(interval_days);
+    let earliest = add_days(start, 1).max(tomorrow).max(add_days(ideal, -window));
+    let latest = add_days(ideal, window);
+    let loads = daily_loads(conn, earliest, add_days(latest, MAX_PUSH_DAYS))?;
+    let load = |day: Date| loads.get(&day.to_string()).copied().unwrap_or(0);
+    let has_room = |day: Date| settings.max_daily_reviews.is_none_or(|max| load(day) < max);
+
+    let candidates: Vec<Date> = (0..)
+        .map(|offset| add_days(earliest, offset))
+        .take_while(|day| *day <= latest)
+        .collect();
+    let lightest = |days: &mut dyn Iterator<Item = Date>| {
+        days.min_by_key(|day| (load(*day), (*day - ideal).get_days().abs(), *day))
+    };
+
+    if let Some(day) = lightest(&mut candidates.iter().copied().filter(|day| has_room(*day))) {
+        return Ok(day.to_string());
+    }
+    let pushed = (1..=MAX_PUSH_DAYS).map(|offset| add_days(latest, offset)).find(|day| has_room(*day));
+    Ok(pushed
+        .or_else(|| lightest(&mut candidates.iter().copied()))
+        .unwrap_or(ideal)
+        .to_string())
+}
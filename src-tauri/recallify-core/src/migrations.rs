@@ -0,0 +1,648 @@
+use rusqlite::{Connection, Transaction};
+use std::fmt;
+
+// Schema migrations, tracked through SQLite's `PRAGMA user_version`.
+//
+// Each step runs inside its own transaction together with the version bump,
+// so a failing step leaves the database at the previous version instead of
+// half-upgraded. Steps must stay idempotent: databases created before this
+// framework existed report version 0 but already contain most of the schema.
+
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    pub up: fn(&Transaction) -> rusqlite::Result<()>,
+}
+
+#[derive(Debug)]
+pub enum MigrationError {
+    // The database was written by a newer build than this one
+    UnknownVersion { found: u32, latest: u32 },
+    // A step failed; its transaction was rolled back
+    StepFailed {
+        version: u32,
+        description: &'static str,
+        source: rusqlite::Error,
+    },
+    Sqlite(rusqlite::Error),
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::UnknownVersion { found, latest } => write!(
+                f,
+                "Database schema version {} is newer than the latest supported version {}",
+                found, latest
+            ),
+            MigrationError::StepFailed {
+                version,
+                description,
+                source,
+            } => write!(
+                f,
+                "Migration {} ({}) failed: {}",
+                version, description, source
+            ),
+            MigrationError::Sqlite(e) => write!(f, "Migration error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MigrationError::StepFailed { source, .. } => Some(source),
+            MigrationError::Sqlite(e) => Some(e),
+            MigrationError::UnknownVersion { .. } => None,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for MigrationError {
+    fn from(e: rusqlite::Error) -> Self {
+        MigrationError::Sqlite(e)
+    }
+}
+
+// Ordered list of all migrations. Append new steps at the end; never edit or
+// renumber a step that has shipped.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "base schema",
+        up: base_schema,
+    },
+    Migration {
+        version: 2,
+        description: "add entries.topics",
+        up: add_entry_topics,
+    },
+    Migration {
+        version: 3,
+        description: "daily activity and milestones",
+        up: daily_activity,
+    },
+    Migration {
+        version: 4,
+        description: "spotify auth",
+        up: spotify_auth,
+    },
+    Migration {
+        version: 5,
+        description: "automatic backups",
+        up: backup_runs,
+    },
+    Migration {
+        version: 6,
+        description: "full-text search index",
+        up: entry_search,
+    },
+    Migration {
+        version: 7,
+        description: "sm-2 scheduling state",
+        up: sm2_state,
+    },
+    Migration {
+        version: 8,
+        description: "fsrs scheduler",
+        up: fsrs_state,
+    },
+    Migration {
+        version: 9,
+        description: "review log",
+        up: review_log,
+    },
+    Migration {
+        version: 10,
+        description: "time zone setting",
+        up: timezone_setting,
+    },
+    Migration {
+        version: 11,
+        description: "search triggers without sql functions",
+        up: plain_search_triggers,
+    },
+];
+
+// Data derived from the rest of the database, rebuilt with the current
+// code. That code expects the latest schema, so rebuilds never run inside a
+// step: the ones a database needs run once every step has, in the
+// transaction of the last one. If a rebuild fails, the database stays below
+// the latest version and it runs again next time.
+pub struct Rebuild {
+    // Databases migrated from below this version need it
+    pub version: u32,
+    pub description: &'static str,
+    pub run: fn(&Connection) -> rusqlite::Result<()>,
+}
+
+// FSRS memory states: seeded for version 8, recomputed from the review log
+// for version 9
+pub const REBUILDS: &[Rebuild] = &[Rebuild {
+    version: 9,
+    description: "fsrs memory states",
+    run: rebuild_memory_states,
+}];
+
+fn rebuild_memory_states(conn: &Connection) -> rusqlite::Result<()> {
+    crate::scheduler::rebuild_memory_states(conn).map(|_| ())
+}
+
+fn rebuild_derived(conn: &Connection, start: u32) -> rusqlite::Result<()> {
+    for rebuild in REBUILDS.iter().filter(|r| r.version > start) {
+        (rebuild.run)(conn)?;
+    }
+    Ok(())
+}
+
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+pub fn current_version(conn: &Connection) -> rusqlite::Result<u32> {
+    conn.pragma_query_value(None, "user_version", |row| row.get(0))
+}
+
+// Applies every pending migration in order and returns the resulting version.
+pub fn run(conn: &Connection) -> Result<u32, MigrationError> {
+    let latest = latest_version();
+    let start = current_version(conn)?;
+
+    if start > latest {
+        return Err(MigrationError::UnknownVersion {
+            found: start,
+            latest,
+        });
+    }
+
+    let mut version = start;
+    let pending: Vec<&Migration> = MIGRATIONS.iter().filter(|m| m.version > start).collect();
+    for (i, migration) in pending.iter().enumerate() {
+        let step_failed = |source| MigrationError::StepFailed {
+            version: migration.version,
+            description: migration.description,
+            source,
+        };
+
+        let tx = conn.unchecked_transaction().map_err(step_failed)?;
+        (migration.up)(&tx).map_err(step_failed)?;
+        if i + 1 == pending.len() {
+            rebuild_derived(&tx, start).map_err(step_failed)?;
+        }
+        tx.pragma_update(None, "user_version", migration.version)
+            .map_err(step_failed)?;
+        tx.commit().map_err(step_failed)?;
+
+        // stderr, so tools printing results on stdout stay parseable
+        eprintln!(
+            "Applied migration {}: {}",
+            migration.version, migration.description
+        );
+        version = migration.version;
+    }
+
+    Ok(version)
+}
+
+fn column_exists(conn: &Connection, table: &str, column: &str) -> rusqlite::Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let names = stmt.query_map([], |row| row.get::<_, String>(1))?;
+    for name in names {
+        if name? == column {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn base_schema(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS subjects (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS entries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            subject_id INTEGER NOT NULL,
+            study_date DATE NOT NULL,
+            study_notes TEXT NOT NULL,
+            morning_recall_notes TEXT,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (subject_id) REFERENCES subjects(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS revision_intervals (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entry_id INTEGER NOT NULL,
+            interval_days INTEGER NOT NULL,
+            FOREIGN KEY (entry_id) REFERENCES entries(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entry_id INTEGER NOT NULL,
+            interval_days INTEGER NOT NULL,
+            due_date DATE NOT NULL,
+            status TEXT NOT NULL CHECK(status IN ('pending', 'completed', 'overdue', 'rescheduled')),
+            completed_at TIMESTAMP,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (entry_id) REFERENCES entries(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS activity_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entry_id INTEGER NOT NULL,
+            activity_type TEXT NOT NULL CHECK(activity_type IN ('study', 'revision_completed', 'entry_created')),
+            activity_date DATE NOT NULL,
+            details TEXT,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (entry_id) REFERENCES entries(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS pomodoro_sessions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_type TEXT NOT NULL CHECK(session_type IN ('work', 'short_break', 'long_break')),
+            duration_minutes INTEGER NOT NULL,
+            subject_id INTEGER,
+            syllabus_item_id INTEGER,
+            completed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (subject_id) REFERENCES subjects(id) ON DELETE SET NULL,
+            FOREIGN KEY (syllabus_item_id) REFERENCES syllabus_items(id) ON DELETE SET NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS pomodoro_state (
+            id INTEGER PRIMARY KEY CHECK(id = 1),
+            session_type TEXT NOT NULL CHECK(session_type IN ('work', 'short_break', 'long_break')),
+            start_timestamp INTEGER,
+            duration_seconds INTEGER NOT NULL,
+            remaining_seconds INTEGER NOT NULL,
+            is_running INTEGER DEFAULT 0,
+            pomodoro_count INTEGER DEFAULT 0 CHECK(pomodoro_count >= 0 AND pomodoro_count <= 4),
+            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS syllabus_items (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            subject_id INTEGER NOT NULL,
+            parent_id INTEGER,
+            title TEXT NOT NULL,
+            description TEXT,
+            estimated_hours REAL,
+            due_date DATE,
+            is_completed INTEGER DEFAULT 0,
+            sort_order INTEGER DEFAULT 0,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (subject_id) REFERENCES subjects(id) ON DELETE CASCADE,
+            FOREIGN KEY (parent_id) REFERENCES syllabus_items(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS entry_syllabus_links (
+            entry_id INTEGER NOT NULL,
+            syllabus_item_id INTEGER NOT NULL,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (entry_id, syllabus_item_id),
+            FOREIGN KEY (entry_id) REFERENCES entries(id) ON DELETE CASCADE,
+            FOREIGN KEY (syllabus_item_id) REFERENCES syllabus_items(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS pdf_attachments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entry_id INTEGER NOT NULL,
+            file_name TEXT NOT NULL,
+            file_path TEXT NOT NULL,
+            file_size INTEGER NOT NULL,
+            page_count INTEGER,
+            last_viewed_page INTEGER DEFAULT 1,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (entry_id) REFERENCES entries(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS export_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            export_type TEXT NOT NULL,
+            file_name TEXT NOT NULL,
+            file_path TEXT NOT NULL,
+            file_size INTEGER,
+            exported_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT UNIQUE NOT NULL,
+            color TEXT DEFAULT '#3B82F6',
+            usage_count INTEGER DEFAULT 0,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS entry_tags (
+            entry_id INTEGER NOT NULL,
+            tag_id INTEGER NOT NULL,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (entry_id, tag_id),
+            FOREIGN KEY (entry_id) REFERENCES entries(id) ON DELETE CASCADE,
+            FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_entries_subject_id ON entries(subject_id);
+        CREATE INDEX IF NOT EXISTS idx_entries_study_date ON entries(study_date);
+        CREATE INDEX IF NOT EXISTS idx_revisions_entry_id ON revisions(entry_id);
+        CREATE INDEX IF NOT EXISTS idx_revisions_due_date ON revisions(due_date);
+        CREATE INDEX IF NOT EXISTS idx_revisions_status ON revisions(status);
+        CREATE INDEX IF NOT EXISTS idx_activity_log_entry_id ON activity_log(entry_id);
+        CREATE INDEX IF NOT EXISTS idx_activity_log_activity_date ON activity_log(activity_date);
+        CREATE INDEX IF NOT EXISTS idx_pomodoro_sessions_completed_at ON pomodoro_sessions(completed_at);
+        CREATE INDEX IF NOT EXISTS idx_pomodoro_sessions_subject_id ON pomodoro_sessions(subject_id);
+        CREATE INDEX IF NOT EXISTS idx_syllabus_items_subject_id ON syllabus_items(subject_id);
+        CREATE INDEX IF NOT EXISTS idx_syllabus_items_parent_id ON syllabus_items(parent_id);
+        CREATE INDEX IF NOT EXISTS idx_pdf_attachments_entry_id ON pdf_attachments(entry_id);
+        CREATE INDEX IF NOT EXISTS idx_export_history_exported_at ON export_history(exported_at);
+
+        -- Default settings
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('default_intervals', '3,7');
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('notification_enabled', 'true');
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('notification_time', '10:00');
+
+        -- Pomodoro settings
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('pomodoro_work_duration', '25');
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('pomodoro_short_break', '5');
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('pomodoro_long_break_default', '20');
+
+        -- Initialize pomodoro state
+        INSERT OR IGNORE INTO pomodoro_state (id, session_type, duration_seconds, remaining_seconds, pomodoro_count)
+            VALUES (1, 'work', 1500, 1500, 0);
+        ",
+    )
+}
+
+// v3.0: topics column on entries
+fn add_entry_topics(tx: &Transaction) -> rusqlite::Result<()> {
+    if !column_exists(tx, "entries", "topics")? {
+        tx.execute("ALTER TABLE entries ADD COLUMN topics TEXT", [])?;
+    }
+    Ok(())
+}
+
+// v3.2: daily_activity for streak tracking, milestone_celebrations, sound and dark mode settings
+fn daily_activity(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS daily_activity (
+            activity_date DATE PRIMARY KEY,
+            study_minutes INTEGER DEFAULT 0,
+            pomodoro_count INTEGER DEFAULT 0,
+            entry_count INTEGER DEFAULT 0,
+            subjects_studied TEXT,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        );
+        CREATE INDEX IF NOT EXISTS idx_daily_activity_date ON daily_activity(activity_date);
+
+        CREATE TABLE IF NOT EXISTS milestone_celebrations (
+            milestone_days INTEGER PRIMARY KEY,
+            achieved_at TIMESTAMP NOT NULL,
+            celebration_shown INTEGER DEFAULT 0,
+            shown_at TIMESTAMP
+        );
+
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('pomodoro_sound_enabled', 'true');
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('pomodoro_sound_choice', 'gentle_bell');
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('dark_mode_enabled', 'false');
+        ",
+    )
+}
+
+// v3.3: spotify_auth table for music integration
+fn spotify_auth(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS spotify_auth (
+            id INTEGER PRIMARY KEY CHECK(id = 1),
+            access_token TEXT NOT NULL,
+            refresh_token TEXT NOT NULL,
+            expires_at TIMESTAMP NOT NULL,
+            device_id TEXT,
+            last_playlist_uri TEXT,
+            last_track_uri TEXT,
+            last_position_ms INTEGER DEFAULT 0,
+            is_premium INTEGER DEFAULT 1,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        );
+        ",
+    )
+}
+
+// Automatic backup schedule and a record of every backup run
+fn backup_runs(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS backup_runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            kind TEXT NOT NULL CHECK(kind IN ('daily', 'weekly', 'manual')),
+            status TEXT NOT NULL CHECK(status IN ('success', 'failed')),
+            file_path TEXT,
+            pdfs_path TEXT,
+            size_bytes INTEGER,
+            error TEXT,
+            started_at TIMESTAMP NOT NULL,
+            finished_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        );
+        CREATE INDEX IF NOT EXISTS idx_backup_runs_started_at ON backup_runs(started_at);
+
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('auto_backup_enabled', 'true');
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('auto_backup_interval_hours', '24');
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('auto_backup_keep_daily', '7');
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('auto_backup_keep_weekly', '4');
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('auto_backup_include_pdfs', 'false');
+        ",
+    )
+}
+
+// FTS5 index over entries for search::search_entries, filled from existing
+// entries and kept in sync by triggers. Needs the strip_html function from
+// search::register_functions on the connection. The fill is written out
+// rather than calling search::rebuild_index, which follows the latest
+// schema.
+fn entry_search(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "
+        CREATE VIRTUAL TABLE IF NOT EXISTS entry_search USING fts5(
+            notes, recall_notes, topics, tags,
+            tokenize = 'unicode61 remove_diacritics 2',
+            prefix = '2 3'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS entry_search_insert AFTER INSERT ON entries BEGIN
+            INSERT INTO entry_search (rowid, notes, recall_notes, topics, tags)
+            VALUES (
+                new.id, strip_html(new.study_notes), strip_html(new.morning_recall_notes), new.topics,
+                (SELECT group_concat(t.name, ' ') FROM entry_tags et JOIN tags t ON t.id = et.tag_id
+                 WHERE et.entry_id = new.id)
+            );
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS entry_search_update
+        AFTER UPDATE OF study_notes, morning_recall_notes, topics ON entries BEGIN
+            UPDATE entry_search
+            SET notes = strip_html(new.study_notes),
+                recall_notes = strip_html(new.morning_recall_notes),
+                topics = new.topics
+            WHERE rowid = new.id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS entry_search_delete AFTER DELETE ON entries BEGIN
+            DELETE FROM entry_search WHERE rowid = old.id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS entry_search_tag_added AFTER INSERT ON entry_tags BEGIN
+            UPDATE entry_search
+            SET tags = (SELECT group_concat(t.name, ' ') FROM entry_tags et JOIN tags t ON t.id = et.tag_id
+                        WHERE et.entry_id = new.entry_id)
+            WHERE rowid = new.entry_id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS entry_search_tag_removed AFTER DELETE ON entry_tags BEGIN
+            UPDATE entry_search
+            SET tags = (SELECT group_concat(t.name, ' ') FROM entry_tags et JOIN tags t ON t.id = et.tag_id
+                        WHERE et.entry_id = old.entry_id)
+            WHERE rowid = old.entry_id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS entry_search_tag_renamed AFTER UPDATE OF name ON tags BEGIN
+            UPDATE entry_search
+            SET tags = (SELECT group_concat(t.name, ' ') FROM entry_tags et JOIN tags t ON t.id = et.tag_id
+                        WHERE et.entry_id = entry_search.rowid)
+            WHERE rowid IN (SELECT entry_id FROM entry_tags WHERE tag_id = new.id);
+        END;
+
+        DELETE FROM entry_search;
+        INSERT INTO entry_search (rowid, notes, recall_notes, topics, tags)
+            SELECT e.id, strip_html(e.study_notes), strip_html(e.morning_recall_notes), e.topics,
+                   (SELECT group_concat(t.name, ' ') FROM entry_tags et JOIN tags t ON t.id = et.tag_id
+                    WHERE et.entry_id = e.id)
+            FROM entries e;
+        ",
+    )
+}
+
+// Per-entry SM-2 state for scheduler::grade_revision, and the grade each
+// revision was completed with
+fn sm2_state(tx: &Transaction) -> rusqlite::Result<()> {
+    let columns = [
+        ("entries", "ease_factor", "REAL NOT NULL DEFAULT 2.5"),
+        ("entries", "repetitions", "INTEGER NOT NULL DEFAULT 0"),
+        ("entries", "interval_days", "INTEGER NOT NULL DEFAULT 0"),
+        ("revisions", "quality", "INTEGER CHECK(quality BETWEEN 0 AND 5)"),
+    ];
+    for (table, column, definition) in columns {
+        if !column_exists(tx, table, column)? {
+            tx.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+        }
+    }
+    Ok(())
+}
+
+// FSRS memory state per entry, seeded from the revision history, and the
+// scheduler settings. SM-2 stays the default. The seeding is one of the
+// REBUILDS.
+fn fsrs_state(tx: &Transaction) -> rusqlite::Result<()> {
+    let columns = [
+        ("stability", "REAL"),
+        ("difficulty", "REAL"),
+        ("last_review_date", "DATE"),
+    ];
+    for (column, definition) in columns {
+        if !column_exists(tx, "entries", column)? {
+            tx.execute(&format!("ALTER TABLE entries ADD COLUMN {} {}", column, definition), [])?;
+        }
+    }
+    tx.execute_batch(
+        "
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('scheduler', 'sm2');
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('fsrs_desired_retention', '0.9');
+        ",
+    )
+}
+
+// One row per completed revision, see review_log.rs. Existing completions
+// are carried over without states; the FSRS memory states are recomputed
+// from them by the REBUILDS.
+fn review_log(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS review_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            revision_id INTEGER REFERENCES revisions(id) ON DELETE SET NULL,
+            entry_id INTEGER NOT NULL REFERENCES entries(id) ON DELETE CASCADE,
+            quality INTEGER CHECK(quality BETWEEN 0 AND 5),
+            duration_seconds INTEGER CHECK(duration_seconds >= 0),
+            scheduler TEXT CHECK(scheduler IN ('sm2', 'fsrs')),
+            scheduled_date DATE NOT NULL,
+            reviewed_date DATE NOT NULL,
+            state_before TEXT,
+            state_after TEXT,
+            next_due_date DATE,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+        CREATE INDEX IF NOT EXISTS idx_review_log_entry ON review_log(entry_id);
+        CREATE INDEX IF NOT EXISTS idx_review_log_revision ON review_log(revision_id);
+        CREATE INDEX IF NOT EXISTS idx_review_log_reviewed_date ON review_log(reviewed_date);
+        ",
+    )?;
+
+    let logged: i64 = tx.query_row("SELECT COUNT(*) FROM review_log", [], |row| row.get(0))?;
+    if logged == 0 {
+        tx.execute(
+            "INSERT INTO review_log (revision_id, entry_id, quality, scheduled_date, reviewed_date, created_at)
+             SELECT id, entry_id, quality, due_date, DATE(completed_at, 'localtime'), completed_at
+             FROM revisions
+             WHERE status = 'completed' AND completed_at IS NOT NULL
+             ORDER BY completed_at, id",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+// The zone dates are kept in, see clock.rs. Starts out as the system's, so
+// existing dates keep their meaning; empty would follow the system.
+fn timezone_setting(tx: &Transaction) -> rusqlite::Result<()> {
+    let system = crate::clock::system_timezone();
+    tx.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES ('timezone', ?1)",
+        [system.iana_name().unwrap_or("")],
+    )?;
+    Ok(())
+}
+
+// The insert and update triggers of migration 6 call strip_html, so writes
+// from tools without it registered failed. Entries are indexed from Rust
+// instead (see search.rs); the trigger left only drops an edited entry's
+// stale row until then.
+fn plain_search_triggers(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "
+        DROP TRIGGER IF EXISTS entry_search_insert;
+        DROP TRIGGER IF EXISTS entry_search_update;
+
+        CREATE TRIGGER IF NOT EXISTS entry_search_outdated
+        AFTER UPDATE OF study_notes, morning_recall_notes, topics ON entries BEGIN
+            DELETE FROM entry_search WHERE rowid = new.id;
+        END;
+        ",
+    )
+}
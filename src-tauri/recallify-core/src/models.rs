@@ -0,0 +1,714 @@
+use rusqlite::Row;
+use serde::{Deserialize, Deserializer, Serialize};
+
+// Serde mirrors of the records in src/types/index.ts. Field names follow the
+// column names so the frontend sees the same shapes it used to get from
+// `db_select`.
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Subject {
+    pub id: i64,
+    pub name: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Subject {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Subject {
+            id: row.get("id")?,
+            name: row.get("name")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubjectName {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubjectWithStats {
+    #[serde(flatten)]
+    pub subject: Subject,
+    #[serde(rename = "entryCount")]
+    pub entry_count: i64,
+    // Earliest pending or overdue revision across the subject's entries
+    pub next_revision_due: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Entry {
+    pub id: i64,
+    pub subject_id: i64,
+    pub study_date: String,
+    pub study_notes: String,
+    pub morning_recall_notes: Option<String>,
+    pub topics: Option<String>,
+    // SM-2 state, see scheduler::Sm2State
+    pub ease_factor: f64,
+    pub repetitions: i64,
+    pub interval_days: i64,
+    // FSRS memory state as of last_review_date, see fsrs::MemoryState
+    pub stability: Option<f64>,
+    pub difficulty: Option<f64>,
+    pub last_review_date: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Entry {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Entry {
+            id: row.get("id")?,
+            subject_id: row.get("subject_id")?,
+            study_date: row.get("study_date")?,
+            study_notes: row.get("study_notes")?,
+            morning_recall_notes: row.get("morning_recall_notes")?,
+            topics: row.get("topics")?,
+            ease_factor: row.get("ease_factor")?,
+            repetitions: row.get("repetitions")?,
+            interval_days: row.get("interval_days")?,
+            stability: row.get("stability")?,
+            difficulty: row.get("difficulty")?,
+            last_review_date: row.get("last_review_date")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewEntry {
+    pub subject_id: i64,
+    pub study_date: String,
+    pub study_notes: String,
+    pub intervals: Vec<i64>,
+    pub topics: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EntryUpdate {
+    pub study_notes: String,
+    pub morning_recall_notes: Option<String>,
+    // When present, replaces the intervals and all pending revisions
+    pub intervals: Option<Vec<i64>>,
+    pub topics: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RevisionInterval {
+    pub id: i64,
+    pub entry_id: i64,
+    pub interval_days: i64,
+}
+
+impl RevisionInterval {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(RevisionInterval {
+            id: row.get("id")?,
+            entry_id: row.get("entry_id")?,
+            interval_days: row.get("interval_days")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Revision {
+    pub id: i64,
+    pub entry_id: i64,
+    pub interval_days: i64,
+    pub due_date: String,
+    pub status: String,
+    // 0-5 grade given when the revision was completed through grading
+    pub quality: Option<i64>,
+    pub completed_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Revision {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Revision {
+            id: row.get("id")?,
+            entry_id: row.get("entry_id")?,
+            interval_days: row.get("interval_days")?,
+            due_date: row.get("due_date")?,
+            status: row.get("status")?,
+            quality: row.get("quality")?,
+            completed_at: row.get("completed_at")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EntryWithDetails {
+    #[serde(flatten)]
+    pub entry: Entry,
+    pub subject_name: String,
+    pub revisions: Vec<Revision>,
+    pub intervals: Vec<RevisionInterval>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RevisionGrade {
+    // The graded revision, now completed
+    pub revision: Revision,
+    // The entry's only open revision from now on
+    pub next_revision: Revision,
+    // With the updated scheduling state
+    pub entry: Entry,
+    // The grading as recorded in review_log, for undoing it
+    pub review: ReviewLog,
+}
+
+// An entry's SM-2 and FSRS state, recorded around every graded review
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchedulerState {
+    pub ease_factor: f64,
+    pub repetitions: i64,
+    pub interval_days: i64,
+    pub stability: Option<f64>,
+    pub difficulty: Option<f64>,
+    pub last_review_date: Option<String>,
+}
+
+impl SchedulerState {
+    pub fn of(entry: &Entry) -> Self {
+        SchedulerState {
+            ease_factor: entry.ease_factor,
+            repetitions: entry.repetitions,
+            interval_days: entry.interval_days,
+            stability: entry.stability,
+            difficulty: entry.difficulty,
+            last_review_date: entry.last_review_date.clone(),
+        }
+    }
+}
+
+// One completed revision. Revisions completed without a grade have no
+// quality, scheduler or states.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewLog {
+    pub id: i64,
+    // NULL once the revision itself is deleted
+    pub revision_id: Option<i64>,
+    pub entry_id: i64,
+    pub quality: Option<i64>,
+    pub duration_seconds: Option<i64>,
+    // 'sm2' or 'fsrs', whichever picked next_due_date
+    pub scheduler: Option<String>,
+    // Due date of the revision, and the local date it was done on
+    pub scheduled_date: String,
+    pub reviewed_date: String,
+    pub state_before: Option<SchedulerState>,
+    pub state_after: Option<SchedulerState>,
+    pub next_due_date: Option<String>,
+    pub created_at: String,
+}
+
+impl ReviewLog {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(ReviewLog {
+            id: row.get("id")?,
+            revision_id: row.get("revision_id")?,
+            entry_id: row.get("entry_id")?,
+            quality: row.get("quality")?,
+            duration_seconds: row.get("duration_seconds")?,
+            scheduler: row.get("scheduler")?,
+            scheduled_date: row.get("scheduled_date")?,
+            reviewed_date: row.get("reviewed_date")?,
+            state_before: scheduler_state(row, "state_before")?,
+            state_after: scheduler_state(row, "state_after")?,
+            next_due_date: row.get("next_due_date")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+}
+
+// States are stored as JSON text
+fn scheduler_state(row: &Row, column: &str) -> rusqlite::Result<Option<SchedulerState>> {
+    let Some(json) = row.get::<_, Option<String>>(column)? else {
+        return Ok(None);
+    };
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewLogFilters {
+    pub entry_id: Option<i64>,
+    pub subject_id: Option<i64>,
+    // Compared with reviewed_date
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub limit: Option<i64>,
+}
+
+// Recall over graded reviews: a grade of 3 or more counts as recalled
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionStats {
+    pub reviews: i64,
+    pub recalled: i64,
+    pub retention: Option<f64>,
+    // Days past the due date, early reviews counting as 0
+    pub average_days_late: Option<f64>,
+    pub average_seconds: Option<f64>,
+}
+
+// Expected review workload per day, see forecast::forecast_reviews
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewForecast {
+    // One per day from today on, days without reviews included
+    pub days: Vec<ForecastDay>,
+    pub subjects: Vec<ForecastSubject>,
+    pub total_reviews: i64,
+    pub total_minutes: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastDay {
+    pub date: String,
+    // Open revisions due that day; overdue ones are counted today
+    pub scheduled: i64,
+    pub overdue: i64,
+    // Reviews the scheduler would set after those
+    pub projected: i64,
+    pub reviews: i64,
+    pub minutes: f64,
+    pub subjects: Vec<ForecastLoad>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastLoad {
+    pub subject_id: i64,
+    pub reviews: i64,
+    pub minutes: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastSubject {
+    pub subject_id: i64,
+    pub subject_name: String,
+    pub reviews: i64,
+    pub minutes: f64,
+    // Average time of the subject's timed reviews, or of all of them
+    pub minutes_per_review: f64,
+}
+
+// Which overdue revisions catch_up::plan_catch_up puts on the first days
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatchUpStrategy {
+    // Shortest intervals first, as the freshest material is forgotten first
+    Interval,
+    // One subject at a time, the one with the most overdue first
+    Subject,
+    // Nearest upcoming syllabus due date first
+    ExamDate,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatchUpPlan {
+    pub strategy: CatchUpStrategy,
+    pub max_per_day: i64,
+    // Nothing was changed
+    pub dry_run: bool,
+    pub moves: Vec<CatchUpMove>,
+    // Open revisions on each day the plan uses, moved ones included
+    pub days: Vec<CatchUpDay>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatchUpMove {
+    pub revision_id: i64,
+    // The pending revision replacing it; None in a dry run
+    pub new_revision_id: Option<i64>,
+    pub entry_id: i64,
+    pub subject_id: i64,
+    pub subject_name: String,
+    pub interval_days: i64,
+    pub from_date: String,
+    pub to_date: String,
+    pub exam_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatchUpDay {
+    pub date: String,
+    pub moved: i64,
+    pub total: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RevisionWithDetails {
+    #[serde(flatten)]
+    pub revision: Revision,
+    pub entry: EntryWithDetails,
+    pub subject: Subject,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityLog {
+    pub id: i64,
+    pub entry_id: i64,
+    pub activity_type: String,
+    pub activity_date: String,
+    pub details: Option<String>,
+    pub created_at: String,
+}
+
+impl ActivityLog {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(ActivityLog {
+            id: row.get("id")?,
+            entry_id: row.get("entry_id")?,
+            activity_type: row.get("activity_type")?,
+            activity_date: row.get("activity_date")?,
+            details: row.get("details")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityLogWithDetails {
+    #[serde(flatten)]
+    pub activity: ActivityLog,
+    pub entry: EntryWithDetails,
+    pub subject: Subject,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityLogFilters {
+    pub subject_id: Option<i64>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub activity_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFilters {
+    pub subject_id: Option<i64>,
+    // Tag name, matched case-insensitively
+    pub tag: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub entry_id: i64,
+    pub subject_id: i64,
+    pub subject_name: String,
+    pub study_date: String,
+    pub topics: Option<String>,
+    // Best-matching passage with matches wrapped in <mark></mark>
+    pub snippet: String,
+    // bm25 score; lower is a better match
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyllabusItem {
+    pub id: i64,
+    pub subject_id: i64,
+    pub parent_id: Option<i64>,
+    pub title: String,
+    pub description: Option<String>,
+    pub estimated_hours: Option<f64>,
+    pub due_date: Option<String>,
+    pub is_completed: i64,
+    pub sort_order: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl SyllabusItem {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(SyllabusItem {
+            id: row.get("id")?,
+            subject_id: row.get("subject_id")?,
+            parent_id: row.get("parent_id")?,
+            title: row.get("title")?,
+            description: row.get("description")?,
+            estimated_hours: row.get("estimated_hours")?,
+            due_date: row.get("due_date")?,
+            is_completed: row.get("is_completed")?,
+            sort_order: row.get("sort_order")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyllabusItemWithCount {
+    #[serde(flatten)]
+    pub item: SyllabusItem,
+    pub entry_count: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewSyllabusItem {
+    pub subject_id: i64,
+    pub parent_id: Option<i64>,
+    pub title: String,
+    pub description: Option<String>,
+    pub estimated_hours: Option<f64>,
+    pub due_date: Option<String>,
+    pub sort_order: Option<i64>,
+}
+
+// Absent fields are left unchanged; an explicit `null` clears the column.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SyllabusItemUpdate {
+    pub title: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub description: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub estimated_hours: Option<Option<f64>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub due_date: Option<Option<String>>,
+    pub is_completed: Option<i64>,
+}
+
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PdfAttachment {
+    pub id: i64,
+    pub entry_id: i64,
+    pub file_name: String,
+    pub file_path: String,
+    pub file_size: i64,
+    pub page_count: Option<i64>,
+    pub last_viewed_page: i64,
+    pub created_at: String,
+}
+
+impl PdfAttachment {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(PdfAttachment {
+            id: row.get("id")?,
+            entry_id: row.get("entry_id")?,
+            file_name: row.get("file_name")?,
+            file_path: row.get("file_path")?,
+            file_size: row.get("file_size")?,
+            page_count: row.get("page_count")?,
+            last_viewed_page: row.get("last_viewed_page")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewPdfAttachment {
+    pub entry_id: i64,
+    pub file_name: String,
+    pub file_path: String,
+    pub file_size: i64,
+    pub page_count: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewPomodoroSession {
+    pub session_type: String,
+    pub duration_minutes: i64,
+    pub subject_id: Option<i64>,
+    pub syllabus_item_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PomodoroSessionWithSubject {
+    pub id: i64,
+    pub session_type: String,
+    pub duration_minutes: i64,
+    pub subject_id: Option<i64>,
+    pub subject_name: Option<String>,
+    pub completed_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PomodoroState {
+    pub id: i64,
+    pub session_type: String,
+    pub start_timestamp: Option<i64>,
+    pub duration_seconds: i64,
+    pub remaining_seconds: i64,
+    pub is_running: i64,
+    pub pomodoro_count: i64,
+    pub updated_at: String,
+}
+
+impl PomodoroState {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(PomodoroState {
+            id: row.get("id")?,
+            session_type: row.get("session_type")?,
+            start_timestamp: row.get("start_timestamp")?,
+            duration_seconds: row.get("duration_seconds")?,
+            remaining_seconds: row.get("remaining_seconds")?,
+            is_running: row.get("is_running")?,
+            pomodoro_count: row.get("pomodoro_count")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+// Absent fields are left unchanged.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PomodoroStateUpdate {
+    pub session_type: Option<String>,
+    pub start_timestamp: Option<i64>,
+    pub duration_seconds: Option<i64>,
+    pub remaining_seconds: Option<i64>,
+    pub is_running: Option<i64>,
+    pub pomodoro_count: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsRange {
+    Today,
+    Week,
+    Month,
+    All,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PomodoroStats {
+    pub total_sessions: i64,
+    pub total_minutes: i64,
+    pub work_sessions: i64,
+    pub break_sessions: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubjectPomodoroTotal {
+    pub subject_id: i64,
+    pub subject_name: String,
+    pub total_minutes: i64,
+    pub session_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StudyTime {
+    pub session_count: i64,
+    pub total_minutes: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubjectStudyTime {
+    pub id: i64,
+    pub name: String,
+    pub session_count: i64,
+    pub total_minutes: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PomodoroSummary {
+    pub total_pomodoros: i64,
+    pub total_minutes: i64,
+    pub subjects_studied: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    pub color: Option<String>,
+    pub usage_count: i64,
+    pub created_at: String,
+}
+
+impl Tag {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Tag {
+            id: row.get("id")?,
+            name: row.get("name")?,
+            color: row.get("color")?,
+            usage_count: row.get("usage_count")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyActivity {
+    pub activity_date: String,
+    pub study_minutes: i64,
+    pub pomodoro_count: i64,
+    pub entry_count: i64,
+    pub subjects_studied: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl DailyActivity {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(DailyActivity {
+            activity_date: row.get("activity_date")?,
+            study_minutes: row.get("study_minutes")?,
+            pomodoro_count: row.get("pomodoro_count")?,
+            entry_count: row.get("entry_count")?,
+            subjects_studied: row.get("subjects_studied")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreakStats {
+    // Consecutive active days ending today, or yesterday when today has
+    // nothing yet
+    pub current_streak: i64,
+    pub longest_streak: i64,
+    pub total_active_days: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MilestoneCelebration {
+    pub milestone_days: i64,
+    pub achieved_at: String,
+    pub celebration_shown: i64,
+    pub shown_at: Option<String>,
+}
+
+impl MilestoneCelebration {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(MilestoneCelebration {
+            milestone_days: row.get("milestone_days")?,
+            achieved_at: row.get("achieved_at")?,
+            celebration_shown: row.get("celebration_shown")?,
+            shown_at: row.get("shown_at")?,
+        })
+    }
+}
@@ -0,0 +1,179 @@
+use rusqlite::Connection;
+use serde::Serialize;
+
+// Detection and cleanup of rows left behind while foreign keys were not
+// enforced (databases written before `PRAGMA foreign_keys = ON`).
+//
+// An entry is only alive if its subject exists, and a syllabus item only if
+// its subject and every ancestor exist, so children of orphans are reported
+// as orphans too even though their direct parent row is still present.
+
+const LIVE_ENTRIES: &str = "SELECT id FROM entries WHERE subject_id IN (SELECT id FROM subjects)";
+
+const LIVE_SYLLABUS_ITEMS: &str = "WITH RECURSIVE live(id) AS (
+        SELECT id FROM syllabus_items
+        WHERE parent_id IS NULL AND subject_id IN (SELECT id FROM subjects)
+        UNION
+        SELECT s.id FROM syllabus_items s JOIN live ON s.parent_id = live.id
+        WHERE s.subject_id IN (SELECT id FROM subjects)
+    )
+    SELECT id FROM live";
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanAction {
+    Delete,
+    SetNull,
+}
+
+struct OrphanCheck {
+    table: &'static str,
+    column: &'static str,
+    // Parent ids the column is allowed to reference
+    parents: &'static str,
+    action: OrphanAction,
+}
+
+// Children are listed before their parents so removal never relies on
+// ON DELETE CASCADE.
+fn checks() -> Vec<OrphanCheck> {
+    let by_entry = |table| OrphanCheck {
+        table,
+        column: "entry_id",
+        parents: LIVE_ENTRIES,
+        action: OrphanAction::Delete,
+    };
+
+    vec![
+        by_entry("review_log"),
+        by_entry("revision_intervals"),
+        by_entry("revisions"),
+        OrphanCheck {
+            table: "review_log",
+            column: "revision_id",
+            parents: "SELECT id FROM revisions",
+            action: OrphanAction::SetNull,
+        },
+        by_entry("activity_log"),
+        by_entry("pdf_attachments"),
+        by_entry("entry_tags"),
+        by_entry("entry_syllabus_links"),
+        OrphanCheck {
+            table: "entry_tags",
+            column: "tag_id",
+            parents: "SELECT id FROM tags",
+            action: OrphanAction::Delete,
+        },
+        OrphanCheck {
+            table: "entry_syllabus_links",
+            column: "syllabus_item_id",
+            parents: LIVE_SYLLABUS_ITEMS,
+            action: OrphanAction::Delete,
+        },
+        OrphanCheck {
+            table: "pomodoro_sessions",
+            column: "subject_id",
+            parents: "SELECT id FROM subjects",
+            action: OrphanAction::SetNull,
+        },
+        OrphanCheck {
+            table: "pomodoro_sessions",
+            column: "syllabus_item_id",
+            parents: LIVE_SYLLABUS_ITEMS,
+            action: OrphanAction::SetNull,
+        },
+        OrphanCheck {
+            table: "entries",
+            column: "subject_id",
+            parents: "SELECT id FROM subjects",
+            action: OrphanAction::Delete,
+        },
+        OrphanCheck {
+            table: "syllabus_items",
+            column: "id",
+            parents: LIVE_SYLLABUS_ITEMS,
+            action: OrphanAction::Delete,
+        },
+    ]
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanCount {
+    pub table: &'static str,
+    pub column: &'static str,
+    pub count: i64,
+    pub action: OrphanAction,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanReport {
+    pub orphans: Vec<OrphanCount>,
+    pub total: i64,
+    // PDF files on disk that belong to orphaned attachments
+    pub pdf_files: Vec<String>,
+    pub removed: bool,
+}
+
+// Finds orphaned rows and, when `remove` is set, deletes them (or clears the
+// dangling reference) in a single transaction. PDF files are only listed;
+// the caller deletes them once the transaction has committed.
+pub fn repair(conn: &Connection, remove: bool) -> rusqlite::Result<OrphanReport> {
+    let tx = conn.unchecked_transaction()?;
+
+    let pdf_files = {
+        let mut stmt = tx.prepare(&format!(
+            "SELECT file_path FROM pdf_attachments WHERE entry_id NOT IN ({})",
+            LIVE_ENTRIES
+        ))?;
+        let paths = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        paths.collect::<rusqlite::Result<Vec<_>>>()?
+    };
+
+    let mut orphans = Vec::new();
+    for check in checks() {
+        let condition = format!(
+            "{column} IS NOT NULL AND {column} NOT IN ({parents})",
+            column = check.column,
+            parents = check.parents
+        );
+
+        let count: i64 = tx.query_row(
+            &format!("SELECT COUNT(*) FROM {} WHERE {}", check.table, condition),
+            [],
+            |row| row.get(0),
+        )?;
+
+        if remove && count > 0 {
+            let sql = match check.action {
+                OrphanAction::Delete => format!("DELETE FROM {} WHERE {}", check.table, condition),
+                OrphanAction::SetNull => format!(
+                    "UPDATE {table} SET {column} = NULL WHERE {condition}",
+                    table = check.table,
+                    column = check.column,
+                    condition = condition
+                ),
+            };
+            tx.execute(&sql, [])?;
+        }
+
+        orphans.push(OrphanCount {
+            table: check.table,
+            column: check.column,
+            count,
+            action: check.action,
+        });
+    }
+
+    if remove {
+        tx.commit()?;
+    }
+
+    Ok(OrphanReport {
+        total: orphans.iter().map(|o| o.count).sum(),
+        orphans,
+        pdf_files,
+        removed: remove,
+    })
+}
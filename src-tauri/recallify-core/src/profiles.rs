@@ -0,0 +1,193 @@
+use crate::error::{AppError, ResultExt};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Study profiles: separate databases, each with its own pdfs and backups
+// folders, kept under the data directory.
+//
+//   recallify.db, pdfs/, backups/   the default profile
+//   profiles/<id>/                  every other profile, laid out the same
+//   profiles.json                   profile names and which one is active
+//
+// The id names the folder and never changes; renaming only changes the
+// display name, so no stored paths have to be rewritten.
+
+pub const DEFAULT_PROFILE: &str = "default";
+const PROFILES_DIR: &str = "profiles";
+pub const PROFILES_FILE: &str = "profiles.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profiles {
+    pub active: String,
+    pub profiles: Vec<Profile>,
+}
+
+impl Default for Profiles {
+    fn default() -> Self {
+        Profiles {
+            active: DEFAULT_PROFILE.to_string(),
+            profiles: vec![Profile {
+                id: DEFAULT_PROFILE.to_string(),
+                name: "Default".to_string(),
+            }],
+        }
+    }
+}
+
+impl Profiles {
+    pub fn find(&self, id: &str) -> Result<&Profile, AppError> {
+        self.profiles
+            .iter()
+            .find(|p| p.id == id)
+            .ok_or_else(|| AppError::not_found(format!("Profile {} not found", id)))
+    }
+
+    fn check_name(&self, name: &str, except: Option<&str>) -> Result<String, AppError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::validation("Profile name cannot be empty"));
+        }
+        let taken = self
+            .profiles
+            .iter()
+            .any(|p| Some(p.id.as_str()) != except && p.name.eq_ignore_ascii_case(name));
+        if taken {
+            return Err(AppError::validation(format!("A profile named {} already exists", name)));
+        }
+        Ok(name.to_string())
+    }
+}
+
+pub fn profiles_root(data_dir: &Path) -> PathBuf {
+    data_dir.join(PROFILES_DIR)
+}
+
+pub fn profile_dir(data_dir: &Path, id: &str) -> PathBuf {
+    if id == DEFAULT_PROFILE {
+        data_dir.to_path_buf()
+    } else {
+        profiles_root(data_dir).join(id)
+    }
+}
+
+// All profiles under `data_dir`. A directory without profiles.json only has
+// the default profile.
+pub fn load(data_dir: &Path) -> Result<Profiles, AppError> {
+    let path = data_dir.join(PROFILES_FILE);
+    match fs::read_to_string(&path) {
+        Ok(text) => serde_json::from_str(&text)
+            .map_err(|e| AppError::validation(e.to_string()).context(format!("Failed to read {}", path.display()))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Profiles::default()),
+        Err(e) => Err(AppError::from(e).context(format!("Failed to read {}", path.display()))),
+    }
+}
+
+fn save(data_dir: &Path, profiles: &Profiles) -> Result<(), AppError> {
+    let path = data_dir.join(PROFILES_FILE);
+    let text = serde_json::to_string_pretty(profiles).map_err(|e| AppError::internal(e.to_string()))?;
+    // Written aside and renamed so a crash never leaves half a file
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, text).context(format!("Failed to write {}", temp.display()))?;
+    fs::rename(&temp, &path).context(format!("Failed to write {}", path.display()))
+}
+
+// The directory the active profile keeps its database, pdfs and backups in.
+// Falls back to the default profile if profiles.json cannot be read, so a
+// damaged file never hides the original data.
+pub fn active_dir(data_dir: &Path) -> PathBuf {
+    match load(data_dir) {
+        Ok(profiles) => profile_dir(data_dir, &profiles.active),
+        Err(e) => {
+            eprintln!("{}", e);
+            data_dir.to_path_buf()
+        }
+    }
+}
+
+fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "profile".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+// Adds an empty profile; its database is created when it is first opened.
+pub fn create(data_dir: &Path, name: &str) -> Result<Profile, AppError> {
+    let mut profiles = load(data_dir)?;
+    let name = profiles.check_name(name, None)?;
+
+    let base = slug(&name);
+    let mut id = base.clone();
+    let mut n = 2;
+    while id == DEFAULT_PROFILE || profiles.find(&id).is_ok() || profile_dir(data_dir, &id).exists() {
+        id = format!("{}-{}", base, n);
+        n += 1;
+    }
+
+    let dir = profile_dir(data_dir, &id);
+    fs::create_dir_all(&dir).context(format!("Failed to create {}", dir.display()))?;
+    let profile = Profile { id, name };
+    profiles.profiles.push(profile.clone());
+    save(data_dir, &profiles)?;
+    Ok(profile)
+}
+
+pub fn rename(data_dir: &Path, id: &str, name: &str) -> Result<Profile, AppError> {
+    let mut profiles = load(data_dir)?;
+    profiles.find(id)?;
+    let name = profiles.check_name(name, Some(id))?;
+    let profile = profiles.profiles.iter_mut().find(|p| p.id == id).expect("profile found above");
+    profile.name = name;
+    let renamed = profile.clone();
+    save(data_dir, &profiles)?;
+    Ok(renamed)
+}
+
+// Only records the choice; the caller reopens the database.
+pub fn set_active(data_dir: &Path, id: &str) -> Result<(), AppError> {
+    let mut profiles = load(data_dir)?;
+    profiles.find(id)?;
+    profiles.active = id.to_string();
+    save(data_dir, &profiles)
+}
+
+// Deletes a profile with its database, pdfs and backups. The default and
+// the active profile cannot be deleted.
+pub fn delete(data_dir: &Path, id: &str) -> Result<(), AppError> {
+    let mut profiles = load(data_dir)?;
+    profiles.find(id)?;
+    if id == DEFAULT_PROFILE {
+        return Err(AppError::validation("The default profile cannot be deleted"));
+    }
+    if id == profiles.active {
+        return Err(AppError::validation("Switch to another profile before deleting this one"));
+    }
+
+    let dir = profile_dir(data_dir, id);
+    match fs::remove_dir_all(&dir) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(AppError::from(e).context(format!("Failed to delete {}", dir.display()))),
+    }
+    profiles.profiles.retain(|p| p.id != id);
+    save(data_dir, &profiles)
+}
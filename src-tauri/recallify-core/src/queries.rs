@@ -0,0 +1,1068 @@
+use crate::clock;
+use crate::db;
+use crate::load_balance;
+use crate::models::*;
+use crate::search;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Params, Row};
+use std::collections::BTreeMap;
+
+// Typed queries behind the Tauri commands. Every function takes the
+// connection explicitly; multi-statement writes run in a transaction.
+//
+// "Today" and every date column are in the user's time zone: SQL goes
+// through `local_date()`, Rust through clock::today (see clock.rs).
+
+const MILESTONES: &[i64] = &[7, 14, 30, 50, 100, 180, 365];
+
+fn query_all<T, P: Params>(
+    conn: &Connection,
+    sql: &str,
+    params: P,
+    map: impl FnMut(&Row) -> rusqlite::Result<T>,
+) -> rusqlite::Result<Vec<T>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map(params, map)?;
+    rows.collect()
+}
+
+// Subjects
+
+pub fn get_subjects(conn: &Connection) -> rusqlite::Result<Vec<SubjectWithStats>> {
+    query_all(
+        conn,
+        "SELECT s.*,
+            (SELECT COUNT(*) FROM entries e WHERE e.subject_id = s.id) AS entry_count,
+            (SELECT MIN(r.due_date) FROM revisions r
+             JOIN entries e ON r.entry_id = e.id
+             WHERE e.subject_id = s.id AND r.status IN ('pending', 'overdue')) AS next_revision_due
+         FROM subjects s
+         ORDER BY s.created_at DESC",
+        [],
+        |row| {
+            Ok(SubjectWithStats {
+                subject: Subject::from_row(row)?,
+                entry_count: row.get("entry_count")?,
+                next_revision_due: row.get("next_revision_due")?,
+            })
+        },
+    )
+}
+
+pub fn get_subject_names(conn: &Connection) -> rusqlite::Result<Vec<SubjectName>> {
+    query_all(
+        conn,
+        "SELECT id, name FROM subjects ORDER BY name ASC",
+        [],
+        |row| {
+            Ok(SubjectName {
+                id: row.get(0)?,
+                name: row.get(1)?,
+            })
+        },
+    )
+}
+
+pub fn get_subject(conn: &Connection, id: i64) -> rusqlite::Result<Subject> {
+    conn.query_row(
+        "SELECT * FROM subjects WHERE id = ?1",
+        [id],
+        Subject::from_row,
+    )
+}
+
+pub fn create_subject(conn: &Connection, name: &str) -> rusqlite::Result<Subject> {
+    conn.execute("INSERT INTO subjects (name) VALUES (?1)", [name])?;
+    get_subject(conn, conn.last_insert_rowid())
+}
+
+pub fn update_subject(conn: &Connection, id: i64, name: &str) -> rusqlite::Result<Subject> {
+    conn.execute(
+        "UPDATE subjects SET name = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
+        params![name, id],
+    )?;
+    get_subject(conn, id)
+}
+
+pub fn delete_subject(conn: &Connection, id: i64) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM subjects WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+// Entries
+
+fn entry_details(conn: &Connection, entry: Entry) -> rusqlite::Result<EntryWithDetails> {
+    let subject_name = conn.query_row(
+        "SELECT name FROM subjects WHERE id = ?1",
+        [entry.subject_id],
+        |row| row.get(0),
+    )?;
+    let revisions = query_all(
+        conn,
+        "SELECT * FROM revisions WHERE entry_id = ?1 ORDER BY due_date ASC",
+        [entry.id],
+        Revision::from_row,
+    )?;
+    let intervals = query_all(
+        conn,
+        "SELECT * FROM revision_intervals WHERE entry_id = ?1 ORDER BY interval_days ASC",
+        [entry.id],
+        RevisionInterval::from_row,
+    )?;
+
+    Ok(EntryWithDetails {
+        entry,
+        subject_name,
+        revisions,
+        intervals,
+    })
+}
+
+pub fn get_entry(conn: &Connection, id: i64) -> rusqlite::Result<EntryWithDetails> {
+    let entry = conn.query_row("SELECT * FROM entries WHERE id = ?1", [id], Entry::from_row)?;
+    entry_details(conn, entry)
+}
+
+pub fn get_entries_by_subject(
+    conn: &Connection,
+    subject_id: i64,
+) -> rusqlite::Result<Vec<EntryWithDetails>> {
+    let entries = query_all(
+        conn,
+        "SELECT * FROM entries WHERE subject_id = ?1 ORDER BY study_date DESC",
+        [subject_id],
+        Entry::from_row,
+    )?;
+    entries
+        .into_iter()
+        .map(|entry| entry_details(conn, entry))
+        .collect()
+}
+
+// Creates one revision_intervals row and one pending revision per interval,
+// due `interval` days after the study date, or on a lighter day nearby
+// with load balancing (see load_balance.rs).
+fn insert_intervals(
+    conn: &Connection,
+    entry_id: i64,
+    study_date: &str,
+    intervals: &[i64],
+) -> rusqlite::Result<()> {
+    for interval in intervals {
+        conn.execute(
+            "INSERT INTO revision_intervals (entry_id, interval_days) VALUES (?1, ?2)",
+            params![entry_id, interval],
+        )?;
+        conn.execute(
+            "INSERT INTO revisions (entry_id, interval_days, due_date, status) VALUES (?1, ?2, ?3, 'pending')",
+            params![entry_id, interval, load_balance::due_date(conn, study_date, *interval)?],
+        )?;
+    }
+    Ok(())
+}
+
+pub fn create_entry(conn: &Connection, new: &NewEntry) -> rusqlite::Result<EntryWithDetails> {
+    let tx = conn.unchecked_transaction()?;
+
+    tx.execute(
+        "INSERT INTO entries (subject_id, study_date, study_notes, topics) VALUES (?1, ?2, ?3, ?4)",
+        params![new.subject_id, new.study_date, new.study_notes, new.topics],
+    )?;
+    let entry_id = tx.last_insert_rowid();
+    search::index_entry(&tx, entry_id)?;
+
+    insert_intervals(&tx, entry_id, &new.study_date, &new.intervals)?;
+
+    tx.execute(
+        "INSERT INTO activity_log (entry_id, activity_type, activity_date) VALUES (?1, 'study', ?2)",
+        params![entry_id, new.study_date],
+    )?;
+
+    tx.commit()?;
+    get_entry(conn, entry_id)
+}
+
+pub fn update_entry(
+    conn: &Connection,
+    id: i64,
+    update: &EntryUpdate,
+) -> rusqlite::Result<EntryWithDetails> {
+    let tx = conn.unchecked_transaction()?;
+
+    let changed = tx.execute(
+        "UPDATE entries SET study_notes = ?1, morning_recall_notes = ?2, topics = ?3, updated_at = CURRENT_TIMESTAMP WHERE id = ?4",
+        params![update.study_notes, update.morning_recall_notes, update.topics, id],
+    )?;
+    if changed == 0 {
+        return Err(rusqlite::Error::QueryReturnedNoRows);
+    }
+    search::index_entry(&tx, id)?;
+
+    if let Some(intervals) = &update.intervals {
+        let study_date: String = tx.query_row(
+            "SELECT study_date FROM entries WHERE id = ?1",
+            [id],
+            |row| row.get(0),
+        )?;
+
+        // Completed revisions are history and stay; pending ones are regenerated
+        tx.execute("DELETE FROM revision_intervals WHERE entry_id = ?1", [id])?;
+        tx.execute(
+            "DELETE FROM revisions WHERE entry_id = ?1 AND status = 'pending'",
+            [id],
+        )?;
+        insert_intervals(&tx, id, &study_date, intervals)?;
+    }
+
+    tx.commit()?;
+    get_entry(conn, id)
+}
+
+pub fn delete_entry(conn: &Connection, id: i64) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM entries WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+// Revisions
+
+pub fn get_revision(conn: &Connection, id: i64) -> rusqlite::Result<Revision> {
+    conn.query_row(
+        "SELECT * FROM revisions WHERE id = ?1",
+        [id],
+        Revision::from_row,
+    )
+}
+
+fn revision_details(conn: &Connection, revision: Revision) -> rusqlite::Result<RevisionWithDetails> {
+    let entry = get_entry(conn, revision.entry_id)?;
+    let subject = get_subject(conn, entry.entry.subject_id)?;
+    Ok(RevisionWithDetails {
+        revision,
+        entry,
+        subject,
+    })
+}
+
+pub fn get_revisions_due_today(conn: &Connection) -> rusqlite::Result<Vec<RevisionWithDetails>> {
+    let revisions = query_all(
+        conn,
+        "SELECT * FROM revisions
+         WHERE due_date <= local_date() AND status IN ('pending', 'overdue')
+         ORDER BY due_date ASC",
+        [],
+        Revision::from_row,
+    )?;
+    revisions
+        .into_iter()
+        .map(|revision| revision_details(conn, revision))
+        .collect()
+}
+
+pub fn get_revisions_by_date(
+    conn: &Connection,
+    date: &str,
+) -> rusqlite::Result<Vec<RevisionWithDetails>> {
+    let revisions = query_all(
+        conn,
+        "SELECT * FROM revisions WHERE due_date = ?1 ORDER BY status ASC",
+        [date],
+        Revision::from_row,
+    )?;
+    revisions
+        .into_iter()
+        .map(|revision| revision_details(conn, revision))
+        .collect()
+}
+
+pub fn get_revisions_between(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+) -> rusqlite::Result<Vec<Revision>> {
+    query_all(
+        conn,
+        "SELECT * FROM revisions WHERE due_date >= ?1 AND due_date <= ?2 ORDER BY due_date ASC",
+        [start_date, end_date],
+        Revision::from_row,
+    )
+}
+
+// Completing a revision that is already done changes and logs nothing, so
+// a repeated click can't add a second review.
+pub fn complete_revision(conn: &Connection, id: i64) -> rusqlite::Result<()> {
+    let tx = db::write_transaction(conn)?;
+
+    let changed = tx.execute(
+        "UPDATE revisions SET status = 'completed', completed_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?1 AND status IN ('pending', 'overdue')",
+        [id],
+    )?;
+    if changed == 0 {
+        let exists: bool =
+            tx.query_row("SELECT EXISTS (SELECT 1 FROM revisions WHERE id = ?1)", [id], |row| row.get(0))?;
+        return if exists { Ok(()) } else { Err(rusqlite::Error::QueryReturnedNoRows) };
+    }
+
+    tx.execute(
+        "INSERT INTO activity_log (entry_id, activity_type, activity_date, details)
+         SELECT entry_id, 'revision_completed', local_date(), 'Day ' || interval_days || ' revision'
+         FROM revisions WHERE id = ?1",
+        [id],
+    )?;
+
+    // Ungraded, so it leaves the scheduler state alone
+    tx.execute(
+        "INSERT INTO review_log (revision_id, entry_id, scheduled_date, reviewed_date)
+         SELECT id, entry_id, due_date, local_date() FROM revisions WHERE id = ?1",
+        [id],
+    )?;
+
+    tx.commit()
+}
+
+// Reopens a revision completed without a grade and forgets its review.
+// Graded revisions are not matched: reopening one has to roll back what
+// the grade scheduled, see scheduler::uncomplete_revision.
+pub fn uncomplete_revision(conn: &Connection, id: i64) -> rusqlite::Result<()> {
+    let tx = conn.unchecked_transaction()?;
+
+    let changed = tx.execute(
+        "UPDATE revisions
+         SET status = CASE WHEN due_date < local_date() THEN 'overdue' ELSE 'pending' END,
+             completed_at = NULL,
+             updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?1 AND quality IS NULL",
+        [id],
+    )?;
+    if changed == 0 {
+        return Err(rusqlite::Error::QueryReturnedNoRows);
+    }
+    tx.execute("DELETE FROM review_log WHERE revision_id = ?1", [id])?;
+
+    tx.commit()
+}
+
+// Marks the revision as rescheduled and creates its pending replacement.
+pub fn reschedule_revision(
+    conn: &Connection,
+    id: i64,
+    new_date: &str,
+) -> rusqlite::Result<Revision> {
+    let tx = conn.unchecked_transaction()?;
+
+    let changed = tx.execute(
+        "UPDATE revisions SET status = 'rescheduled', updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+        [id],
+    )?;
+    if changed == 0 {
+        return Err(rusqlite::Error::QueryReturnedNoRows);
+    }
+
+    tx.execute(
+        "INSERT INTO revisions (entry_id, interval_days, due_date, status)
+         SELECT entry_id, interval_days, ?2, 'pending' FROM revisions WHERE id = ?1",
+        params![id, new_date],
+    )?;
+    let new_id = tx.last_insert_rowid();
+
+    tx.commit()?;
+    get_revision(conn, new_id)
+}
+
+pub fn update_overdue_revisions(conn: &Connection) -> rusqlite::Result<usize> {
+    conn.execute(
+        "UPDATE revisions SET status = 'overdue' WHERE due_date < local_date() AND status = 'pending'",
+        [],
+    )
+}
+
+// Whether update_overdue_revisions would change anything, answered without
+// needing the writer
+pub fn has_revisions_to_mark_overdue(conn: &Connection) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM revisions WHERE due_date < local_date() AND status = 'pending')",
+        [],
+        |row| row.get(0),
+    )
+}
+
+// Activity log
+
+pub fn get_activity_log(
+    conn: &Connection,
+    filters: &ActivityLogFilters,
+) -> rusqlite::Result<Vec<ActivityLogWithDetails>> {
+    let mut sql = String::from(
+        "SELECT a.* FROM activity_log a JOIN entries e ON a.entry_id = e.id WHERE 1=1",
+    );
+    let mut values: Vec<Value> = Vec::new();
+
+    if let Some(activity_type) = &filters.activity_type {
+        sql.push_str(" AND a.activity_type = ?");
+        values.push(activity_type.clone().into());
+    }
+    if let Some(start_date) = &filters.start_date {
+        sql.push_str(" AND a.activity_date >= ?");
+        values.push(start_date.clone().into());
+    }
+    if let Some(end_date) = &filters.end_date {
+        sql.push_str(" AND a.activity_date <= ?");
+        values.push(end_date.clone().into());
+    }
+    if let Some(subject_id) = filters.subject_id {
+        sql.push_str(" AND e.subject_id = ?");
+        values.push(subject_id.into());
+    }
+    sql.push_str(" ORDER BY a.activity_date DESC, a.created_at DESC");
+
+    let activities = query_all(conn, &sql, params_from_iter(values), ActivityLog::from_row)?;
+    activities
+        .into_iter()
+        .map(|activity| {
+            let entry = get_entry(conn, activity.entry_id)?;
+            let subject = get_subject(conn, entry.entry.subject_id)?;
+            Ok(ActivityLogWithDetails {
+                activity,
+                entry,
+                subject,
+            })
+        })
+        .collect()
+}
+
+// Settings
+
+pub fn get_settings(conn: &Connection) -> rusqlite::Result<BTreeMap<String, String>> {
+    let pairs = query_all(conn, "SELECT key, value FROM settings", [], |row| {
+        Ok((row.get(0)?, row.get(1)?))
+    })?;
+    Ok(pairs.into_iter().collect())
+}
+
+pub fn get_setting(conn: &Connection, key: &str) -> rusqlite::Result<Option<String>> {
+    conn.query_row("SELECT value FROM settings WHERE key = ?1", [key], |row| {
+        row.get(0)
+    })
+    .optional()
+}
+
+pub fn update_setting(conn: &Connection, key: &str, value: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value, updated_at) VALUES (?1, ?2, CURRENT_TIMESTAMP)",
+        [key, value],
+    )?;
+    Ok(())
+}
+
+// Syllabus
+
+pub fn get_syllabus_items(
+    conn: &Connection,
+    subject_id: i64,
+) -> rusqlite::Result<Vec<SyllabusItemWithCount>> {
+    query_all(
+        conn,
+        "SELECT s.*,
+            (SELECT COUNT(*) FROM entry_syllabus_links WHERE syllabus_item_id = s.id) AS entry_count
+         FROM syllabus_items s
+         WHERE s.subject_id = ?1
+         ORDER BY s.sort_order, s.created_at",
+        [subject_id],
+        |row| {
+            Ok(SyllabusItemWithCount {
+                item: SyllabusItem::from_row(row)?,
+                entry_count: row.get("entry_count")?,
+            })
+        },
+    )
+}
+
+pub fn create_syllabus_item(conn: &Connection, item: &NewSyllabusItem) -> rusqlite::Result<i64> {
+    conn.execute(
+        "INSERT INTO syllabus_items
+         (subject_id, parent_id, title, description, estimated_hours, due_date, sort_order)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            item.subject_id,
+            item.parent_id,
+            item.title,
+            item.description,
+            item.estimated_hours,
+            item.due_date,
+            item.sort_order.unwrap_or(0),
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn update_syllabus_item(
+    conn: &Connection,
+    id: i64,
+    update: &SyllabusItemUpdate,
+) -> rusqlite::Result<()> {
+    let mut columns: Vec<&str> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+
+    if let Some(title) = &update.title {
+        columns.push("title = ?");
+        values.push(title.clone().into());
+    }
+    if let Some(description) = &update.description {
+        columns.push("description = ?");
+        values.push(description.clone().into());
+    }
+    if let Some(estimated_hours) = update.estimated_hours {
+        columns.push("estimated_hours = ?");
+        values.push(estimated_hours.into());
+    }
+    if let Some(due_date) = &update.due_date {
+        columns.push("due_date = ?");
+        values.push(due_date.clone().into());
+    }
+    if let Some(is_completed) = update.is_completed {
+        columns.push("is_completed = ?");
+        values.push(is_completed.into());
+    }
+
+    if columns.is_empty() {
+        return Ok(());
+    }
+
+    columns.push("updated_at = CURRENT_TIMESTAMP");
+    values.push(id.into());
+    conn.execute(
+        &format!("UPDATE syllabus_items SET {} WHERE id = ?", columns.join(", ")),
+        params_from_iter(values),
+    )?;
+    Ok(())
+}
+
+pub fn delete_syllabus_item(conn: &Connection, id: i64) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM syllabus_items WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+// Replaces the entry's syllabus links with `syllabus_item_ids`.
+pub fn link_entry_to_syllabus(
+    conn: &Connection,
+    entry_id: i64,
+    syllabus_item_ids: &[i64],
+) -> rusqlite::Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "DELETE FROM entry_syllabus_links WHERE entry_id = ?1",
+        [entry_id],
+    )?;
+    for syllabus_item_id in syllabus_item_ids {
+        tx.execute(
+            "INSERT INTO entry_syllabus_links (entry_id, syllabus_item_id) VALUES (?1, ?2)",
+            params![entry_id, syllabus_item_id],
+        )?;
+    }
+    tx.commit()
+}
+
+pub fn get_entry_syllabus_links(
+    conn: &Connection,
+    entry_id: i64,
+) -> rusqlite::Result<Vec<SyllabusItem>> {
+    query_all(
+        conn,
+        "SELECT s.* FROM syllabus_items s
+         JOIN entry_syllabus_links l ON s.id = l.syllabus_item_id
+         WHERE l.entry_id = ?1",
+        [entry_id],
+        SyllabusItem::from_row,
+    )
+}
+
+// PDF attachments
+
+pub fn get_pdf_attachments(conn: &Connection, entry_id: i64) -> rusqlite::Result<Vec<PdfAttachment>> {
+    query_all(
+        conn,
+        "SELECT * FROM pdf_attachments WHERE entry_id = ?1 ORDER BY created_at DESC",
+        [entry_id],
+        PdfAttachment::from_row,
+    )
+}
+
+pub fn get_pdf_attachment(conn: &Connection, id: i64) -> rusqlite::Result<Option<PdfAttachment>> {
+    conn.query_row(
+        "SELECT * FROM pdf_attachments WHERE id = ?1",
+        [id],
+        PdfAttachment::from_row,
+    )
+    .optional()
+}
+
+pub fn create_pdf_attachment(conn: &Connection, pdf: &NewPdfAttachment) -> rusqlite::Result<i64> {
+    conn.execute(
+        "INSERT INTO pdf_attachments (entry_id, file_name, file_path, file_size, page_count)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            pdf.entry_id,
+            pdf.file_name,
+            pdf.file_path,
+            pdf.file_size,
+            pdf.page_count
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn update_pdf_last_viewed_page(
+    conn: &Connection,
+    id: i64,
+    page_number: i64,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE pdf_attachments SET last_viewed_page = ?1 WHERE id = ?2",
+        [page_number, id],
+    )?;
+    Ok(())
+}
+
+pub fn delete_pdf_attachment(conn: &Connection, id: i64) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM pdf_attachments WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+// Pomodoro
+
+pub fn record_pomodoro_session(
+    conn: &Connection,
+    session: &NewPomodoroSession,
+) -> rusqlite::Result<i64> {
+    conn.execute(
+        "INSERT INTO pomodoro_sessions (session_type, duration_minutes, subject_id, syllabus_item_id)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![
+            session.session_type,
+            session.duration_minutes,
+            session.subject_id,
+            session.syllabus_item_id
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn get_pomodoro_sessions(
+    conn: &Connection,
+) -> rusqlite::Result<Vec<PomodoroSessionWithSubject>> {
+    query_all(
+        conn,
+        "SELECT ps.id, ps.session_type, ps.duration_minutes, ps.subject_id,
+                s.name AS subject_name, ps.completed_at
+         FROM pomodoro_sessions ps
+         LEFT JOIN subjects s ON ps.subject_id = s.id
+         ORDER BY ps.completed_at DESC",
+        [],
+        |row| {
+            Ok(PomodoroSessionWithSubject {
+                id: row.get("id")?,
+                session_type: row.get("session_type")?,
+                duration_minutes: row.get("duration_minutes")?,
+                subject_id: row.get("subject_id")?,
+                subject_name: row.get("subject_name")?,
+                completed_at: row.get("completed_at")?,
+            })
+        },
+    )
+}
+
+pub fn get_pomodoro_state(conn: &Connection) -> rusqlite::Result<PomodoroState> {
+    conn.query_row(
+        "SELECT * FROM pomodoro_state WHERE id = 1",
+        [],
+        PomodoroState::from_row,
+    )
+}
+
+pub fn update_pomodoro_state(
+    conn: &Connection,
+    update: &PomodoroStateUpdate,
+) -> rusqlite::Result<PomodoroState> {
+    let mut columns: Vec<&str> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+
+    if let Some(session_type) = &update.session_type {
+        columns.push("session_type = ?");
+        values.push(session_type.clone().into());
+    }
+    if let Some(start_timestamp) = update.start_timestamp {
+        columns.push("start_timestamp = ?");
+        values.push(start_timestamp.into());
+    }
+    if let Some(duration_seconds) = update.duration_seconds {
+        columns.push("duration_seconds = ?");
+        values.push(duration_seconds.into());
+    }
+    if let Some(remaining_seconds) = update.remaining_seconds {
+        columns.push("remaining_seconds = ?");
+        values.push(remaining_seconds.into());
+    }
+    if let Some(is_running) = update.is_running {
+        columns.push("is_running = ?");
+        values.push(is_running.into());
+    }
+    if let Some(pomodoro_count) = update.pomodoro_count {
+        columns.push("pomodoro_count = ?");
+        values.push(pomodoro_count.into());
+    }
+
+    if !columns.is_empty() {
+        columns.push("updated_at = CURRENT_TIMESTAMP");
+        conn.execute(
+            &format!("UPDATE pomodoro_state SET {} WHERE id = 1", columns.join(", ")),
+            params_from_iter(values),
+        )?;
+    }
+
+    get_pomodoro_state(conn)
+}
+
+pub fn get_pomodoro_stats(conn: &Connection, range: StatsRange) -> rusqlite::Result<PomodoroStats> {
+    let filter = match range {
+        StatsRange::Today => "WHERE local_date(completed_at) = local_date()",
+        StatsRange::Week => "WHERE local_date(completed_at) >= DATE(local_date(), '-7 days')",
+        StatsRange::Month => "WHERE local_date(completed_at) >= DATE(local_date(), '-30 days')",
+        StatsRange::All => "",
+    };
+
+    conn.query_row(
+        &format!(
+            "SELECT
+                COUNT(*),
+                COALESCE(SUM(duration_minutes), 0),
+                COALESCE(SUM(CASE WHEN session_type = 'work' THEN 1 ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN session_type != 'work' THEN 1 ELSE 0 END), 0)
+             FROM pomodoro_sessions {}",
+            filter
+        ),
+        [],
+        |row| {
+            Ok(PomodoroStats {
+                total_sessions: row.get(0)?,
+                total_minutes: row.get(1)?,
+                work_sessions: row.get(2)?,
+                break_sessions: row.get(3)?,
+            })
+        },
+    )
+}
+
+// All-time work minutes per subject, only for subjects with sessions.
+pub fn get_subject_pomodoro_totals(
+    conn: &Connection,
+) -> rusqlite::Result<Vec<SubjectPomodoroTotal>> {
+    query_all(
+        conn,
+        "SELECT s.id, s.name, SUM(ps.duration_minutes) AS total_minutes, COUNT(ps.id) AS session_count
+         FROM pomodoro_sessions ps
+         INNER JOIN subjects s ON ps.subject_id = s.id
+         WHERE ps.session_type = 'work'
+         GROUP BY s.id, s.name
+         ORDER BY total_minutes DESC",
+        [],
+        |row| {
+            Ok(SubjectPomodoroTotal {
+                subject_id: row.get(0)?,
+                subject_name: row.get(1)?,
+                total_minutes: row.get(2)?,
+                session_count: row.get(3)?,
+            })
+        },
+    )
+}
+
+pub fn get_study_time_by_subject(
+    conn: &Connection,
+    subject_id: i64,
+    days: i64,
+) -> rusqlite::Result<StudyTime> {
+    conn.query_row(
+        "SELECT COUNT(*), COALESCE(SUM(duration_minutes), 0)
+         FROM pomodoro_sessions
+         WHERE subject_id = ?1
+           AND session_type = 'work'
+           AND local_date(completed_at) >= date(local_date(), '-' || ?2 || ' days')",
+        [subject_id, days],
+        |row| {
+            Ok(StudyTime {
+                session_count: row.get(0)?,
+                total_minutes: row.get(1)?,
+            })
+        },
+    )
+}
+
+pub fn get_all_subjects_study_time(
+    conn: &Connection,
+    days: i64,
+) -> rusqlite::Result<Vec<SubjectStudyTime>> {
+    query_all(
+        conn,
+        "SELECT s.id, s.name, COUNT(ps.id) AS session_count,
+                COALESCE(SUM(ps.duration_minutes), 0) AS total_minutes
+         FROM subjects s
+         LEFT JOIN pomodoro_sessions ps ON s.id = ps.subject_id AND ps.session_type = 'work'
+           AND local_date(ps.completed_at) >= date(local_date(), '-' || ?1 || ' days')
+         GROUP BY s.id, s.name
+         ORDER BY total_minutes DESC",
+        [days],
+        |row| {
+            Ok(SubjectStudyTime {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                session_count: row.get(2)?,
+                total_minutes: row.get(3)?,
+            })
+        },
+    )
+}
+
+pub fn get_today_pomodoro_summary(conn: &Connection) -> rusqlite::Result<PomodoroSummary> {
+    let (total_pomodoros, total_minutes) = conn.query_row(
+        "SELECT COUNT(*), COALESCE(SUM(duration_minutes), 0)
+         FROM pomodoro_sessions
+         WHERE session_type = 'work' AND local_date(completed_at) = local_date()",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    let subjects_studied = query_all(
+        conn,
+        "SELECT s.name
+         FROM pomodoro_sessions ps
+         JOIN subjects s ON ps.subject_id = s.id
+         WHERE ps.session_type = 'work' AND local_date(ps.completed_at) = local_date()
+         GROUP BY s.id
+         ORDER BY MAX(ps.completed_at) DESC",
+        [],
+        |row| row.get(0),
+    )?;
+
+    Ok(PomodoroSummary {
+        total_pomodoros,
+        total_minutes,
+        subjects_studied,
+    })
+}
+
+// Tags
+
+pub fn get_all_tags(conn: &Connection) -> rusqlite::Result<Vec<Tag>> {
+    query_all(
+        conn,
+        "SELECT * FROM tags ORDER BY usage_count DESC, name ASC",
+        [],
+        Tag::from_row,
+    )
+}
+
+// Looks up a tag by its normalized (trimmed, lowercase) name, creating it if
+// needed, and counts one more use of it.
+pub fn get_or_create_tag(conn: &Connection, name: &str) -> rusqlite::Result<i64> {
+    let name = name.trim().to_lowercase();
+
+    let existing: Option<i64> = conn
+        .query_row("SELECT id FROM tags WHERE name = ?1", [&name], |row| {
+            row.get(0)
+        })
+        .optional()?;
+
+    match existing {
+        Some(id) => {
+            conn.execute(
+                "UPDATE tags SET usage_count = usage_count + 1 WHERE id = ?1",
+                [id],
+            )?;
+            Ok(id)
+        }
+        None => {
+            conn.execute("INSERT INTO tags (name) VALUES (?1)", [&name])?;
+            Ok(conn.last_insert_rowid())
+        }
+    }
+}
+
+// Replaces the entry's tags; blank names are skipped.
+pub fn link_tags_to_entry(
+    conn: &Connection,
+    entry_id: i64,
+    tag_names: &[String],
+) -> rusqlite::Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM entry_tags WHERE entry_id = ?1", [entry_id])?;
+    for tag_name in tag_names.iter().filter(|name| !name.trim().is_empty()) {
+        let tag_id = get_or_create_tag(&tx, tag_name)?;
+        tx.execute(
+            "INSERT OR IGNORE INTO entry_tags (entry_id, tag_id) VALUES (?1, ?2)",
+            [entry_id, tag_id],
+        )?;
+    }
+    tx.commit()
+}
+
+pub fn get_entry_tags(conn: &Connection, entry_id: i64) -> rusqlite::Result<Vec<Tag>> {
+    query_all(
+        conn,
+        "SELECT t.* FROM tags t
+         INNER JOIN entry_tags et ON t.id = et.tag_id
+         WHERE et.entry_id = ?1
+         ORDER BY t.name",
+        [entry_id],
+        Tag::from_row,
+    )
+}
+
+// Daily activity and streaks
+
+// Recomputes the daily_activity row for `date` from pomodoro sessions and entries.
+pub fn update_daily_activity(conn: &Connection, date: &str) -> rusqlite::Result<()> {
+    let (pomodoro_count, study_minutes): (i64, i64) = conn.query_row(
+        "SELECT COUNT(*), COALESCE(SUM(duration_minutes), 0)
+         FROM pomodoro_sessions
+         WHERE session_type = 'work' AND local_date(completed_at) = ?1",
+        [date],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    let entry_count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM entries WHERE DATE(study_date) = ?1",
+        [date],
+        |row| row.get(0),
+    )?;
+
+    let subjects: Vec<String> = query_all(
+        conn,
+        "SELECT DISTINCT s.name
+         FROM subjects s
+         INNER JOIN entries e ON s.id = e.subject_id
+         WHERE DATE(e.study_date) = ?1",
+        [date],
+        |row| row.get(0),
+    )?;
+
+    conn.execute(
+        "INSERT INTO daily_activity (activity_date, study_minutes, pomodoro_count, entry_count, subjects_studied, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, CURRENT_TIMESTAMP)
+         ON CONFLICT(activity_date) DO UPDATE SET
+           study_minutes = excluded.study_minutes,
+           pomodoro_count = excluded.pomodoro_count,
+           entry_count = excluded.entry_count,
+           subjects_studied = excluded.subjects_studied,
+           updated_at = CURRENT_TIMESTAMP",
+        params![date, study_minutes, pomodoro_count, entry_count, subjects.join(",")],
+    )?;
+    Ok(())
+}
+
+pub fn get_daily_activities(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+) -> rusqlite::Result<Vec<DailyActivity>> {
+    query_all(
+        conn,
+        "SELECT * FROM daily_activity
+         WHERE activity_date BETWEEN ?1 AND ?2
+         ORDER BY activity_date DESC",
+        [start_date, end_date],
+        DailyActivity::from_row,
+    )
+}
+
+// Dates with any study time or new entries, most recent first.
+pub fn get_active_dates(conn: &Connection) -> rusqlite::Result<Vec<String>> {
+    query_all(
+        conn,
+        "SELECT activity_date FROM daily_activity
+         WHERE study_minutes > 0 OR entry_count > 0
+         ORDER BY activity_date DESC",
+        [],
+        |row| row.get(0),
+    )
+}
+
+// Streaks over the active dates, with today taken from the time zone
+// setting rather than the caller's clock
+pub fn streak_stats(conn: &Connection) -> rusqlite::Result<StreakStats> {
+    let dates: Vec<jiff::civil::Date> = get_active_dates(conn)?
+        .iter()
+        .filter_map(|date| date.parse().ok())
+        .collect();
+    let Some(latest) = dates.first() else {
+        return Ok(StreakStats::default());
+    };
+
+    // Lengths of the runs of consecutive days, newest run first
+    let mut runs = vec![1];
+    for pair in dates.windows(2) {
+        if pair[1].tomorrow().ok() == Some(pair[0]) {
+            *runs.last_mut().unwrap() += 1;
+        } else {
+            runs.push(1);
+        }
+    }
+
+    let today = clock::today_date(conn)?;
+    let current = *latest == today || latest.tomorrow().ok() == Some(today);
+    Ok(StreakStats {
+        current_streak: if current { runs[0] } else { 0 },
+        longest_streak: runs.iter().copied().max().unwrap_or(0),
+        total_active_days: dates.len() as i64,
+    })
+}
+
+// Records a streak milestone the first time it is reached. Returns whether
+// its celebration still has to be shown.
+pub fn record_milestone(conn: &Connection, current_streak: i64) -> rusqlite::Result<bool> {
+    if !MILESTONES.contains(&current_streak) {
+        return Ok(false);
+    }
+
+    let shown: Option<i64> = conn
+        .query_row(
+            "SELECT celebration_shown FROM milestone_celebrations WHERE milestone_days = ?1",
+            [current_streak],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    match shown {
+        Some(1) => Ok(false),
+        Some(_) => Ok(true),
+        None => {
+            conn.execute(
+                "INSERT INTO milestone_celebrations (milestone_days, achieved_at) VALUES (?1, CURRENT_TIMESTAMP)",
+                [current_streak],
+            )?;
+            Ok(true)
+        }
+    }
+}
+
+pub fn mark_milestone_shown(conn: &Connection, milestone_days: i64) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE milestone_celebrations
+         SET celebration_shown = 1, shown_at = CURRENT_TIMESTAMP
+         WHERE milestone_days = ?1",
+        [milestone_days],
+    )?;
+    Ok(())
+}
+
+pub fn get_milestone_achievements(
+    conn: &Connection,
+) -> rusqlite::Result<Vec<MilestoneCelebration>> {
+    query_all(
+        conn,
+        "SELECT * FROM milestone_celebrations
+         WHERE celebration_shown = 1
+         ORDER BY milestone_days ASC",
+        [],
+        MilestoneCelebration::from_row,
+    )
+}
@@ -0,0 +1,77 @@
+use crate::models::{RetentionStats, ReviewLog, ReviewLogFilters};
+use crate::scheduler::PASSING_QUALITY;
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection};
+
+// Every completed revision gets a row in review_log: graded ones from
+// scheduler::grade_revision, with the entry's scheduler state before and
+// after, and plain completions from queries::complete_revision. FSRS
+// histories, retention and undoing a grade all read from it.
+
+pub fn get_review(conn: &Connection, id: i64) -> rusqlite::Result<ReviewLog> {
+    conn.query_row("SELECT * FROM review_log WHERE id = ?1", [id], ReviewLog::from_row)
+}
+
+// WHERE clause over review_log `l` joined to its entry `e`
+fn filter_clause(filters: &ReviewLogFilters) -> (String, Vec<Value>) {
+    let mut sql = String::from(" WHERE 1=1");
+    let mut values: Vec<Value> = Vec::new();
+
+    if let Some(entry_id) = filters.entry_id {
+        sql.push_str(" AND l.entry_id = ?");
+        values.push(entry_id.into());
+    }
+    if let Some(subject_id) = filters.subject_id {
+        sql.push_str(" AND e.subject_id = ?");
+        values.push(subject_id.into());
+    }
+    if let Some(start_date) = &filters.start_date {
+        sql.push_str(" AND l.reviewed_date >= ?");
+        values.push(start_date.clone().into());
+    }
+    if let Some(end_date) = &filters.end_date {
+        sql.push_str(" AND l.reviewed_date <= ?");
+        values.push(end_date.clone().into());
+    }
+    (sql, values)
+}
+
+// Newest first
+pub fn get_review_log(conn: &Connection, filters: &ReviewLogFilters) -> rusqlite::Result<Vec<ReviewLog>> {
+    let (clause, mut values) = filter_clause(filters);
+    let mut sql = format!("SELECT l.* FROM review_log l JOIN entries e ON e.id = l.entry_id{}", clause);
+    sql.push_str(" ORDER BY l.id DESC");
+    if let Some(limit) = filters.limit {
+        sql.push_str(" LIMIT ?");
+        values.push(limit.into());
+    }
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(values), ReviewLog::from_row)?;
+    rows.collect()
+}
+
+pub fn retention_stats(conn: &Connection, filters: &ReviewLogFilters) -> rusqlite::Result<RetentionStats> {
+    let (clause, mut values) = filter_clause(filters);
+    let sql = format!(
+        "SELECT COUNT(*),
+                COALESCE(SUM(l.quality >= ?), 0),
+                AVG(MAX(JULIANDAY(l.reviewed_date) - JULIANDAY(l.scheduled_date), 0)),
+                AVG(l.duration_seconds)
+         FROM review_log l JOIN entries e ON e.id = l.entry_id{} AND l.quality IS NOT NULL",
+        clause
+    );
+    values.insert(0, i64::from(PASSING_QUALITY).into());
+
+    conn.query_row(&sql, params_from_iter(values), |row| {
+        let reviews: i64 = row.get(0)?;
+        let recalled: i64 = row.get(1)?;
+        Ok(RetentionStats {
+            reviews,
+            recalled,
+            retention: (reviews > 0).then(|| recalled as f64 / reviews as f64),
+            average_days_late: row.get(2)?,
+            average_seconds: row.get(3)?,
+        })
+    })
+}
@@ -0,0 +1,86 @@
+use base64::Engine;
+use rusqlite::types::ValueRef;
+use rusqlite::Statement;
+use serde::Serialize;
+
+// JSON conversion for db_select rows. Values follow their SQLite storage
+// class rather than trying Rust types in turn, so BLOBs and mixed-type
+// columns survive the trip to the frontend. The schema declares its flags
+// (is_running, is_completed, celebration_shown) as INTEGER, so they come
+// back as 0 and 1, never true/false.
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnKind {
+    Integer,
+    Real,
+    Text,
+    Blob,
+    // Calendar dates ("YYYY-MM-DD") and timestamps ("YYYY-MM-DD HH:MM:SS",
+    // UTC for CURRENT_TIMESTAMP defaults), returned as stored
+    Date,
+    Datetime,
+    // Expressions and columns without a declared type
+    Any,
+}
+
+impl ColumnKind {
+    // Mirrors SQLite's type affinity rules, with the date types picked out
+    // before they fall through to NUMERIC.
+    fn from_decl_type(decl_type: Option<&str>) -> Self {
+        let decl = match decl_type {
+            Some(decl) => decl.to_ascii_uppercase(),
+            None => return ColumnKind::Any,
+        };
+        if decl.contains("DATETIME") || decl.contains("TIMESTAMP") {
+            ColumnKind::Datetime
+        } else if decl.contains("DATE") {
+            ColumnKind::Date
+        } else if decl.contains("INT") {
+            ColumnKind::Integer
+        } else if decl.contains("CHAR") || decl.contains("CLOB") || decl.contains("TEXT") {
+            ColumnKind::Text
+        } else if decl.is_empty() || decl.contains("BLOB") {
+            ColumnKind::Blob
+        } else if decl.contains("REAL") || decl.contains("FLOA") || decl.contains("DOUB") {
+            ColumnKind::Real
+        } else {
+            ColumnKind::Any
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnInfo {
+    pub name: String,
+    pub decl_type: Option<String>,
+    pub kind: ColumnKind,
+}
+
+pub fn describe_columns(stmt: &Statement) -> Vec<ColumnInfo> {
+    stmt.columns()
+        .iter()
+        .map(|col| ColumnInfo {
+            name: col.name().to_string(),
+            decl_type: col.decl_type().map(str::to_string),
+            kind: ColumnKind::from_decl_type(col.decl_type()),
+        })
+        .collect()
+}
+
+// BLOBs are base64 encoded (standard alphabet, padded); everything else
+// keeps its stored value.
+pub fn value_to_json(value: ValueRef) -> serde_json::Value {
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(i) => serde_json::Value::Number(i.into()),
+        ValueRef::Real(f) => serde_json::Number::from_f64(f)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        ValueRef::Text(bytes) => serde_json::Value::String(String::from_utf8_lossy(bytes).into_owned()),
+        ValueRef::Blob(bytes) => {
+            serde_json::Value::String(base64::engine::general_purpose::STANDARD.encode(bytes))
+        }
+    }
+}
@@ -0,0 +1,426 @@
+use crate::error::AppError;
+use crate::fsrs::{self, Fsrs, MemoryState, Optimization, Review};
+use crate::models::{Entry, Revision, RevisionGrade, SchedulerState};
+use crate::{clock, db, load_balance, queries, review_log};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// Revision scheduling. A new entry gets one revision per interval in its
+// fixed plan (see queries::create_entry); once a revision is graded, the
+// scheduler takes over and the entry only ever has one open revision, due
+// when the scheduler says.
+//
+// Two schedulers are available, chosen by the `scheduler` setting: SM-2
+// (the default) and FSRS. Every grade updates the state of both, so
+// switching takes effect from the next grade without losing anything.
+//
+// Grades use SM-2's 0-5 quality scale:
+//   0  total blackout
+//   1  wrong, but the answer seemed familiar
+//   2  wrong, but the answer was easy to recall once seen
+//   3  right, with serious difficulty
+//   4  right, after some hesitation
+//   5  perfect recall
+
+pub const MAX_QUALITY: u8 = 5;
+// Grades below this count as a lapse and restart the repetitions
+pub const PASSING_QUALITY: u8 = 3;
+
+pub const SCHEDULER_SETTING: &str = "scheduler";
+pub const FSRS_PARAMETERS_SETTING: &str = "fsrs_parameters";
+pub const FSRS_RETENTION_SETTING: &str = "fsrs_desired_retention";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Sm2,
+    Fsrs,
+}
+
+impl Algorithm {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Algorithm::Sm2 => "sm2",
+            Algorithm::Fsrs => "fsrs",
+        }
+    }
+
+    pub fn from_settings(conn: &Connection) -> rusqlite::Result<Self> {
+        Ok(match queries::get_setting(conn, SCHEDULER_SETTING)?.as_deref() {
+            Some("fsrs") => Algorithm::Fsrs,
+            _ => Algorithm::Sm2,
+        })
+    }
+}
+
+pub const DEFAULT_EASE_FACTOR: f64 = 2.5;
+pub const MIN_EASE_FACTOR: f64 = 1.3;
+
+// An entry's SM-2 state, stored in its ease_factor, repetitions and
+// interval_days columns.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Sm2State {
+    pub ease_factor: f64,
+    // Successful reviews in a row
+    pub repetitions: i64,
+    // Days from the last review to the next one
+    pub interval_days: i64,
+}
+
+impl Default for Sm2State {
+    fn default() -> Self {
+        Sm2State {
+            ease_factor: DEFAULT_EASE_FACTOR,
+            repetitions: 0,
+            interval_days: 0,
+        }
+    }
+}
+
+impl Sm2State {
+    pub fn of(entry: &Entry) -> Self {
+        Sm2State {
+            ease_factor: entry.ease_factor,
+            repetitions: entry.repetitions,
+            interval_days: entry.interval_days,
+        }
+    }
+}
+
+// The state after a review graded `quality`. Same rules as the SM-2
+// calculateNextReview in src/utils/spacedRepetition.ts: the first two
+// successful reviews are 1 and 6 days apart, later ones multiply the last
+// interval by the ease factor, and a lapse starts over at 1 day. As there,
+// the interval is multiplied by the new ease factor before it is rounded to
+// two decimals for storing.
+pub fn sm2(state: Sm2State, quality: u8) -> Sm2State {
+    let miss = f64::from(MAX_QUALITY - quality.min(MAX_QUALITY));
+    let unrounded = (state.ease_factor + (0.1 - miss * (0.08 + miss * 0.02))).max(MIN_EASE_FACTOR);
+    let ease_factor = (unrounded * 100.0).round() / 100.0;
+
+    if quality < PASSING_QUALITY {
+        return Sm2State {
+            ease_factor,
+            repetitions: 0,
+            interval_days: 1,
+        };
+    }
+
+    let repetitions = state.repetitions + 1;
+    let interval_days = match repetitions {
+        1 => 1,
+        2 => 6,
+        _ => ((state.interval_days as f64 * unrounded).round() as i64).max(1),
+    };
+    Sm2State {
+        ease_factor,
+        repetitions,
+        interval_days,
+    }
+}
+
+pub fn fsrs_from_settings(conn: &Connection) -> rusqlite::Result<Fsrs> {
+    Ok(Fsrs::from_setting(queries::get_setting(conn, FSRS_PARAMETERS_SETTING)?.as_deref()))
+}
+
+// Desired probability of recall when an FSRS review comes due. Values
+// outside 70-97% make for absurd workloads or intervals and are clamped.
+pub fn desired_retention(conn: &Connection) -> rusqlite::Result<f64> {
+    let setting = queries::get_setting(conn, FSRS_RETENTION_SETTING)?;
+    let retention = setting.and_then(|value| value.trim().parse::<f64>().ok());
+    Ok(retention.filter(|r| r.is_finite()).unwrap_or(fsrs::DEFAULT_RETENTION).clamp(0.7, 0.97))
+}
+
+// Review days are counted in days since 1970-01-01
+pub(crate) fn today_number(conn: &Connection) -> rusqlite::Result<i64> {
+    conn.query_row("SELECT STRFTIME('%s', local_date()) / 86400", [], |row| row.get(0))
+}
+
+// Review histories for FSRS: studying the entry is the first review, rated
+// "good", followed by every review in review_log. Revisions completed
+// without a grade count as "good" too. Keyed by entry id; `entry_id` limits
+// it to one entry.
+fn histories(conn: &Connection, entry_id: Option<i64>) -> rusqlite::Result<BTreeMap<i64, Vec<Review>>> {
+    let mut histories: BTreeMap<i64, Vec<Review>> = BTreeMap::new();
+    let mut stmt = conn.prepare(
+        "SELECT entry_id, day, quality FROM (
+             SELECT id AS entry_id, STRFTIME('%s', study_date) / 86400 AS day, NULL AS quality, 0 AS review_id
+             FROM entries WHERE ?1 IS NULL OR id = ?1
+             UNION ALL
+             SELECT entry_id, STRFTIME('%s', reviewed_date) / 86400, quality, id
+             FROM review_log WHERE ?1 IS NULL OR entry_id = ?1
+         )
+         WHERE day IS NOT NULL
+         ORDER BY entry_id, review_id",
+    )?;
+    let mut rows = stmt.query([entry_id])?;
+    while let Some(row) = rows.next()? {
+        let quality: Option<u8> = row.get(2)?;
+        histories.entry(row.get(0)?).or_default().push(Review {
+            day: row.get(1)?,
+            rating: quality.map_or(fsrs::Rating::Good, fsrs::rating),
+        });
+    }
+    Ok(histories)
+}
+
+fn store_memory_state(conn: &Connection, entry_id: i64, state: MemoryState, last_day: i64) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE entries SET stability = ?2, difficulty = ?3, last_review_date = DATE(?4 * 86400, 'unixepoch')
+         WHERE id = ?1",
+        params![entry_id, state.stability, state.difficulty, last_day],
+    )?;
+    Ok(())
+}
+
+// Recomputes every entry's FSRS stability and difficulty from its history
+// with the current parameters. Seeds entries that have never been graded
+// under FSRS, and refreshes all of them after the parameters change.
+pub fn rebuild_memory_states(conn: &Connection) -> rusqlite::Result<usize> {
+    let fsrs = fsrs_from_settings(conn)?;
+    let histories = histories(conn, None)?;
+    for (entry_id, reviews) in &histories {
+        if let (Some(state), Some(last)) = (fsrs.replay(reviews), reviews.last()) {
+            store_memory_state(conn, *entry_id, state, last.day)?;
+        }
+    }
+    Ok(histories.len())
+}
+
+// Fits the FSRS parameters to the review history without saving them. It
+// takes seconds on a long history and only reads, so the app runs it on a
+// read connection and keeps the writer free.
+pub fn fit_fsrs(conn: &Connection) -> Result<Optimization, AppError> {
+    let histories: Vec<Vec<Review>> = histories(conn, None)?.into_values().collect();
+    fsrs::optimize(&histories).ok_or_else(|| {
+        AppError::validation(format!(
+            "At least {} completed revisions are needed to optimize the FSRS parameters",
+            fsrs::MIN_REVIEWS_TO_OPTIMIZE
+        ))
+    })
+}
+
+// Fits the FSRS parameters to the review history, saves them and
+// recomputes every entry's memory state with them.
+pub fn optimize_fsrs(conn: &Connection) -> Result<Optimization, AppError> {
+    let optimization = fit_fsrs(conn)?;
+    save_fsrs_parameters(conn, &optimization)?;
+    Ok(optimization)
+}
+
+// Saves parameters from fit_fsrs and recomputes every entry's memory state
+// with them.
+pub fn save_fsrs_parameters(conn: &Connection, optimization: &Optimization) -> Result<(), AppError> {
+    let fitted = Fsrs {
+        w: optimization.parameters.as_slice().try_into().expect("optimize returns every weight"),
+    };
+    let tx = conn.unchecked_transaction()?;
+    queries::update_setting(&tx, FSRS_PARAMETERS_SETTING, &fitted.to_setting())?;
+    rebuild_memory_states(&tx)?;
+    tx.commit()?;
+    Ok(())
+}
+
+// Goes back to the published default parameters
+pub fn reset_fsrs(conn: &Connection) -> Result<(), AppError> {
+    let tx = conn.unchecked_transaction()?;
+    queries::update_setting(&tx, FSRS_PARAMETERS_SETTING, "")?;
+    rebuild_memory_states(&tx)?;
+    tx.commit()?;
+    Ok(())
+}
+
+// Completes an open revision with a 0-5 grade, advances the entry's SM-2
+// and FSRS state and replaces the entry's other open revisions with the
+// next one, due when the selected scheduler says. The grading is recorded
+// in review_log together with `duration_seconds`, the time the review took
+// when the caller knows it.
+pub fn grade_revision(
+    conn: &Connection,
+    id: i64,
+    quality: u8,
+    duration_seconds: Option<u32>,
+) -> Result<RevisionGrade, AppError> {
+    if quality > MAX_QUALITY {
+        return Err(AppError::validation(format!("Grade must be between 0 and {}", MAX_QUALITY)));
+    }
+    // Everything is read inside the transaction, so a second grading of the
+    // same revision from another window or the CLI waits and then fails
+    let tx = db::write_transaction(conn)?;
+    let revision = queries::get_revision(&tx, id)?;
+    if !matches!(revision.status.as_str(), "pending" | "overdue") {
+        return Err(AppError::validation(format!("Revision {} is already {}", id, revision.status)));
+    }
+    let entry = queries::get_entry(&tx, revision.entry_id)?.entry;
+    let next = sm2(Sm2State::of(&entry), quality);
+
+    // FSRS state is replayed from the history rather than stepped from the
+    // stored one, so revisions completed without a grade are accounted for
+    let fsrs = fsrs_from_settings(&tx)?;
+    let today = today_number(&tx)?;
+    let mut history = histories(&tx, Some(entry.id))?.remove(&entry.id).unwrap_or_default();
+    history.push(Review {
+        day: today,
+        rating: fsrs::rating(quality),
+    });
+    let memory = fsrs.replay(&history).expect("history has at least today's review");
+
+    let algorithm = Algorithm::from_settings(&tx)?;
+    let interval_days = match algorithm {
+        Algorithm::Sm2 => next.interval_days,
+        Algorithm::Fsrs => fsrs::interval(memory.stability, desired_retention(&tx)?),
+    };
+
+    let changed = tx.execute(
+        "UPDATE revisions
+         SET status = 'completed', quality = ?2, completed_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?1 AND status IN ('pending', 'overdue')",
+        params![id, quality],
+    )?;
+    if changed == 0 {
+        return Err(AppError::validation(format!("Revision {} is no longer open", id)));
+    }
+    tx.execute(
+        "INSERT INTO activity_log (entry_id, activity_type, activity_date, details)
+         VALUES (?1, 'revision_completed', local_date(), ?2)",
+        params![entry.id, grade_details(revision.interval_days, quality)],
+    )?;
+    tx.execute(
+        "UPDATE entries SET ease_factor = ?2, repetitions = ?3, interval_days = ?4 WHERE id = ?1",
+        params![entry.id, next.ease_factor, next.repetitions, next.interval_days],
+    )?;
+    store_memory_state(&tx, entry.id, memory, today)?;
+    tx.execute(
+        "DELETE FROM revisions WHERE entry_id = ?1 AND status IN ('pending', 'overdue')",
+        [entry.id],
+    )?;
+    // interval_days on a revision counts from the study date, like the
+    // fixed plan's "Day 7 revision". The entry's old open revisions are
+    // gone by now, so load balancing does not count them.
+    let due_date = load_balance::due_date(&tx, &clock::today(&tx)?, interval_days)?;
+    tx.execute(
+        "INSERT INTO revisions (entry_id, interval_days, due_date, status)
+         VALUES (?1, CAST(JULIANDAY(?3) - JULIANDAY(?2) AS INTEGER), ?3, 'pending')",
+        params![entry.id, entry.study_date, due_date],
+    )?;
+    let next_id = tx.last_insert_rowid();
+
+    let next_revision = queries::get_revision(&tx, next_id)?;
+    let updated = queries::get_entry(&tx, entry.id)?.entry;
+    tx.execute(
+        "INSERT INTO review_log (
+             revision_id, entry_id, quality, duration_seconds, scheduler, scheduled_date, reviewed_date,
+             state_before, state_after, next_due_date
+         )
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, local_date(), ?7, ?8, ?9)",
+        params![
+            id,
+            entry.id,
+            quality,
+            duration_seconds,
+            algorithm.as_str(),
+            revision.due_date,
+            state_json(&SchedulerState::of(&entry))?,
+            state_json(&SchedulerState::of(&updated))?,
+            next_revision.due_date,
+        ],
+    )?;
+    let review_id = tx.last_insert_rowid();
+    tx.commit()?;
+
+    Ok(RevisionGrade {
+        revision: queries::get_revision(conn, id)?,
+        next_revision,
+        entry: updated,
+        review: review_log::get_review(conn, review_id)?,
+    })
+}
+
+// activity_log details of a grading
+fn grade_details(interval_days: i64, quality: impl std::fmt::Display) -> String {
+    format!("Day {} revision, graded {}/{}", interval_days, quality, MAX_QUALITY)
+}
+
+fn state_json(state: &SchedulerState) -> Result<String, AppError> {
+    serde_json::to_string(state).map_err(|e| AppError::internal(e.to_string()))
+}
+
+// Takes back a grading: the entry gets its scheduler state from before it,
+// the revision is open again and the revision scheduled by the grade is
+// removed, so the revision can be graded again. Only an entry's latest
+// review can be undone. Revisions of the fixed plan superseded by the grade
+// are not brought back, as grading again would supersede them anyway.
+pub fn undo_review(conn: &Connection, review_id: i64) -> Result<Revision, AppError> {
+    let tx = db::write_transaction(conn)?;
+    let review = review_log::get_review(&tx, review_id)?;
+    let (Some(quality), Some(before), Some(revision_id)) = (review.quality, &review.state_before, review.revision_id)
+    else {
+        return Err(AppError::validation("Only graded revisions that still exist can be undone"));
+    };
+    let latest: i64 = tx.query_row(
+        "SELECT MAX(id) FROM review_log WHERE entry_id = ?1",
+        [review.entry_id],
+        |row| row.get(0),
+    )?;
+    if latest != review_id {
+        return Err(AppError::validation("Only the latest review of an entry can be undone"));
+    }
+    let revision = queries::get_revision(&tx, revision_id)?;
+
+    tx.execute(
+        "UPDATE entries
+         SET ease_factor = ?2, repetitions = ?3, interval_days = ?4,
+             stability = ?5, difficulty = ?6, last_review_date = ?7
+         WHERE id = ?1",
+        params![
+            review.entry_id,
+            before.ease_factor,
+            before.repetitions,
+            before.interval_days,
+            before.stability,
+            before.difficulty,
+            before.last_review_date,
+        ],
+    )?;
+    tx.execute(
+        "DELETE FROM revisions WHERE entry_id = ?1 AND status IN ('pending', 'overdue')",
+        [review.entry_id],
+    )?;
+    tx.execute(
+        "UPDATE revisions
+         SET status = CASE WHEN due_date < local_date() THEN 'overdue' ELSE 'pending' END,
+             quality = NULL, completed_at = NULL, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?1",
+        [revision_id],
+    )?;
+    tx.execute(
+        "DELETE FROM activity_log WHERE id = (
+             SELECT id FROM activity_log
+             WHERE entry_id = ?1 AND activity_type = 'revision_completed' AND details = ?2
+             ORDER BY id DESC LIMIT 1
+         )",
+        params![review.entry_id, grade_details(revision.interval_days, quality)],
+    )?;
+    tx.execute("DELETE FROM review_log WHERE id = ?1", [review_id])?;
+    tx.commit()?;
+
+    Ok(queries::get_revision(conn, revision_id)?)
+}
+
+// Reopens a completed revision. A graded one is reopened by undoing its
+// grade, so the scheduler state and the revision it scheduled go back too;
+// that needs the grade to be the entry's latest review.
+pub fn uncomplete_revision(conn: &Connection, id: i64) -> Result<Revision, AppError> {
+    let revision = queries::get_revision(conn, id)?;
+    if revision.quality.is_none() {
+        queries::uncomplete_revision(conn, id)?;
+        return Ok(queries::get_revision(conn, id)?);
+    }
+    let review_id: Option<i64> = conn.query_row(
+        "SELECT MAX(id) FROM review_log WHERE revision_id = ?1 AND quality IS NOT NULL",
+        [id],
+        |row| row.get(0),
+    )?;
+    let review_id = review_id.ok_or_else(|| AppError::validation("This grade has no review to undo"))?;
+    undo_review(conn, review_id)
+}
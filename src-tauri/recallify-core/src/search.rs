@@ -0,0 +1,207 @@
+use crate::models::{SearchFilters, SearchHit};
+use rusqlite::functions::FunctionFlags;
+use rusqlite::{params, Connection};
+
+// Full-text search over entries. The entry_search FTS5 table (migration 6)
+// has one row per entry, with the entry id as rowid, holding plain-text
+// copies of the notes, recall notes, topics and tag names. study_notes holds
+// TipTap HTML, which index_entry and index_missing reduce to text through
+// the strip_html SQL function registered on every connection by
+// db::open_connection. The triggers left since migration 11 are plain SQL,
+// so any tool can write to the database: they keep tag names current and
+// drop the rows of deleted or edited entries. The queries that write
+// entries index them again, the raw SQL bridge indexes whatever is missing
+// after each write, and db::open catches up on changes made elsewhere.
+
+// Tags that end a line or block of text; stripping them must leave a space
+// so words on either side do not run together.
+const BLOCK_TAGS: [&str; 18] = [
+    "p", "br", "div", "li", "ul", "ol", "h1", "h2", "h3", "h4", "h5", "h6", "blockquote", "pre", "tr", "td", "th",
+    "hr",
+];
+
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => {
+            let code = entity.strip_prefix('#')?;
+            let value = match code.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => code.parse().ok()?,
+            };
+            char::from_u32(value)
+        }
+    }
+}
+
+// Plain text of an HTML fragment: tags dropped, entities decoded and
+// whitespace collapsed. Good enough for what TipTap produces; not a
+// general HTML parser.
+pub fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '<' => match rest.find('>') {
+                Some(end) => {
+                    let tag = rest[1..end].trim_start_matches('/');
+                    let name: String = tag
+                        .chars()
+                        .take_while(|c| c.is_ascii_alphanumeric())
+                        .collect::<String>()
+                        .to_ascii_lowercase();
+                    if BLOCK_TAGS.contains(&name.as_str()) {
+                        text.push(' ');
+                    }
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    text.push('<');
+                    rest = &rest[1..];
+                }
+            },
+            '&' => {
+                let entity = rest[1..].find(';').filter(|&len| len <= 10).map(|len| &rest[1..len + 1]);
+                match entity.and_then(decode_entity) {
+                    Some(decoded) => {
+                        text.push(decoded);
+                        rest = &rest[entity.map_or(0, str::len) + 2..];
+                    }
+                    None => {
+                        text.push('&');
+                        rest = &rest[1..];
+                    }
+                }
+            }
+            _ => {
+                text.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub fn register_functions(conn: &Connection) -> rusqlite::Result<()> {
+    conn.create_scalar_function(
+        "strip_html",
+        1,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| Ok(ctx.get::<Option<String>>(0)?.map(|html| strip_html(&html))),
+    )
+}
+
+const INDEX_ENTRIES: &str = "
+    INSERT INTO entry_search (rowid, notes, recall_notes, topics, tags)
+    SELECT e.id, strip_html(e.study_notes), strip_html(e.morning_recall_notes), e.topics,
+           (SELECT group_concat(t.name, ' ') FROM entry_tags et JOIN tags t ON t.id = et.tag_id
+            WHERE et.entry_id = e.id)
+    FROM entries e";
+
+// Refills the index from scratch, e.g. after a salvage that skipped it.
+pub fn rebuild_index(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM entry_search", [])?;
+    conn.execute(INDEX_ENTRIES, [])?;
+    Ok(())
+}
+
+pub fn index_entry(conn: &Connection, entry_id: i64) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM entry_search WHERE rowid = ?1", [entry_id])?;
+    conn.execute(&format!("{} WHERE e.id = ?1", INDEX_ENTRIES), [entry_id])?;
+    Ok(())
+}
+
+// Indexes the entries without a row, those written since by something that
+// does not index them itself.
+pub fn index_missing(conn: &Connection) -> rusqlite::Result<usize> {
+    conn.execute(
+        &format!("{} WHERE e.id NOT IN (SELECT rowid FROM entry_search)", INDEX_ENTRIES),
+        [],
+    )
+}
+
+// Turns what the user typed into an FTS5 query. Words match as prefixes and
+// must all appear; "quoted text" matches as a phrase. Everything is quoted,
+// so FTS5 operators and punctuation in the input cannot cause syntax errors.
+pub fn fts_query(input: &str) -> Option<String> {
+    let mut terms = Vec::new();
+    for (i, part) in input.split('"').enumerate() {
+        let quoted = |text: &str| format!("\"{}\"", text.replace('"', "\"\""));
+        if i % 2 == 1 {
+            let phrase = part.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !phrase.is_empty() {
+                terms.push(quoted(&phrase));
+            }
+        } else {
+            for word in part.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+                terms.push(format!("{}*", quoted(word)));
+            }
+        }
+    }
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+pub const DEFAULT_LIMIT: i64 = 50;
+
+// Snippet markers around each matched term. The frontend splits on these
+// instead of rendering the snippet as HTML.
+pub const HIGHLIGHT_START: &str = "<mark>";
+pub const HIGHLIGHT_END: &str = "</mark>";
+
+// Best matches first. Topic and tag matches weigh more than a word buried in
+// long notes. An empty query returns nothing.
+pub fn search_entries(conn: &Connection, query: &str, filters: &SearchFilters) -> rusqlite::Result<Vec<SearchHit>> {
+    let Some(query) = fts_query(query) else {
+        return Ok(Vec::new());
+    };
+    let tag = filters.tag.as_deref().map(|tag| tag.trim().to_lowercase());
+
+    let mut stmt = conn.prepare(
+        "SELECT e.id, e.subject_id, s.name, e.study_date, e.topics,
+                snippet(entry_search, -1, ?7, ?8, '…', 16),
+                bm25(entry_search, 1.0, 1.0, 3.0, 3.0) AS score
+         FROM entry_search
+         JOIN entries e ON e.id = entry_search.rowid
+         JOIN subjects s ON s.id = e.subject_id
+         WHERE entry_search MATCH ?1
+           AND (?2 IS NULL OR e.subject_id = ?2)
+           AND (?3 IS NULL OR EXISTS (
+                SELECT 1 FROM entry_tags et JOIN tags t ON t.id = et.tag_id
+                WHERE et.entry_id = e.id AND t.name = ?3))
+           AND (?4 IS NULL OR e.study_date >= ?4)
+           AND (?5 IS NULL OR e.study_date <= ?5)
+         ORDER BY score
+         LIMIT ?6",
+    )?;
+    let hits = stmt.query_map(
+        params![
+            query,
+            filters.subject_id,
+            tag,
+            filters.start_date,
+            filters.end_date,
+            filters.limit.unwrap_or(DEFAULT_LIMIT),
+            HIGHLIGHT_START,
+            HIGHLIGHT_END,
+        ],
+        |row| {
+            Ok(SearchHit {
+                entry_id: row.get(0)?,
+                subject_id: row.get(1)?,
+                subject_name: row.get(2)?,
+                study_date: row.get(3)?,
+                topics: row.get(4)?,
+                snippet: row.get(5)?,
+                score: row.get(6)?,
+            })
+        },
+    )?;
+    hits.collect()
+}
@@ -0,0 +1,79 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+// Storage for the single Spotify login (spotify_auth has at most one row,
+// id 1). Tokens are refreshed by the frontend; this only keeps them.
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotifyAuth {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_at: String,
+    pub device_id: Option<String>,
+    pub last_playlist_uri: Option<String>,
+    pub last_track_uri: Option<String>,
+    pub last_position_ms: i64,
+    pub is_premium: i64,
+}
+
+pub fn get_auth(conn: &Connection) -> rusqlite::Result<Option<SpotifyAuth>> {
+    conn.query_row(
+        "SELECT access_token, refresh_token, expires_at, device_id, last_playlist_uri, last_track_uri, last_position_ms, is_premium FROM spotify_auth WHERE id = 1",
+        [],
+        |row| {
+            Ok(SpotifyAuth {
+                access_token: row.get(0)?,
+                refresh_token: row.get(1)?,
+                expires_at: row.get(2)?,
+                device_id: row.get(3)?,
+                last_playlist_uri: row.get(4)?,
+                last_track_uri: row.get(5)?,
+                last_position_ms: row.get(6)?,
+                is_premium: row.get(7)?,
+            })
+        },
+    )
+    .optional()
+}
+
+// Replaces the login, clearing the saved device and playback state.
+pub fn save_auth(
+    conn: &Connection,
+    access_token: &str,
+    refresh_token: &str,
+    expires_at: &str,
+    is_premium: i64,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO spotify_auth (id, access_token, refresh_token, expires_at, is_premium, updated_at) VALUES (1, ?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)",
+        params![access_token, refresh_token, expires_at, is_premium],
+    )?;
+    Ok(())
+}
+
+pub fn update_device(conn: &Connection, device_id: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE spotify_auth SET device_id = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = 1",
+        [device_id],
+    )?;
+    Ok(())
+}
+
+pub fn save_playback_state(
+    conn: &Connection,
+    playlist_uri: Option<&str>,
+    track_uri: Option<&str>,
+    position_ms: i64,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE spotify_auth SET last_playlist_uri = ?1, last_track_uri = ?2, last_position_ms = ?3, updated_at = CURRENT_TIMESTAMP WHERE id = 1",
+        params![playlist_uri, track_uri, position_ms],
+    )?;
+    Ok(())
+}
+
+pub fn logout(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM spotify_auth WHERE id = 1", [])?;
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use crate::error::AppError;
+use rusqlite::hooks::{AuthAction, AuthContext, Authorization};
+use rusqlite::{Connection, Statement};
+use std::sync::{Arc, Mutex};
+
+// Restrictions for the raw SQL bridge (db_select, db_execute, db_transaction).
+// SQLite consults the authorizer while a statement is being prepared, so a
+// refused statement never runs. ATTACH/DETACH are refused as well because
+// SQLite counts them as read-only, pragma assignments because they can
+// switch off connection settings such as foreign key enforcement, and
+// BEGIN, COMMIT, ROLLBACK and savepoints because a COMMIT in the middle of
+// a db_transaction batch would apply its first half on its own.
+
+fn refusal(action: &AuthAction) -> Option<String> {
+    let what = match action {
+        AuthAction::CreateIndex { .. }
+        | AuthAction::CreateTable { .. }
+        | AuthAction::CreateTempIndex { .. }
+        | AuthAction::CreateTempTable { .. }
+        | AuthAction::CreateTempTrigger { .. }
+        | AuthAction::CreateTempView { .. }
+        | AuthAction::CreateTrigger { .. }
+        | AuthAction::CreateView { .. }
+        | AuthAction::CreateVtable { .. } => "CREATE statements are",
+        AuthAction::DropIndex { .. }
+        | AuthAction::DropTable { .. }
+        | AuthAction::DropTempIndex { .. }
+        | AuthAction::DropTempTable { .. }
+        | AuthAction::DropTempTrigger { .. }
+        | AuthAction::DropTempView { .. }
+        | AuthAction::DropTrigger { .. }
+        | AuthAction::DropView { .. }
+        | AuthAction::DropVtable { .. } => "DROP statements are",
+        AuthAction::AlterTable { .. } => "ALTER TABLE is",
+        AuthAction::Attach { .. } => "ATTACH is",
+        AuthAction::Detach { .. } => "DETACH is",
+        AuthAction::Pragma {
+            pragma_value: Some(_),
+            ..
+        } => "PRAGMA assignments are",
+        AuthAction::Transaction { .. } => "Transaction statements are",
+        AuthAction::Savepoint { .. } => "Savepoints are",
+        _ => return None,
+    };
+    Some(format!("{} not allowed through the raw SQL bridge", what))
+}
+
+// Installs the authorizer for as long as it is alive. The connection is
+// shared with the typed commands and migrations, so it must not outlive
+// the raw SQL call that created it.
+pub struct SchemaGuard<'a> {
+    conn: &'a Connection,
+    refused: Arc<Mutex<Option<String>>>,
+}
+
+impl<'a> SchemaGuard<'a> {
+    pub fn install(conn: &'a Connection) -> Self {
+        let refused = Arc::new(Mutex::new(None));
+        let slot = Arc::clone(&refused);
+        conn.authorizer(Some(move |ctx: AuthContext<'_>| match refusal(&ctx.action) {
+            Some(msg) => {
+                if let Ok(mut slot) = slot.lock() {
+                    slot.get_or_insert(msg);
+                }
+                Authorization::Deny
+            }
+            None => Authorization::Allow,
+        }));
+        SchemaGuard { conn, refused }
+    }
+
+    // Replaces SQLite's generic "not authorized" error with the reason the
+    // statement was refused.
+    pub fn explain(&self, error: rusqlite::Error) -> AppError {
+        match self.refused.lock().ok().and_then(|mut slot| slot.take()) {
+            Some(msg) => AppError::validation(msg),
+            None => error.into(),
+        }
+    }
+}
+
+impl Drop for SchemaGuard<'_> {
+    fn drop(&mut self) {
+        self.conn.authorizer(None::<fn(AuthContext<'_>) -> Authorization>);
+    }
+}
+
+pub fn ensure_read_only(stmt: &Statement) -> Result<(), AppError> {
+    if stmt.readonly() {
+        Ok(())
+    } else {
+        Err(AppError::validation(
+            "db_select only accepts read-only statements; use db_execute for writes",
+        ))
+    }
+}
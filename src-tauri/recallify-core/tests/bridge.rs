@@ -0,0 +1,124 @@
+mod common;
+
+use common::memory_db;
+use recallify_core::bridge::{self, BatchStatement};
+use recallify_core::error::ErrorCode;
+use serde_json::json;
+
+#[test]
+fn execute_and_select() {
+    let conn = memory_db();
+    let result = bridge::execute(&conn, "INSERT INTO subjects (name) VALUES (?)", &[json!("History")]).unwrap();
+    assert_eq!(result["rowsAffected"], 1);
+    let id = result["lastInsertId"].as_i64().unwrap();
+
+    let rows = bridge::select(&conn, "SELECT id, name FROM subjects WHERE id = ?", &[json!(id)], false).unwrap();
+    assert_eq!(rows, json!([{ "id": id, "name": "History" }]));
+}
+
+#[test]
+fn select_can_describe_columns() {
+    let conn = memory_db();
+    let result = bridge::select(&conn, "SELECT 1 AS one, 'a' AS letter", &[], true).unwrap();
+    assert_eq!(result["rows"], json!([{ "one": 1, "letter": "a" }]));
+    assert_eq!(result["columns"][0]["name"], "one");
+}
+
+#[test]
+fn flags_come_back_as_integers() {
+    let conn = memory_db();
+    let subject = bridge::execute(&conn, "INSERT INTO subjects (name) VALUES ('Art')", &[]).unwrap();
+    bridge::execute(
+        &conn,
+        "INSERT INTO syllabus_items (subject_id, title, is_completed) VALUES (?, 'Colour theory', 1)",
+        &[subject["lastInsertId"].clone()],
+    )
+    .unwrap();
+
+    let result = bridge::select(&conn, "SELECT is_completed FROM syllabus_items", &[], true).unwrap();
+    assert_eq!(result["rows"], json!([{ "is_completed": 1 }]));
+    assert_eq!(result["columns"][0]["declType"], "INTEGER");
+    assert_eq!(result["columns"][0]["kind"], "integer");
+}
+
+#[test]
+fn select_refuses_writes() {
+    let conn = memory_db();
+    let err = bridge::select(&conn, "DELETE FROM subjects", &[], false).unwrap_err();
+    assert_eq!(err.code, ErrorCode::Validation);
+}
+
+#[test]
+fn schema_changes_are_refused() {
+    let conn = memory_db();
+    let err = bridge::execute(&conn, "DROP TABLE subjects", &[]).unwrap_err();
+    assert_eq!(err.code, ErrorCode::Validation);
+    assert!(err.message.contains("not allowed"), "{}", err.message);
+
+    // The guard is gone once the call returns
+    conn.execute_batch("CREATE TABLE scratch (id INTEGER)").unwrap();
+}
+
+#[test]
+fn batches_cannot_commit_early() {
+    let conn = memory_db();
+    let statements: Vec<BatchStatement> = serde_json::from_value(json!([
+        { "sql": "INSERT INTO subjects (name) VALUES ('Art')" },
+        { "sql": "COMMIT" },
+        { "sql": "INSERT INTO subjects (name) VALUES ('Music')" }
+    ]))
+    .unwrap();
+
+    let err = bridge::transaction(&conn, &statements).unwrap_err();
+    assert_eq!(err.code, ErrorCode::Validation);
+    assert!(err.message.contains("not allowed"), "{}", err.message);
+    let subjects: i64 = conn.query_row("SELECT COUNT(*) FROM subjects", [], |row| row.get(0)).unwrap();
+    assert_eq!(subjects, 0);
+
+    for sql in ["BEGIN", "SAVEPOINT half", "ROLLBACK"] {
+        let err = bridge::execute(&conn, sql, &[]).unwrap_err();
+        assert_eq!(err.code, ErrorCode::Validation, "{}", sql);
+    }
+}
+
+#[test]
+fn transaction_resolves_earlier_insert_ids() {
+    let conn = memory_db();
+    let statements: Vec<BatchStatement> = serde_json::from_value(json!([
+        { "sql": "INSERT INTO subjects (name) VALUES (?)", "params": ["Art"] },
+        {
+            "sql": "INSERT INTO entries (subject_id, study_date, study_notes) VALUES (?, '2024-01-01', 'x')",
+            "params": [{ "$lastInsertId": 0 }]
+        }
+    ]))
+    .unwrap();
+
+    let results = bridge::transaction(&conn, &statements).unwrap();
+    let subject_id = results[0]["lastInsertId"].as_i64().unwrap();
+    let entry_subject: i64 = conn
+        .query_row("SELECT subject_id FROM entries", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(entry_subject, subject_id);
+    // Entries written through the bridge are indexed for search
+    let indexed: i64 = conn
+        .query_row("SELECT COUNT(*) FROM entry_search WHERE entry_search MATCH 'x'", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(indexed, 1);
+}
+
+#[test]
+fn failed_transaction_applies_nothing() {
+    let conn = memory_db();
+    let statements: Vec<BatchStatement> = serde_json::from_value(json!([
+        { "sql": "INSERT INTO subjects (name) VALUES ('Art')" },
+        { "sql": "INSERT INTO entries (subject_id, study_date, study_notes) VALUES (999, '2024-01-01', 'x')" }
+    ]))
+    .unwrap();
+
+    let err = bridge::transaction(&conn, &statements).unwrap_err();
+    assert_eq!(err.code, ErrorCode::Constraint);
+    assert_eq!(err.context.as_deref(), Some("Statement 1 failed"));
+
+    let count: i64 = conn.query_row("SELECT COUNT(*) FROM subjects", [], |row| row.get(0)).unwrap();
+    assert_eq!(count, 0);
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use common::{entry, memory_db, subject};
+use recallify_core::catch_up;
+use recallify_core::error::ErrorCode;
+use recallify_core::models::{CatchUpPlan, CatchUpStrategy, NewSyllabusItem};
+use recallify_core::queries;
+use rusqlite::Connection;
+
+fn date_offset(conn: &Connection, days: i64) -> String {
+    conn.query_row("SELECT DATE(local_date(), PRINTF('%+d days', ?1))", [days], |row| row.get(0))
+        .unwrap()
+}
+
+fn count(conn: &Connection, sql: &str) -> i64 {
+    conn.query_row(sql, [], |row| row.get(0)).unwrap()
+}
+
+// Interval and new day of each move
+fn schedule(plan: &CatchUpPlan) -> Vec<(i64, String)> {
+    plan.moves.iter().map(|m| (m.interval_days, m.to_date.clone())).collect()
+}
+
+fn exam(conn: &Connection, subject_id: i64, days: i64) -> i64 {
+    queries::create_syllabus_item(
+        conn,
+        &NewSyllabusItem {
+            subject_id,
+            parent_id: None,
+            title: "Exam".to_string(),
+            description: None,
+            estimated_hours: None,
+            due_date: Some(date_offset(conn, days)),
+            sort_order: None,
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn shortest_intervals_go_first_around_the_existing_load() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    for _ in 0..3 {
+        entry(&conn, math.id, &date_offset(&conn, -10), &[3]);
+    }
+    for _ in 0..2 {
+        entry(&conn, math.id, &date_offset(&conn, -20), &[1]);
+    }
+    // Already due today
+    entry(&conn, math.id, &date_offset(&conn, -3), &[3]);
+    queries::update_overdue_revisions(&conn).unwrap();
+
+    let preview = catch_up::plan_catch_up(&conn, 2, CatchUpStrategy::Interval, true).unwrap();
+    let expected: Vec<(i64, String)> = [(1, 0), (1, 1), (3, 1), (3, 2), (3, 2)]
+        .into_iter()
+        .map(|(interval, offset)| (interval, date_offset(&conn, offset)))
+        .collect();
+    assert_eq!(schedule(&preview), expected);
+    assert!(preview.moves.iter().all(|m| m.new_revision_id.is_none()));
+    assert_eq!(preview.days[0].total, 2);
+    assert_eq!(preview.days[0].moved, 1);
+    assert_eq!(count(&conn, "SELECT COUNT(*) FROM revisions WHERE status = 'overdue'"), 5);
+
+    let applied = catch_up::plan_catch_up(&conn, 2, CatchUpStrategy::Interval, false).unwrap();
+    assert_eq!(schedule(&applied), expected);
+    assert_eq!(count(&conn, "SELECT COUNT(*) FROM revisions WHERE status = 'overdue'"), 0);
+    assert_eq!(count(&conn, "SELECT COUNT(*) FROM revisions WHERE status = 'rescheduled'"), 5);
+    for planned in &applied.moves {
+        let new = queries::get_revision(&conn, planned.new_revision_id.unwrap()).unwrap();
+        assert_eq!((new.entry_id, new.status.as_str()), (planned.entry_id, "pending"));
+        assert_eq!(new.due_date, planned.to_date);
+    }
+    // Nothing left to catch up on
+    let again = catch_up::plan_catch_up(&conn, 2, CatchUpStrategy::Interval, false).unwrap();
+    assert!(again.moves.is_empty() && again.days.is_empty());
+}
+
+#[test]
+fn subjects_and_exam_dates_set_the_order() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    let art = subject(&conn, "Art");
+    let history = subject(&conn, "History");
+    for _ in 0..2 {
+        entry(&conn, math.id, &date_offset(&conn, -10), &[3]);
+    }
+    entry(&conn, art.id, &date_offset(&conn, -10), &[3]);
+    let linked = entry(&conn, history.id, &date_offset(&conn, -10), &[3]);
+    exam(&conn, math.id, 30);
+    let near = exam(&conn, history.id, 5);
+    queries::link_entry_to_syllabus(&conn, linked.entry.id, &[near]).unwrap();
+
+    let by_subject = catch_up::plan_catch_up(&conn, 1, CatchUpStrategy::Subject, true).unwrap();
+    let order: Vec<&str> = by_subject.moves.iter().map(|m| m.subject_name.as_str()).collect();
+    assert_eq!(order, vec!["Math", "Math", "Art", "History"]);
+
+    let by_exam = catch_up::plan_catch_up(&conn, 1, CatchUpStrategy::ExamDate, true).unwrap();
+    let order: Vec<(&str, Option<String>)> = by_exam
+        .moves
+        .iter()
+        .map(|m| (m.subject_name.as_str(), m.exam_date.clone()))
+        .collect();
+    assert_eq!(order[0], ("History", Some(date_offset(&conn, 5))));
+    assert_eq!(order[1].1, Some(date_offset(&conn, 30)));
+    assert_eq!(order[3], ("Art", None));
+
+    for max_per_day in [0, catch_up::MAX_PER_DAY_LIMIT + 1] {
+        let err = catch_up::plan_catch_up(&conn, max_per_day, CatchUpStrategy::Interval, true).unwrap_err();
+        assert_eq!(err.code, ErrorCode::Validation);
+    }
+}
+
+#[test]
+fn only_the_earliest_open_revision_of_an_entry_moves() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    let created = entry(&conn, math.id, &date_offset(&conn, -10), &[1, 3, 7]);
+
+    let plan = catch_up::plan_catch_up(&conn, 1, CatchUpStrategy::Interval, false).unwrap();
+    let moved: Vec<i64> = plan.moves.iter().map(|m| m.revision_id).collect();
+    assert_eq!(moved, vec![created.revisions[0].id]);
+    assert_eq!(schedule(&plan), vec![(1, date_offset(&conn, 0))]);
+}
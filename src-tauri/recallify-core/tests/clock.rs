@@ -0,0 +1,139 @@
+mod common;
+
+use common::{memory_db, subject};
+use jiff::tz::TimeZone;
+use recallify_core::error::ErrorCode;
+use recallify_core::{clock, queries};
+use rusqlite::Connection;
+
+fn use_zone(conn: &Connection, timezone: &str) {
+    clock::set_timezone(conn, Some(timezone)).unwrap();
+}
+
+#[test]
+fn timestamps_fall_on_the_date_of_the_setting() {
+    let conn = memory_db();
+    let kiritimati = TimeZone::get("Pacific/Kiritimati").unwrap();
+    assert_eq!(clock::local_date("2024-01-04 23:30:00", &kiritimati).as_deref(), Some("2024-01-05"));
+    assert_eq!(clock::local_date("2024-01-04T09:59:59Z", &kiritimati).as_deref(), Some("2024-01-04"));
+    // Dates are already local
+    assert_eq!(clock::local_date("2024-01-04", &kiritimati).as_deref(), Some("2024-01-04"));
+    assert_eq!(clock::local_date("yesterday", &kiritimati), None);
+
+    use_zone(&conn, "America/Los_Angeles");
+    let date: String = conn
+        .query_row("SELECT local_date('2024-07-01 06:00:00')", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(date, "2024-06-30");
+    let today: String = conn.query_row("SELECT local_date()", [], |row| row.get(0)).unwrap();
+    assert_eq!(today, clock::today(&conn).unwrap());
+}
+
+#[test]
+fn unknown_zones_are_rejected_and_empty_follows_the_system() {
+    let conn = memory_db();
+    use_zone(&conn, "Asia/Tokyo");
+
+    let err = clock::set_timezone(&conn, Some("Mars/Olympus_Mons")).unwrap_err();
+    assert_eq!(err.code, ErrorCode::Validation);
+    let info = clock::info(&conn).unwrap();
+    assert_eq!(info.timezone.as_deref(), Some("Asia/Tokyo"));
+    assert_eq!(info.effective.as_deref(), Some("Asia/Tokyo"));
+
+    let info = clock::set_timezone(&conn, Some(" ")).unwrap();
+    assert_eq!(info.timezone, None);
+    assert_eq!(info.effective, info.system);
+    assert_eq!(queries::get_setting(&conn, clock::TIMEZONE_SETTING).unwrap().as_deref(), Some(""));
+
+    // A zone saved by another version that this one does not know
+    queries::update_setting(&conn, clock::TIMEZONE_SETTING, "Nowhere/Special").unwrap();
+    clock::register_functions(&conn).unwrap();
+    assert_eq!(clock::info(&conn).unwrap().effective, info.system);
+}
+
+#[test]
+fn each_database_keeps_its_own_today() {
+    // 25 hours apart, so always on different dates
+    let east = memory_db();
+    use_zone(&east, "Pacific/Kiritimati");
+    let west = memory_db();
+    use_zone(&west, "Pacific/Pago_Pago");
+    assert_ne!(clock::today(&east).unwrap(), clock::today(&west).unwrap());
+
+    // A connection follows a setting saved through another one once its
+    // functions are registered again
+    queries::update_setting(&east, clock::TIMEZONE_SETTING, "Pacific/Pago_Pago").unwrap();
+    clock::register_functions(&east).unwrap();
+    assert_eq!(clock::today(&east).unwrap(), clock::today(&west).unwrap());
+}
+
+#[test]
+fn late_pomodoros_count_toward_the_local_day() {
+    let conn = memory_db();
+    use_zone(&conn, "Pacific/Kiritimati");
+    conn.execute(
+        "INSERT INTO pomodoro_sessions (session_type, duration_minutes, completed_at)
+         VALUES ('work', 25, '2024-01-04 23:30:00')",
+        [],
+    )
+    .unwrap();
+
+    queries::update_daily_activity(&conn, "2024-01-04").unwrap();
+    queries::update_daily_activity(&conn, "2024-01-05").unwrap();
+    let days = queries::get_daily_activities(&conn, "2024-01-04", "2024-01-05").unwrap();
+    let minutes: Vec<_> = days.iter().map(|d| (d.activity_date.as_str(), d.study_minutes)).collect();
+    assert!(minutes.contains(&("2024-01-04", 0)));
+    assert!(minutes.contains(&("2024-01-05", 25)));
+}
+
+#[test]
+fn study_time_windows_start_at_local_midnight() {
+    let conn = memory_db();
+    use_zone(&conn, "Pacific/Kiritimati");
+    let kiritimati = TimeZone::get("Pacific/Kiritimati").unwrap();
+    let math = subject(&conn, "Math");
+
+    // Just after and just before the start of yesterday, local time
+    let yesterday = clock::today_date(&conn).unwrap().yesterday().unwrap();
+    for time in [yesterday.at(0, 0, 30, 0), yesterday.at(0, 0, 0, 0) - jiff::Span::new().minutes(1)] {
+        let utc = time.to_zoned(kiritimati.clone()).unwrap().timestamp();
+        conn.execute(
+            "INSERT INTO pomodoro_sessions (subject_id, session_type, duration_minutes, completed_at)
+             VALUES (?1, 'work', 25, ?2)",
+            rusqlite::params![math.id, utc.strftime("%Y-%m-%d %H:%M:%S").to_string()],
+        )
+        .unwrap();
+    }
+
+    let time = queries::get_study_time_by_subject(&conn, math.id, 1).unwrap();
+    assert_eq!((time.session_count, time.total_minutes), (1, 25));
+    let all = queries::get_all_subjects_study_time(&conn, 1).unwrap();
+    assert_eq!((all[0].session_count, all[0].total_minutes), (1, 25));
+}
+
+#[test]
+fn streaks_end_today_or_yesterday() {
+    let conn = memory_db();
+    use_zone(&conn, "UTC");
+    assert_eq!(queries::streak_stats(&conn).unwrap().current_streak, 0);
+
+    // Yesterday and the two days before it, then a gap, then a single day
+    for offset in [-1, -2, -3, -6] {
+        conn.execute(
+            "INSERT INTO daily_activity (activity_date, study_minutes)
+             VALUES (DATE(local_date(), PRINTF('%+d days', ?1)), 25)",
+            [offset],
+        )
+        .unwrap();
+    }
+    let stats = queries::streak_stats(&conn).unwrap();
+    assert_eq!(stats.current_streak, 3);
+    assert_eq!(stats.longest_streak, 3);
+    assert_eq!(stats.total_active_days, 4);
+
+    conn.execute("DELETE FROM daily_activity WHERE activity_date > DATE(local_date(), '-3 days')", [])
+        .unwrap();
+    let stats = queries::streak_stats(&conn).unwrap();
+    assert_eq!(stats.current_streak, 0);
+    assert_eq!(stats.longest_streak, 1);
+}
@@ -0,0 +1,33 @@
+#![allow(dead_code)]
+
+use recallify_core::models::{EntryWithDetails, NewEntry, Subject};
+use recallify_core::{clock, migrations, queries, search};
+use rusqlite::Connection;
+
+// A migrated in-memory database set up like db::open_connection does.
+pub fn memory_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.pragma_update(None, "foreign_keys", "ON").unwrap();
+    search::register_functions(&conn).unwrap();
+    clock::register_functions(&conn).unwrap();
+    migrations::run(&conn).unwrap();
+    conn
+}
+
+pub fn subject(conn: &Connection, name: &str) -> Subject {
+    queries::create_subject(conn, name).unwrap()
+}
+
+pub fn entry(conn: &Connection, subject_id: i64, study_date: &str, intervals: &[i64]) -> EntryWithDetails {
+    queries::create_entry(
+        conn,
+        &NewEntry {
+            subject_id,
+            study_date: study_date.to_string(),
+            study_notes: "notes".to_string(),
+            intervals: intervals.to_vec(),
+            topics: None,
+        },
+    )
+    .unwrap()
+}
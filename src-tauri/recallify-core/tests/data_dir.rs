@@ -0,0 +1,141 @@
+mod common;
+
+use common::{entry, subject};
+use recallify_core::data_dir::{self, DataDirSource, DataLocation};
+use recallify_core::error::ErrorCode;
+use recallify_core::{db, files, profiles};
+use rusqlite::Connection;
+use std::fs;
+use std::path::Path;
+
+fn attach_pdf(conn: &Connection, data_dir: &Path) -> String {
+    let subject = subject(conn, "Physics");
+    let entry = entry(conn, subject.id, "2024-01-01", &[1]);
+    let path = files::save_pdf(data_dir, "1_notes.pdf", b"%PDF-1.4").unwrap();
+    conn.execute(
+        "INSERT INTO pdf_attachments (entry_id, file_name, file_path, file_size) VALUES (?1, 'notes.pdf', ?2, 8)",
+        rusqlite::params![entry.entry.id, path.to_string_lossy()],
+    )
+    .unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+fn attachment_path(conn: &Connection) -> String {
+    conn.query_row("SELECT file_path FROM pdf_attachments", [], |row| row.get(0))
+        .unwrap()
+}
+
+#[test]
+fn data_is_copied_with_paths_repointed() {
+    let root = tempfile::tempdir().unwrap();
+    let from = root.path().join("old");
+    let to = root.path().join("new");
+    let conn = db::open(&from, None).unwrap();
+    attach_pdf(&conn, &from);
+    fs::create_dir_all(files::backups_dir(&from)).unwrap();
+    fs::write(files::backups_dir(&from).join("daily.db"), b"backup").unwrap();
+
+    data_dir::copy_data(&conn, None, &from, &from, &to).unwrap();
+
+    // The original keeps working until the caller switches over
+    assert!(attachment_path(&conn).starts_with(&from.to_string_lossy().into_owned()));
+    drop(conn);
+
+    let copy = db::open(&to, None).unwrap();
+    let moved = attachment_path(&copy);
+    assert_eq!(Path::new(&moved), files::pdfs_dir(&to).join("1_notes.pdf"));
+    assert_eq!(files::read_pdf(&to, Path::new(&moved)).unwrap(), b"%PDF-1.4");
+    assert!(files::backups_dir(&to).join("daily.db").exists());
+
+    assert!(data_dir::remove_data(&from).is_empty());
+    assert!(!db::db_path(&from).exists());
+    assert!(!files::pdfs_dir(&from).exists());
+}
+
+#[test]
+fn every_profile_moves_with_the_data_dir() {
+    let root = tempfile::tempdir().unwrap();
+    let from = root.path().join("old");
+    let to = root.path().join("new");
+    let exam = profiles::create(&from, "Exam").unwrap();
+    let other = profiles::create(&from, "Other").unwrap();
+    {
+        let other_dir = profiles::profile_dir(&from, &other.id);
+        let conn = db::open(&other_dir, None).unwrap();
+        attach_pdf(&conn, &other_dir);
+    }
+    profiles::set_active(&from, &exam.id).unwrap();
+    let active = profiles::active_dir(&from);
+    let conn = db::open(&active, None).unwrap();
+    subject(&conn, "Exam subject");
+
+    data_dir::copy_data(&conn, None, &active, &from, &to).unwrap();
+    drop(conn);
+    assert!(data_dir::remove_data(&from).is_empty());
+    assert!(fs::read_dir(&from).unwrap().next().is_none());
+
+    assert_eq!(profiles::active_dir(&to), profiles::profile_dir(&to, &exam.id));
+    let exam_conn = db::open(&profiles::active_dir(&to), None).unwrap();
+    let names: i64 = exam_conn.query_row("SELECT COUNT(*) FROM subjects", [], |row| row.get(0)).unwrap();
+    assert_eq!(names, 1);
+
+    // The closed profile was copied as a file and repoints its paths when opened
+    let other_dir = profiles::profile_dir(&to, &other.id);
+    let other_conn = db::open(&other_dir, None).unwrap();
+    assert_eq!(
+        Path::new(&attachment_path(&other_conn)),
+        files::pdfs_dir(&other_dir).join("1_notes.pdf")
+    );
+}
+
+#[test]
+fn encrypted_data_stays_encrypted() {
+    let root = tempfile::tempdir().unwrap();
+    let from = root.path().join("old");
+    let to = root.path().join("new");
+    let conn = db::open(&from, Some("secret")).unwrap();
+
+    data_dir::copy_data(&conn, Some("secret"), &from, &from, &to).unwrap();
+
+    assert!(db::is_encrypted(&db::db_path(&to)).unwrap());
+    assert!(db::open(&to, Some("secret")).is_ok());
+}
+
+#[test]
+fn move_target_must_be_empty_and_outside_the_data_dir() {
+    let root = tempfile::tempdir().unwrap();
+    let from = root.path().join("old");
+    let conn = db::open(&from, None).unwrap();
+
+    let inside = from.join("nested");
+    let err = data_dir::copy_data(&conn, None, &from, &from, &inside).unwrap_err();
+    assert_eq!(err.code, ErrorCode::Validation);
+
+    let occupied = root.path().join("occupied");
+    fs::create_dir_all(&occupied).unwrap();
+    fs::write(occupied.join("keep.txt"), b"mine").unwrap();
+    let err = data_dir::copy_data(&conn, None, &from, &from, &occupied).unwrap_err();
+    assert_eq!(err.code, ErrorCode::Validation);
+    assert!(occupied.join("keep.txt").exists());
+
+    let err = data_dir::copy_data(&conn, None, &from, &from, Path::new("relative")).unwrap_err();
+    assert_eq!(err.code, ErrorCode::Validation);
+
+    // An empty folder is fine
+    let empty = root.path().join("empty");
+    fs::create_dir_all(&empty).unwrap();
+    data_dir::copy_data(&conn, None, &from, &from, &empty).unwrap();
+}
+
+#[test]
+fn portable_data_cannot_be_moved() {
+    let portable = DataLocation {
+        path: "/media/usb/RecallifyData".into(),
+        source: DataDirSource::Portable,
+    };
+    let err = data_dir::check_movable(&portable).unwrap_err();
+    assert!(err.message.contains(data_dir::PORTABLE_MARKER));
+
+    let custom = DataLocation { source: DataDirSource::Custom, ..portable };
+    assert!(data_dir::check_movable(&custom).is_ok());
+}
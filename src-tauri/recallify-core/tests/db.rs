@@ -0,0 +1,95 @@
+use recallify_core::db;
+use recallify_core::error::ErrorCode;
+use recallify_core::{migrations, queries};
+
+#[test]
+fn open_creates_a_migrated_wal_database() {
+    let dir = tempfile::tempdir().unwrap();
+    let conn = db::open(dir.path(), None).unwrap();
+
+    assert!(db::db_path(dir.path()).exists());
+    assert_eq!(migrations::current_version(&conn).unwrap(), migrations::latest_version());
+    let mode: String = conn.query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+    assert_eq!(mode, "wal");
+    let foreign_keys: i64 = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0)).unwrap();
+    assert_eq!(foreign_keys, 1);
+}
+
+#[test]
+fn reopening_keeps_data() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let conn = db::open(dir.path(), None).unwrap();
+        queries::create_subject(&conn, "Biology").unwrap();
+    }
+    let conn = db::open(dir.path(), None).unwrap();
+    let names: Vec<String> = queries::get_subjects(&conn)
+        .unwrap()
+        .into_iter()
+        .map(|s| s.subject.name)
+        .collect();
+    assert_eq!(names, ["Biology"]);
+}
+
+#[test]
+fn encrypted_database_needs_its_key() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let conn = db::open(dir.path(), Some("secret")).unwrap();
+        queries::create_subject(&conn, "Chemistry").unwrap();
+    }
+
+    assert!(db::is_encrypted(&db::db_path(dir.path())).unwrap());
+    let err = db::open(dir.path(), Some("wrong")).unwrap_err();
+    assert_eq!(err.code, ErrorCode::Validation);
+    assert_eq!(err.message, "Incorrect passphrase");
+
+    let conn = db::open(dir.path(), Some("secret")).unwrap();
+    assert_eq!(queries::get_subjects(&conn).unwrap().len(), 1);
+}
+
+#[test]
+fn plaintext_and_missing_files_are_not_encrypted() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = db::db_path(dir.path());
+    assert!(!db::is_encrypted(&path).unwrap());
+
+    db::open(dir.path(), None).unwrap();
+    assert!(!db::is_encrypted(&path).unwrap());
+}
+
+#[test]
+fn export_encrypts_a_copy() {
+    let dir = tempfile::tempdir().unwrap();
+    let conn = db::open(dir.path(), None).unwrap();
+    queries::create_subject(&conn, "Physics").unwrap();
+
+    let target = dir.path().join("encrypted.db");
+    db::export(&conn, &target, "secret").unwrap();
+    assert!(db::is_encrypted(&target).unwrap());
+
+    let copy = db::open_read_connection(&target, Some("secret")).unwrap();
+    assert_eq!(migrations::current_version(&copy).unwrap(), migrations::latest_version());
+    let count: i64 = copy.query_row("SELECT COUNT(*) FROM subjects", [], |row| row.get(0)).unwrap();
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn healthy_database_has_no_integrity_problems() {
+    let dir = tempfile::tempdir().unwrap();
+    let conn = db::open(dir.path(), None).unwrap();
+    assert!(db::integrity_problems(&conn, false).unwrap().is_empty());
+    assert!(db::integrity_problems(&conn, true).unwrap().is_empty());
+}
+
+#[test]
+fn newer_schema_is_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let conn = db::open(dir.path(), None).unwrap();
+        conn.pragma_update(None, "user_version", migrations::latest_version() + 1).unwrap();
+    }
+    let err = db::open(dir.path(), None).unwrap_err();
+    assert_eq!(err.code, ErrorCode::Database);
+    assert_eq!(err.context.as_deref(), Some("Failed to initialize database"));
+}
@@ -0,0 +1,66 @@
+use recallify_core::error::ErrorCode;
+use recallify_core::files;
+
+#[test]
+fn pdfs_are_saved_under_the_data_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = files::save_pdf(dir.path(), "1700000000_notes.pdf", b"%PDF-1.4").unwrap();
+
+    assert_eq!(path, files::pdfs_dir(dir.path()).join("1700000000_notes.pdf"));
+    assert_eq!(files::read_pdf(dir.path(), &path).unwrap(), b"%PDF-1.4");
+
+    files::delete_pdf(dir.path(), &path).unwrap();
+    assert!(!path.exists());
+}
+
+#[test]
+fn file_names_cannot_leave_the_pdfs_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["../escape.pdf", "nested/file.pdf", "..", ""] {
+        let err = files::save_pdf(dir.path(), name, b"x").unwrap_err();
+        assert_eq!(err.code, ErrorCode::Validation, "{}", name);
+    }
+}
+
+#[test]
+fn missing_files_are_not_found() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = files::pdfs_dir(dir.path()).join("missing.pdf");
+
+    assert_eq!(files::read_pdf(dir.path(), &missing).unwrap_err().code, ErrorCode::NotFound);
+    let err = files::delete_pdf(dir.path(), &missing).unwrap_err();
+    assert_eq!(err.code, ErrorCode::NotFound);
+    assert!(err.context.unwrap().contains("missing.pdf"));
+}
+
+#[test]
+fn only_files_in_the_pdfs_dir_are_read_or_deleted() {
+    let dir = tempfile::tempdir().unwrap();
+    files::save_pdf(dir.path(), "1_notes.pdf", b"%PDF-1.4").unwrap();
+    let outside = dir.path().join("recallify.db");
+    std::fs::write(&outside, b"data").unwrap();
+    let escaping = files::pdfs_dir(dir.path()).join("..").join("recallify.db");
+
+    for path in [&outside, &escaping] {
+        assert_eq!(files::read_pdf(dir.path(), path).unwrap_err().code, ErrorCode::Validation);
+        assert_eq!(files::delete_pdf(dir.path(), path).unwrap_err().code, ErrorCode::Validation);
+    }
+    assert!(outside.exists());
+}
+
+#[test]
+fn picked_pdfs_are_copied_in() {
+    let dir = tempfile::tempdir().unwrap();
+    let picked = dir.path().join("notes.pdf");
+    std::fs::write(&picked, b"%PDF-1.4").unwrap();
+
+    let imported = files::import_pdf(dir.path(), &picked, "1_notes.pdf").unwrap();
+    assert_eq!(imported.file_size, 8);
+    assert_eq!(files::read_pdf(dir.path(), imported.file_path.as_ref()).unwrap(), b"%PDF-1.4");
+
+    let other = dir.path().join("secrets.txt");
+    std::fs::write(&other, b"password").unwrap();
+    let err = files::import_pdf(dir.path(), &other, "2_secrets.pdf").unwrap_err();
+    assert_eq!(err.code, ErrorCode::Validation);
+    assert!(!files::pdfs_dir(dir.path()).join("2_secrets.pdf").exists());
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use common::{entry, memory_db, subject};
+use recallify_core::error::ErrorCode;
+use recallify_core::forecast::{self, DEFAULT_REVIEW_MINUTES};
+use recallify_core::models::ReviewForecast;
+use recallify_core::{queries, scheduler};
+use rusqlite::Connection;
+
+fn date_offset(conn: &Connection, days: i64) -> String {
+    conn.query_row("SELECT DATE(local_date(), PRINTF('%+d days', ?1))", [days], |row| row.get(0))
+        .unwrap()
+}
+
+// Days from today that have reviews
+fn review_days(forecast: &ReviewForecast) -> Vec<(usize, i64, i64)> {
+    forecast
+        .days
+        .iter()
+        .enumerate()
+        .filter(|(_, day)| day.reviews > 0)
+        .map(|(offset, day)| (offset, day.scheduled, day.projected))
+        .collect()
+}
+
+#[test]
+fn sm2_projects_past_the_next_revision() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    let today = date_offset(&conn, 0);
+    entry(&conn, math.id, &today, &[3, 7]);
+
+    let forecast = forecast::forecast_reviews(&conn, 30, None).unwrap();
+    assert_eq!(forecast.days.len(), 30);
+    assert_eq!(forecast.days[0].date, today);
+    // Graded 4 on day 3, SM-2 goes 1, 6 and 15 days on; the day 7
+    // revision of the plan is superseded by that grade
+    assert_eq!(review_days(&forecast), vec![(3, 1, 0), (4, 0, 1), (10, 0, 1), (25, 0, 1)]);
+    assert_eq!(forecast.total_reviews, 4);
+    assert_eq!(forecast.total_minutes, 4.0 * DEFAULT_REVIEW_MINUTES);
+    assert_eq!(forecast.subjects[0].subject_name, "Math");
+    assert_eq!(forecast.subjects[0].reviews, 4);
+}
+
+#[test]
+fn overdue_revisions_are_due_today_and_timed_reviews_set_the_minutes() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    let art = subject(&conn, "Art");
+    let late = entry(&conn, math.id, &date_offset(&conn, -10), &[3]);
+    queries::update_overdue_revisions(&conn).unwrap();
+    let timed = entry(&conn, art.id, &date_offset(&conn, -3), &[3]);
+    scheduler::grade_revision(&conn, timed.revisions[0].id, 4, Some(120)).unwrap();
+
+    let forecast = forecast::forecast_reviews(&conn, 7, None).unwrap();
+    let first = &forecast.days[0];
+    assert_eq!((first.scheduled, first.overdue), (1, 1));
+    assert_eq!(first.subjects[0].subject_id, late.entry.subject_id);
+    assert_eq!(first.minutes, 2.0);
+    // The graded entry is back tomorrow, as is the overdue one once reviewed
+    let loads: Vec<_> = forecast.days[1].subjects.iter().map(|load| (load.subject_id, load.minutes)).collect();
+    assert_eq!(loads, vec![(math.id, 2.0), (art.id, 2.0)]);
+
+    let art_only = forecast::forecast_reviews(&conn, 7, Some(art.id)).unwrap();
+    assert!(art_only.days.iter().all(|day| day.overdue == 0));
+    assert_eq!(art_only.subjects.len(), 1);
+    assert_eq!(art_only.subjects[0].minutes_per_review, 2.0);
+}
+
+#[test]
+fn fsrs_intervals_grow_and_the_length_is_checked() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    entry(&conn, math.id, &date_offset(&conn, 0), &[1]);
+    queries::update_setting(&conn, scheduler::SCHEDULER_SETTING, "fsrs").unwrap();
+
+    let forecast = forecast::forecast_reviews(&conn, 365, None).unwrap();
+    let days: Vec<usize> = review_days(&forecast).iter().map(|(offset, _, _)| *offset).collect();
+    assert_eq!(days[0], 1);
+    assert!(days.len() >= 3);
+    assert!(days.windows(3).all(|w| w[2] - w[1] > w[1] - w[0]));
+
+    for days in [0, 366] {
+        let err = forecast::forecast_reviews(&conn, days, None).unwrap_err();
+        assert_eq!(err.code, ErrorCode::Validation);
+    }
+}
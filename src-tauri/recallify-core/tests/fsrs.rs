@@ -0,0 +1,193 @@
+mod common;
+
+use common::{entry, memory_db, subject};
+use recallify_core::error::ErrorCode;
+use recallify_core::fsrs::{self, Fsrs, Rating, Review};
+use recallify_core::{queries, scheduler};
+use rusqlite::{params, Connection};
+
+fn date_offset(conn: &Connection, days: i64) -> String {
+    conn.query_row(
+        "SELECT DATE(local_date(), PRINTF('%+d days', ?1))",
+        [days],
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
+// An entry studied `days_ago` whose first revision was completed `reviewed_ago`
+// with the given grade
+fn reviewed_entry(conn: &Connection, subject_id: i64, days_ago: i64, reviewed_ago: i64, quality: i64) {
+    let study_date = date_offset(conn, -days_ago);
+    let created = entry(conn, subject_id, &study_date, &[days_ago - reviewed_ago]);
+    conn.execute(
+        "UPDATE revisions SET status = 'completed', quality = ?2,
+             completed_at = DATETIME('now', '-' || ?3 || ' days')
+         WHERE id = ?1",
+        params![created.revisions[0].id, quality, reviewed_ago],
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO review_log (revision_id, entry_id, quality, scheduled_date, reviewed_date)
+         SELECT id, entry_id, quality, due_date, local_date(completed_at) FROM revisions WHERE id = ?1",
+        [created.revisions[0].id],
+    )
+    .unwrap();
+}
+
+#[test]
+fn stability_grows_with_success_and_shrinks_on_a_lapse() {
+    let fsrs = Fsrs::default();
+    let good = fsrs.initial_state(Rating::Good);
+    assert_eq!(good.stability, fsrs::DEFAULT_PARAMETERS[2]);
+    assert!(fsrs.initial_state(Rating::Easy).difficulty < good.difficulty);
+
+    // Recall is 90% after `stability` days by definition
+    assert!((fsrs::retrievability(good.stability, good.stability) - 0.9).abs() < 1e-9);
+    assert_eq!(fsrs::interval(10.0, 0.9), 10);
+    assert!(fsrs::interval(10.0, 0.95) < 10);
+
+    let on_time = good.stability.round() as i64;
+    let hard = fsrs.next_state(good, on_time, Rating::Hard);
+    let recalled = fsrs.next_state(good, on_time, Rating::Good);
+    let easy = fsrs.next_state(good, on_time, Rating::Easy);
+    let lapsed = fsrs.next_state(good, on_time, Rating::Again);
+    assert!(easy.stability > recalled.stability && recalled.stability > hard.stability);
+    assert!(hard.stability > good.stability);
+    assert!(lapsed.stability < good.stability);
+    assert!(lapsed.difficulty > good.difficulty);
+
+    let history = [
+        Review { day: 0, rating: Rating::Good },
+        Review { day: on_time, rating: Rating::Good },
+    ];
+    assert_eq!(fsrs.replay(&history), Some(recalled));
+    assert_eq!(fsrs.replay(&[]), None);
+}
+
+#[test]
+fn parameters_round_trip_through_the_setting() {
+    let fsrs = Fsrs::default();
+    assert_eq!(Fsrs::from_setting(Some(&fsrs.to_setting())), fsrs);
+    assert_eq!(Fsrs::from_setting(None), fsrs);
+    assert_eq!(Fsrs::from_setting(Some("")), fsrs);
+    assert_eq!(Fsrs::from_setting(Some("1,2,3")), fsrs);
+
+    let mut custom = fsrs;
+    custom.w[0] = 1.25;
+    assert_eq!(Fsrs::from_setting(Some(&custom.to_setting())), custom);
+}
+
+#[test]
+fn optimizing_fits_the_weights_to_the_history() {
+    // Everything studied is forgotten within a week, far sooner than the
+    // defaults expect
+    let histories: Vec<Vec<Review>> = (0..60)
+        .map(|i| {
+            vec![
+                Review { day: 0, rating: Rating::Good },
+                Review {
+                    day: 5 + i % 5,
+                    rating: if i % 6 == 0 { Rating::Good } else { Rating::Again },
+                },
+            ]
+        })
+        .collect();
+
+    let optimization = fsrs::optimize(&histories).unwrap();
+    assert_eq!(optimization.reviews, 60);
+    assert!(optimization.loss_after < optimization.loss_before);
+    // A first "good" review now buys a shorter first interval
+    assert!(optimization.parameters[2] < fsrs::DEFAULT_PARAMETERS[2]);
+
+    assert!(fsrs::optimize(&histories[..fsrs::MIN_REVIEWS_TO_OPTIMIZE - 1]).is_none());
+}
+
+#[test]
+fn fsrs_schedules_the_next_revision_when_selected() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    let created = entry(&conn, math.id, &date_offset(&conn, -3), &[3]);
+    queries::update_setting(&conn, scheduler::SCHEDULER_SETTING, "fsrs").unwrap();
+
+    let graded = scheduler::grade_revision(&conn, created.revisions[0].id, 4, None).unwrap();
+
+    let fsrs = Fsrs::default();
+    let expected = fsrs.next_state(fsrs.initial_state(Rating::Good), 3, Rating::Good);
+    let stability = graded.entry.stability.unwrap();
+    assert!((stability - expected.stability).abs() < 1e-9);
+    assert_eq!(graded.entry.last_review_date, Some(date_offset(&conn, 0)));
+    assert_eq!(
+        graded.next_revision.due_date,
+        date_offset(&conn, fsrs::interval(stability, fsrs::DEFAULT_RETENTION))
+    );
+    // SM-2 state keeps up, ready for a switch back
+    assert_eq!(graded.entry.repetitions, 1);
+
+    // Higher desired retention means sooner reviews
+    let next = scheduler::grade_revision(&conn, graded.next_revision.id, 4, None).unwrap();
+    queries::update_setting(&conn, scheduler::FSRS_RETENTION_SETTING, "0.95").unwrap();
+    let stability = next.entry.stability.unwrap();
+    assert!(fsrs::interval(stability, scheduler::desired_retention(&conn).unwrap()) < fsrs::interval(stability, 0.9));
+}
+
+#[test]
+fn revision_history_seeds_the_memory_state() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    reviewed_entry(&conn, math.id, 10, 3, 4);
+    // Completed without a grade, counts as recalled
+    let plain = entry(&conn, math.id, &date_offset(&conn, -10), &[7]);
+    queries::complete_revision(&conn, plain.revisions[0].id).unwrap();
+
+    assert_eq!(scheduler::rebuild_memory_states(&conn).unwrap(), 2);
+    for entry in queries::get_entries_by_subject(&conn, math.id).unwrap() {
+        let stability = entry.entry.stability.unwrap();
+        assert!(stability > fsrs::DEFAULT_PARAMETERS[2], "{}", stability);
+        assert!(entry.entry.last_review_date.is_some());
+    }
+}
+
+#[test]
+fn optimization_needs_enough_history_and_saves_the_weights() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+
+    let err = scheduler::optimize_fsrs(&conn).unwrap_err();
+    assert_eq!(err.code, ErrorCode::Validation);
+
+    for i in 0..40 {
+        reviewed_entry(&conn, math.id, 12, 12 - (5 + i % 4), if i % 5 == 0 { 4 } else { 1 });
+    }
+    let optimization = scheduler::fit_fsrs(&conn).unwrap();
+    assert_eq!(optimization.reviews, 40);
+    // Fitting alone saves nothing
+    assert_eq!(scheduler::fsrs_from_settings(&conn).unwrap(), Fsrs::default());
+    scheduler::save_fsrs_parameters(&conn, &optimization).unwrap();
+
+    // Every entry's memory state was recomputed with the fitted weights
+    let saved = scheduler::fsrs_from_settings(&conn).unwrap();
+    assert_ne!(saved, Fsrs::default());
+    let mut stmt = conn
+        .prepare(
+            "SELECT e.stability, JULIANDAY(e.last_review_date) - JULIANDAY(e.study_date), r.quality
+             FROM entries e JOIN revisions r ON r.entry_id = e.id",
+        )
+        .unwrap();
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, f64>(0)?, row.get::<_, f64>(1)?, row.get::<_, u8>(2)?)))
+        .unwrap();
+    for row in rows {
+        let (stability, elapsed, quality) = row.unwrap();
+        let expected = saved
+            .replay(&[
+                Review { day: 0, rating: Rating::Good },
+                Review { day: elapsed as i64, rating: fsrs::rating(quality) },
+            ])
+            .unwrap();
+        assert!((stability - expected.stability).abs() < 1e-9);
+    }
+
+    scheduler::reset_fsrs(&conn).unwrap();
+    assert_eq!(scheduler::fsrs_from_settings(&conn).unwrap(), Fsrs::default());
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use common::{entry, memory_db, subject};
+use recallify_core::error::ErrorCode;
+use recallify_core::load_balance::{self, LoadBalancing};
+use recallify_core::scheduler;
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+
+fn date_offset(conn: &Connection, days: i64) -> String {
+    conn.query_row("SELECT DATE(local_date(), PRINTF('%+d days', ?1))", [days], |row| row.get(0))
+        .unwrap()
+}
+
+fn balance(conn: &Connection, max_daily_reviews: Option<i64>, tolerance_percent: i64) {
+    LoadBalancing {
+        enabled: true,
+        max_daily_reviews,
+        tolerance_percent,
+    }
+    .save(conn)
+    .unwrap();
+}
+
+// Open revisions per due date
+fn loads(conn: &Connection) -> BTreeMap<String, i64> {
+    let mut stmt = conn
+        .prepare("SELECT due_date, COUNT(*) FROM revisions WHERE status = 'pending' GROUP BY due_date")
+        .unwrap();
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).unwrap();
+    rows.collect::<rusqlite::Result<_>>().unwrap()
+}
+
+#[test]
+fn entries_of_one_day_spread_over_the_window() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    let today = date_offset(&conn, 0);
+    for _ in 0..5 {
+        entry(&conn, math.id, &today, &[3, 7]);
+    }
+    // Off by default: everything on the planned days
+    let expected: BTreeMap<String, i64> = [(date_offset(&conn, 3), 5), (date_offset(&conn, 7), 5)].into();
+    assert_eq!(loads(&conn), expected);
+
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    balance(&conn, None, 10);
+    for _ in 0..5 {
+        entry(&conn, math.id, &today, &[3, 7]);
+    }
+    let expected: BTreeMap<String, i64> = [(2, 2), (3, 2), (4, 1), (6, 2), (7, 2), (8, 1)]
+        .into_iter()
+        .map(|(offset, count)| (date_offset(&conn, offset), count))
+        .collect();
+    assert_eq!(loads(&conn), expected);
+}
+
+#[test]
+fn full_days_are_skipped() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    balance(&conn, Some(2), 0);
+    for _ in 0..3 {
+        entry(&conn, math.id, &date_offset(&conn, 0), &[3]);
+    }
+    let expected: BTreeMap<String, i64> = [(date_offset(&conn, 3), 2), (date_offset(&conn, 4), 1)].into();
+    assert_eq!(loads(&conn), expected);
+
+    // Revisions already due are left where they are
+    let late = entry(&conn, math.id, &date_offset(&conn, -10), &[3]);
+    assert_eq!(late.revisions[0].due_date, date_offset(&conn, -7));
+}
+
+#[test]
+fn graded_revisions_come_back_on_a_day_with_room() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    balance(&conn, Some(1), 10);
+    entry(&conn, math.id, &date_offset(&conn, -2), &[3]);
+    let graded = entry(&conn, math.id, &date_offset(&conn, -3), &[3]);
+
+    // SM-2 wants it back tomorrow, which already has a revision
+    let grade = scheduler::grade_revision(&conn, graded.revisions[0].id, 4, None).unwrap();
+    assert_eq!(grade.next_revision.due_date, date_offset(&conn, 2));
+    assert_eq!(grade.next_revision.interval_days, 5);
+    assert_eq!(grade.review.next_due_date.as_deref(), Some(grade.next_revision.due_date.as_str()));
+}
+
+#[test]
+fn settings_are_validated() {
+    let conn = memory_db();
+    assert_eq!(LoadBalancing::from_settings(&conn).unwrap(), LoadBalancing::default());
+
+    for (max_daily_reviews, tolerance_percent) in [(Some(0), 10), (None, 80)] {
+        let settings = LoadBalancing {
+            enabled: true,
+            max_daily_reviews,
+            tolerance_percent,
+        };
+        assert_eq!(settings.save(&conn).unwrap_err().code, ErrorCode::Validation);
+    }
+    balance(&conn, Some(30), 15);
+    let saved = LoadBalancing::from_settings(&conn).unwrap();
+    assert_eq!(saved.max_daily_reviews, Some(30));
+    assert_eq!(saved.window(2), 0);
+    assert_eq!(saved.window(20), 3);
+    assert_eq!(load_balance::due_date(&conn, "2024-01-01", 3).unwrap(), "2024-01-04");
+}
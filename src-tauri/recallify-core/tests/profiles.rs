@@ -0,0 +1,69 @@
+use recallify_core::error::ErrorCode;
+use recallify_core::profiles::{self, DEFAULT_PROFILE};
+use recallify_core::{db, queries};
+
+#[test]
+fn a_fresh_data_dir_has_only_the_default_profile() {
+    let dir = tempfile::tempdir().unwrap();
+    let loaded = profiles::load(dir.path()).unwrap();
+    assert_eq!(loaded.active, DEFAULT_PROFILE);
+    assert_eq!(loaded.profiles.len(), 1);
+    assert_eq!(profiles::active_dir(dir.path()), dir.path());
+}
+
+#[test]
+fn profiles_keep_separate_databases() {
+    let dir = tempfile::tempdir().unwrap();
+    let university = profiles::create(dir.path(), "University").unwrap();
+    assert_eq!(university.id, "university");
+
+    queries::create_subject(&db::open(dir.path(), None).unwrap(), "Default subject").unwrap();
+
+    profiles::set_active(dir.path(), &university.id).unwrap();
+    let active = profiles::active_dir(dir.path());
+    assert_eq!(active, profiles::profile_dir(dir.path(), "university"));
+    let conn = db::open(&active, None).unwrap();
+    assert!(queries::get_subjects(&conn).unwrap().is_empty());
+}
+
+#[test]
+fn names_must_be_unique_but_ids_are_stable() {
+    let dir = tempfile::tempdir().unwrap();
+    let first = profiles::create(dir.path(), "AWS Certification!").unwrap();
+    assert_eq!(first.id, "aws-certification");
+
+    let err = profiles::create(dir.path(), "aws certification!").unwrap_err();
+    assert_eq!(err.code, ErrorCode::Validation);
+    assert_eq!(profiles::create(dir.path(), "  ").unwrap_err().code, ErrorCode::Validation);
+
+    let renamed = profiles::rename(dir.path(), &first.id, "Cloud").unwrap();
+    assert_eq!(renamed.id, first.id);
+    assert_eq!(renamed.name, "Cloud");
+
+    // The old name is free again, so the slug is taken but the name is not
+    let second = profiles::create(dir.path(), "AWS Certification").unwrap();
+    assert_eq!(second.id, "aws-certification-2");
+
+    let err = profiles::create(dir.path(), "Default").unwrap_err();
+    assert_eq!(err.code, ErrorCode::Validation);
+}
+
+#[test]
+fn only_inactive_profiles_can_be_deleted() {
+    let dir = tempfile::tempdir().unwrap();
+    let exam = profiles::create(dir.path(), "Exam").unwrap();
+    let exam_dir = profiles::profile_dir(dir.path(), &exam.id);
+    db::open(&exam_dir, None).unwrap();
+
+    let err = profiles::delete(dir.path(), DEFAULT_PROFILE).unwrap_err();
+    assert_eq!(err.code, ErrorCode::Validation);
+
+    profiles::set_active(dir.path(), &exam.id).unwrap();
+    assert_eq!(profiles::delete(dir.path(), &exam.id).unwrap_err().code, ErrorCode::Validation);
+
+    profiles::set_active(dir.path(), DEFAULT_PROFILE).unwrap();
+    profiles::delete(dir.path(), &exam.id).unwrap();
+    assert!(!exam_dir.exists());
+    assert_eq!(profiles::load(dir.path()).unwrap().profiles.len(), 1);
+    assert_eq!(profiles::set_active(dir.path(), &exam.id).unwrap_err().code, ErrorCode::NotFound);
+}
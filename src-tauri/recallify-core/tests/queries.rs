@@ -0,0 +1,125 @@
+mod common;
+
+use common::{entry, memory_db, subject};
+use recallify_core::error::{AppError, ErrorCode};
+use recallify_core::models::EntryUpdate;
+use recallify_core::queries;
+
+#[test]
+fn entry_schedules_a_revision_per_interval() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    let created = entry(&conn, math.id, "2024-01-01", &[1, 3, 7]);
+
+    let due: Vec<&str> = created.revisions.iter().map(|r| r.due_date.as_str()).collect();
+    assert_eq!(due, ["2024-01-02", "2024-01-04", "2024-01-08"]);
+    assert!(created.revisions.iter().all(|r| r.status == "pending"));
+    assert_eq!(created.subject_name, "Math");
+}
+
+#[test]
+fn completing_and_uncompleting_a_revision() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    let created = entry(&conn, math.id, "2024-01-01", &[1]);
+    let id = created.revisions[0].id;
+
+    queries::complete_revision(&conn, id).unwrap();
+    let revision = queries::get_revision(&conn, id).unwrap();
+    assert_eq!(revision.status, "completed");
+    assert!(revision.completed_at.is_some());
+
+    queries::uncomplete_revision(&conn, id).unwrap();
+    let revision = queries::get_revision(&conn, id).unwrap();
+    assert_ne!(revision.status, "completed");
+    assert!(revision.completed_at.is_none());
+}
+
+#[test]
+fn past_due_revisions_are_marked_overdue_once() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    entry(&conn, math.id, "2024-01-01", &[1, 3]);
+
+    assert!(queries::has_revisions_to_mark_overdue(&conn).unwrap());
+    assert_eq!(queries::update_overdue_revisions(&conn).unwrap(), 2);
+    assert!(!queries::has_revisions_to_mark_overdue(&conn).unwrap());
+    assert_eq!(queries::update_overdue_revisions(&conn).unwrap(), 0);
+}
+
+#[test]
+fn changing_intervals_replaces_pending_revisions() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    let created = entry(&conn, math.id, "2024-01-01", &[1, 3]);
+    queries::complete_revision(&conn, created.revisions[0].id).unwrap();
+
+    let update = EntryUpdate {
+        study_notes: "revised".to_string(),
+        morning_recall_notes: None,
+        intervals: Some(vec![10]),
+        topics: None,
+    };
+    let updated = queries::update_entry(&conn, created.entry.id, &update).unwrap();
+
+    let mut revisions: Vec<(&str, &str)> = updated
+        .revisions
+        .iter()
+        .map(|r| (r.due_date.as_str(), r.status.as_str()))
+        .collect();
+    revisions.sort();
+    assert_eq!(revisions, [("2024-01-02", "completed"), ("2024-01-11", "pending")]);
+}
+
+#[test]
+fn deleting_a_subject_removes_its_entries() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    let created = entry(&conn, math.id, "2024-01-01", &[1]);
+
+    queries::delete_subject(&conn, math.id).unwrap();
+
+    let err = AppError::from(queries::get_entry(&conn, created.entry.id).unwrap_err());
+    assert_eq!(err.code, ErrorCode::NotFound);
+}
+
+#[test]
+fn entry_for_a_missing_subject_is_a_constraint_error() {
+    let conn = memory_db();
+    let err = conn
+        .execute(
+            "INSERT INTO entries (subject_id, study_date, study_notes) VALUES (99, '2024-01-01', 'x')",
+            [],
+        )
+        .unwrap_err();
+    assert_eq!(AppError::from(err).code, ErrorCode::Constraint);
+}
+
+#[test]
+fn tags_are_trimmed_and_deduplicated() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    let created = entry(&conn, math.id, "2024-01-01", &[1]);
+
+    let tags = ["Algebra ".to_string(), " ".to_string(), "algebra".to_string(), "Proofs".to_string()];
+    queries::link_tags_to_entry(&conn, created.entry.id, &tags).unwrap();
+
+    let mut names: Vec<String> = queries::get_entry_tags(&conn, created.entry.id)
+        .unwrap()
+        .into_iter()
+        .map(|t| t.name.to_lowercase())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["algebra", "proofs"]);
+}
+
+#[test]
+fn settings_round_trip() {
+    let conn = memory_db();
+    queries::update_setting(&conn, "default_intervals", "2,5,12").unwrap();
+    assert_eq!(
+        queries::get_setting(&conn, "default_intervals").unwrap().as_deref(),
+        Some("2,5,12")
+    );
+    assert_eq!(queries::get_setting(&conn, "no_such_setting").unwrap(), None);
+}
@@ -0,0 +1,186 @@
+mod common;
+
+use common::{entry, memory_db, subject};
+use recallify_core::error::ErrorCode;
+use recallify_core::migrations::{self, MIGRATIONS};
+use recallify_core::models::{ReviewLogFilters, SchedulerState};
+use recallify_core::{queries, review_log, scheduler};
+use rusqlite::Connection;
+
+fn today(conn: &Connection) -> String {
+    conn.query_row("SELECT local_date()", [], |row| row.get(0)).unwrap()
+}
+
+// Revision id and grade of each review of the entry, newest first
+fn entry_log(conn: &Connection, entry_id: i64) -> Vec<(Option<i64>, Option<i64>)> {
+    let filters = ReviewLogFilters {
+        entry_id: Some(entry_id),
+        ..Default::default()
+    };
+    review_log::get_review_log(conn, &filters)
+        .unwrap()
+        .into_iter()
+        .map(|review| (review.revision_id, review.quality))
+        .collect()
+}
+
+#[test]
+fn grading_is_logged_and_the_latest_grade_can_be_undone() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    let created = entry(&conn, math.id, "2024-01-01", &[3, 7]);
+    let first = &created.revisions[0];
+
+    let graded = scheduler::grade_revision(&conn, first.id, 4, Some(40)).unwrap();
+    let review = &graded.review;
+    assert_eq!(review.revision_id, Some(first.id));
+    assert_eq!(review.quality, Some(4));
+    assert_eq!(review.duration_seconds, Some(40));
+    assert_eq!(review.scheduler.as_deref(), Some("sm2"));
+    assert_eq!(review.scheduled_date, first.due_date);
+    assert_eq!(review.reviewed_date, today(&conn));
+    assert_eq!(review.state_before, Some(SchedulerState::of(&created.entry)));
+    assert_eq!(review.state_after, Some(SchedulerState::of(&graded.entry)));
+    assert_eq!(review.next_due_date.as_deref(), Some(graded.next_revision.due_date.as_str()));
+
+    let regraded = scheduler::grade_revision(&conn, graded.next_revision.id, 1, None).unwrap();
+
+    // Undoing an older grade would leave the later one built on nothing
+    let err = scheduler::undo_review(&conn, review.id).unwrap_err();
+    assert_eq!(err.code, ErrorCode::Validation);
+
+    let reopened = scheduler::undo_review(&conn, regraded.review.id).unwrap();
+    assert_eq!(reopened.id, graded.next_revision.id);
+    assert_eq!(reopened.status, "pending");
+    assert_eq!(reopened.quality, None);
+
+    let restored = queries::get_entry(&conn, created.entry.id).unwrap();
+    assert_eq!(SchedulerState::of(&restored.entry), SchedulerState::of(&graded.entry));
+    let open: Vec<_> = restored.revisions.iter().filter(|r| r.status != "completed").collect();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].id, reopened.id);
+    assert_eq!(entry_log(&conn, created.entry.id), vec![(Some(first.id), Some(4))]);
+    let graded_activity = queries::get_activity_log(&conn, &Default::default())
+        .unwrap()
+        .into_iter()
+        .filter(|a| a.activity.details.as_deref().is_some_and(|d| d.contains("graded")))
+        .count();
+    assert_eq!(graded_activity, 1);
+
+    // Then grading again works as if the mistake never happened
+    let again = scheduler::grade_revision(&conn, reopened.id, 5, None).unwrap();
+    assert_eq!(again.entry.repetitions, 2);
+}
+
+#[test]
+fn plain_completions_are_logged_until_reopened() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    let created = entry(&conn, math.id, "2024-01-01", &[3]);
+    let id = created.revisions[0].id;
+
+    queries::complete_revision(&conn, id).unwrap();
+    // A second click finds it completed and logs nothing more
+    queries::complete_revision(&conn, id).unwrap();
+    let log = review_log::get_review_log(&conn, &Default::default()).unwrap();
+    assert_eq!(log.len(), 1);
+    let completions: i64 = conn
+        .query_row("SELECT COUNT(*) FROM activity_log WHERE activity_type = 'revision_completed'", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(completions, 1);
+    assert_eq!(log[0].quality, None);
+    assert_eq!(log[0].state_before, None);
+    assert_eq!(log[0].scheduled_date, created.revisions[0].due_date);
+
+    // Nothing to roll back without a grade
+    let err = scheduler::undo_review(&conn, log[0].id).unwrap_err();
+    assert_eq!(err.code, ErrorCode::Validation);
+
+    queries::uncomplete_revision(&conn, id).unwrap();
+    assert!(entry_log(&conn, created.entry.id).is_empty());
+}
+
+#[test]
+fn reopening_a_graded_revision_undoes_its_grade() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    let created = entry(&conn, math.id, "2024-01-01", &[3, 7]);
+    let id = created.revisions[0].id;
+    let graded = scheduler::grade_revision(&conn, id, 4, None).unwrap();
+
+    // The plain query leaves graded revisions alone
+    assert!(queries::uncomplete_revision(&conn, id).is_err());
+
+    // Only the latest grade of an entry can be taken back
+    scheduler::grade_revision(&conn, graded.next_revision.id, 5, None).unwrap();
+    let err = scheduler::uncomplete_revision(&conn, id).unwrap_err();
+    assert_eq!(err.code, ErrorCode::Validation);
+
+    scheduler::uncomplete_revision(&conn, graded.next_revision.id).unwrap();
+    let reopened = scheduler::uncomplete_revision(&conn, id).unwrap();
+    assert_eq!((reopened.status.as_str(), reopened.quality), ("overdue", None));
+    let restored = queries::get_entry(&conn, created.entry.id).unwrap();
+    assert_eq!(restored.entry.repetitions, 0);
+    let open: Vec<i64> = restored.revisions.iter().filter(|r| r.status != "completed").map(|r| r.id).collect();
+    assert_eq!(open, vec![id]);
+    assert!(entry_log(&conn, created.entry.id).is_empty());
+}
+
+#[test]
+fn retention_counts_graded_reviews() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    let art = subject(&conn, "Art");
+    for (quality, seconds) in [(5, 20), (4, 40), (1, 60)] {
+        let created = entry(&conn, math.id, "2024-01-01", &[3]);
+        scheduler::grade_revision(&conn, created.revisions[0].id, quality, Some(seconds)).unwrap();
+    }
+    let plain = entry(&conn, math.id, "2024-01-01", &[3]);
+    queries::complete_revision(&conn, plain.revisions[0].id).unwrap();
+
+    let stats = review_log::retention_stats(&conn, &Default::default()).unwrap();
+    assert_eq!(stats.reviews, 3);
+    assert_eq!(stats.recalled, 2);
+    assert!((stats.retention.unwrap() - 2.0 / 3.0).abs() < 1e-9);
+    assert_eq!(stats.average_seconds, Some(40.0));
+    // Due 2024-01-04, reviewed today
+    assert!(stats.average_days_late.unwrap() > 600.0);
+
+    let filters = ReviewLogFilters {
+        subject_id: Some(art.id),
+        ..Default::default()
+    };
+    let stats = review_log::retention_stats(&conn, &filters).unwrap();
+    assert_eq!(stats.reviews, 0);
+    assert_eq!(stats.retention, None);
+    assert_eq!(stats.average_seconds, None);
+}
+
+#[test]
+fn migration_carries_over_completed_revisions() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    let created = entry(&conn, math.id, "2024-01-01", &[3, 7]);
+    conn.execute(
+        "UPDATE revisions SET status = 'completed', quality = 4, completed_at = '2024-01-04 10:00:00' WHERE id = ?1",
+        [created.revisions[0].id],
+    )
+    .unwrap();
+
+    // As before the review log existed
+    conn.execute_batch("DROP TABLE review_log; UPDATE entries SET stability = NULL; PRAGMA user_version = 8;")
+        .unwrap();
+    assert_eq!(migrations::run(&conn).unwrap(), migrations::latest_version());
+    let expected = vec![(Some(created.revisions[0].id), Some(4))];
+    assert_eq!(entry_log(&conn, created.entry.id), expected);
+    // Rebuilt from the carried over history
+    let entry = queries::get_entry(&conn, created.entry.id).unwrap().entry;
+    assert!(entry.stability.is_some());
+
+    // Running the step again adds nothing
+    let step = MIGRATIONS.iter().find(|m| m.description == "review log").unwrap();
+    let tx = conn.unchecked_transaction().unwrap();
+    (step.up)(&tx).unwrap();
+    tx.commit().unwrap();
+    assert_eq!(entry_log(&conn, created.entry.id), expected);
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use common::{entry, memory_db, subject};
+use recallify_core::error::ErrorCode;
+use recallify_core::queries;
+use recallify_core::scheduler::{self, Sm2State};
+use rusqlite::Connection;
+
+fn today_plus(conn: &Connection, days: i64) -> String {
+    conn.query_row(
+        "SELECT DATE(local_date(), '+' || ?1 || ' days')",
+        [days],
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
+#[test]
+fn sm2_matches_the_reference_sequence() {
+    let first = scheduler::sm2(Sm2State::default(), 5);
+    assert_eq!(first, Sm2State { ease_factor: 2.6, repetitions: 1, interval_days: 1 });
+    let second = scheduler::sm2(first, 4);
+    assert_eq!(second, Sm2State { ease_factor: 2.6, repetitions: 2, interval_days: 6 });
+    let third = scheduler::sm2(second, 3);
+    assert_eq!(third, Sm2State { ease_factor: 2.46, repetitions: 3, interval_days: 15 });
+
+    // A lapse starts over but keeps the lowered ease
+    let lapse = scheduler::sm2(third, 1);
+    assert_eq!(lapse, Sm2State { ease_factor: 1.92, repetitions: 0, interval_days: 1 });
+
+    // Ease never drops below the minimum
+    let mut state = Sm2State::default();
+    for _ in 0..10 {
+        state = scheduler::sm2(state, 0);
+    }
+    assert_eq!(state.ease_factor, scheduler::MIN_EASE_FACTOR);
+}
+
+#[test]
+fn sm2_intervals_match_the_frontend() {
+    // Worked through calculateNextReview in src/utils/spacedRepetition.ts.
+    // The last step multiplies 55 days by an ease of 2.3 that is a hair
+    // above it before rounding, which makes 127 rather than 126.
+    let mut state = Sm2State::default();
+    let mut intervals = Vec::new();
+    for quality in [0, 5, 5, 5, 5, 5, 5] {
+        state = scheduler::sm2(state, quality);
+        intervals.push(state.interval_days);
+    }
+    assert_eq!(intervals, [1, 1, 6, 12, 25, 55, 127]);
+    assert_eq!(state.ease_factor, 2.3);
+}
+
+#[test]
+fn grading_replaces_the_fixed_plan_with_the_next_sm2_revision() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    let created = entry(&conn, math.id, "2024-01-01", &[3, 7, 14]);
+    let first = created.revisions[0].id;
+
+    let graded = scheduler::grade_revision(&conn, first, 4, None).unwrap();
+    assert_eq!(graded.revision.status, "completed");
+    assert_eq!(graded.revision.quality, Some(4));
+    assert_eq!(graded.entry.repetitions, 1);
+    assert_eq!(graded.next_revision.due_date, today_plus(&conn, 1));
+
+    // The day 7 and 14 revisions are superseded
+    let revisions = queries::get_entry(&conn, created.entry.id).unwrap().revisions;
+    assert_eq!(revisions.len(), 2);
+    let open: Vec<_> = revisions.iter().filter(|r| r.status != "completed").collect();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].id, graded.next_revision.id);
+
+    let graded = scheduler::grade_revision(&conn, graded.next_revision.id, 5, None).unwrap();
+    assert_eq!(graded.entry.repetitions, 2);
+    assert_eq!(graded.entry.ease_factor, 2.6);
+    assert_eq!(graded.next_revision.due_date, today_plus(&conn, 6));
+
+    let activity = queries::get_activity_log(&conn, &Default::default()).unwrap();
+    assert!(activity
+        .iter()
+        .any(|a| a.activity.details.as_deref().is_some_and(|d| d.contains("graded 5/5"))));
+}
+
+#[test]
+fn only_open_revisions_with_valid_grades_can_be_graded() {
+    let conn = memory_db();
+    let math = subject(&conn, "Math");
+    let created = entry(&conn, math.id, "2024-01-01", &[1]);
+    let id = created.revisions[0].id;
+
+    let err = scheduler::grade_revision(&conn, id, 6, None).unwrap_err();
+    assert_eq!(err.code, ErrorCode::Validation);
+
+    queries::complete_revision(&conn, id).unwrap();
+    let err = scheduler::grade_revision(&conn, id, 3, None).unwrap_err();
+    assert_eq!(err.code, ErrorCode::Validation);
+
+    let err = scheduler::grade_revision(&conn, 9999, 3, None).unwrap_err();
+    assert_eq!(err.code, ErrorCode::NotFound);
+
+    // Nothing changed on the entry
+    let entry = queries::get_entry(&conn, created.entry.id).unwrap().entry;
+    assert_eq!(Sm2State::of(&entry), Sm2State::default());
+}
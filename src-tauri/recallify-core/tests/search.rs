@@ -0,0 +1,197 @@
+mod common;
+
+use common::{entry, memory_db, subject};
+use recallify_core::models::{EntryUpdate, SearchFilters};
+use recallify_core::{db, queries, search};
+use rusqlite::Connection;
+
+fn entry_ids(conn: &Connection, query: &str, filters: &SearchFilters) -> Vec<i64> {
+    search::search_entries(conn, query, filters)
+        .unwrap()
+        .into_iter()
+        .map(|hit| hit.entry_id)
+        .collect()
+}
+
+fn write_notes(conn: &Connection, id: i64, notes: &str, recall: Option<&str>, topics: Option<&str>) {
+    queries::update_entry(
+        conn,
+        id,
+        &EntryUpdate {
+            study_notes: notes.to_string(),
+            morning_recall_notes: recall.map(str::to_string),
+            intervals: None,
+            topics: topics.map(str::to_string),
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn html_is_reduced_to_plain_text() {
+    assert_eq!(
+        search::strip_html("<h1>Krebs&nbsp;cycle</h1><p>ATP &amp; NADH</p><ul><li>one</li><li>two</li></ul>"),
+        "Krebs cycle ATP & NADH one two"
+    );
+    assert_eq!(search::strip_html("<p>mito<strong>chondria</strong></p>"), "mitochondria");
+    assert_eq!(search::strip_html("a < b &unknown; &#233;t&#xE9;"), "a < b &unknown; été");
+    assert_eq!(search::strip_html("unclosed <tag"), "unclosed <tag");
+}
+
+#[test]
+fn index_follows_entry_and_tag_changes() {
+    let conn = memory_db();
+    let biology = subject(&conn, "Biology");
+    let id = entry(&conn, biology.id, "2024-01-01", &[1]).entry.id;
+    let all = SearchFilters::default();
+
+    write_notes(&conn, id, "<p>The <em>mitochondria</em> make ATP</p>", Some("forgot glycolysis"), Some("Cells"));
+    assert_eq!(entry_ids(&conn, "mitochondria", &all), [id]);
+    assert_eq!(entry_ids(&conn, "glyco", &all), [id]);
+    assert_eq!(entry_ids(&conn, "cells", &all), [id]);
+    // Markup is not indexed
+    assert!(entry_ids(&conn, "em", &all).is_empty());
+
+    write_notes(&conn, id, "<p>Ribosomes</p>", None, None);
+    assert!(entry_ids(&conn, "mitochondria", &all).is_empty());
+    assert_eq!(entry_ids(&conn, "ribosomes", &all), [id]);
+
+    queries::link_tags_to_entry(&conn, id, &["Exam".to_string()]).unwrap();
+    assert_eq!(entry_ids(&conn, "exam", &all), [id]);
+    conn.execute("UPDATE tags SET name = 'finals' WHERE name = 'exam'", []).unwrap();
+    assert!(entry_ids(&conn, "exam", &all).is_empty());
+    assert_eq!(entry_ids(&conn, "finals", &all), [id]);
+    queries::link_tags_to_entry(&conn, id, &[]).unwrap();
+    assert!(entry_ids(&conn, "finals", &all).is_empty());
+
+    queries::delete_entry(&conn, id).unwrap();
+    assert!(entry_ids(&conn, "ribosomes", &all).is_empty());
+    let rows: i64 = conn.query_row("SELECT COUNT(*) FROM entry_search", [], |row| row.get(0)).unwrap();
+    assert_eq!(rows, 0);
+}
+
+#[test]
+fn other_tools_can_write_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let conn = db::open(dir.path(), None).unwrap();
+    let biology = subject(&conn, "Biology");
+    let edited = entry(&conn, biology.id, "2024-01-01", &[1]).entry.id;
+    write_notes(&conn, edited, "<p>Mitochondria</p>", None, None);
+    drop(conn);
+
+    // A plain connection, as the sqlite3 shell would open it, without strip_html
+    let plain = Connection::open(db::db_path(dir.path())).unwrap();
+    plain
+        .execute("UPDATE entries SET study_notes = '<p>Ribosomes</p>' WHERE id = ?1", [edited])
+        .unwrap();
+    plain
+        .execute(
+            "INSERT INTO entries (subject_id, study_date, study_notes) VALUES (?1, '2024-01-02', '<p>Golgi</p>')",
+            [biology.id],
+        )
+        .unwrap();
+    let added = plain.last_insert_rowid();
+    drop(plain);
+
+    let conn = db::open(dir.path(), None).unwrap();
+    let all = SearchFilters::default();
+    assert!(entry_ids(&conn, "mitochondria", &all).is_empty());
+    assert_eq!(entry_ids(&conn, "ribosomes", &all), [edited]);
+    assert_eq!(entry_ids(&conn, "golgi", &all), [added]);
+}
+
+#[test]
+fn filters_narrow_the_results() {
+    let conn = memory_db();
+    let biology = subject(&conn, "Biology");
+    let chemistry = subject(&conn, "Chemistry");
+    let january = entry(&conn, biology.id, "2024-01-10", &[1]).entry.id;
+    let march = entry(&conn, biology.id, "2024-03-10", &[1]).entry.id;
+    let other = entry(&conn, chemistry.id, "2024-01-10", &[1]).entry.id;
+    for id in [january, march, other] {
+        write_notes(&conn, id, "<p>Enzymes and catalysts</p>", None, None);
+    }
+    queries::link_tags_to_entry(&conn, march, &["exam".to_string()]).unwrap();
+
+    let mut ids = entry_ids(&conn, "enzymes", &SearchFilters::default());
+    ids.sort();
+    assert_eq!(ids, [january, march, other]);
+
+    let by_subject = SearchFilters { subject_id: Some(chemistry.id), ..Default::default() };
+    assert_eq!(entry_ids(&conn, "enzymes", &by_subject), [other]);
+
+    let by_tag = SearchFilters { tag: Some(" Exam ".to_string()), ..Default::default() };
+    assert_eq!(entry_ids(&conn, "enzymes", &by_tag), [march]);
+
+    let by_date = SearchFilters {
+        subject_id: Some(biology.id),
+        start_date: Some("2024-01-01".to_string()),
+        end_date: Some("2024-01-31".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(entry_ids(&conn, "enzymes", &by_date), [january]);
+
+    let limited = SearchFilters { limit: Some(2), ..Default::default() };
+    assert_eq!(entry_ids(&conn, "enzymes", &limited).len(), 2);
+}
+
+#[test]
+fn topic_matches_rank_first_and_snippets_mark_matches() {
+    let conn = memory_db();
+    let biology = subject(&conn, "Biology");
+    let in_notes = entry(&conn, biology.id, "2024-01-01", &[1]).entry.id;
+    let in_topics = entry(&conn, biology.id, "2024-01-02", &[1]).entry.id;
+    write_notes(&conn, in_notes, "<p>Long notes that mention osmosis once among many other words</p>", None, None);
+    write_notes(&conn, in_topics, "<p>Water movement</p>", None, Some("Osmosis"));
+
+    let hits = search::search_entries(&conn, "osmosis", &SearchFilters::default()).unwrap();
+    assert_eq!(hits[0].entry_id, in_topics);
+    assert_eq!(hits[0].subject_name, "Biology");
+    assert!(hits[1].snippet.contains("<mark>osmosis</mark>"));
+    assert!(hits[0].score <= hits[1].score);
+}
+
+#[test]
+fn user_input_never_breaks_the_query() {
+    let conn = memory_db();
+    let physics = subject(&conn, "Physics");
+    let id = entry(&conn, physics.id, "2024-01-01", &[1]).entry.id;
+    write_notes(&conn, id, "<p>Newton's second law: F = ma</p>", None, None);
+    let all = SearchFilters::default();
+
+    for input in ["AND", "OR (", "\"unterminated", "law*", "NEAR(a b)", "col:law", "^", "-", ""] {
+        search::search_entries(&conn, input, &all).unwrap();
+    }
+    assert_eq!(entry_ids(&conn, "\"second law\"", &all), [id]);
+    assert!(entry_ids(&conn, "\"law second\"", &all).is_empty());
+    assert_eq!(entry_ids(&conn, "newton's", &all), [id]);
+    assert!(entry_ids(&conn, "   ", &all).is_empty());
+}
+
+#[test]
+fn index_survives_rebuild_reopen_and_export() {
+    let dir = tempfile::tempdir().unwrap();
+    let conn = db::open(dir.path(), Some("secret")).unwrap();
+    let chemistry = subject(&conn, "Chemistry");
+    let id = entry(&conn, chemistry.id, "2024-01-01", &[1]).entry.id;
+    write_notes(&conn, id, "<p>Covalent bonds</p>", None, None);
+
+    conn.execute("DELETE FROM entry_search", []).unwrap();
+    assert!(entry_ids(&conn, "covalent", &SearchFilters::default()).is_empty());
+    search::rebuild_index(&conn).unwrap();
+    assert_eq!(entry_ids(&conn, "covalent", &SearchFilters::default()), [id]);
+
+    // Reopened connections register strip_html for the triggers
+    drop(conn);
+    let conn = db::open(dir.path(), Some("secret")).unwrap();
+    write_notes(&conn, id, "<p>Ionic bonds</p>", None, None);
+    assert_eq!(entry_ids(&conn, "ionic", &SearchFilters::default()), [id]);
+
+    // Removing encryption exports the index along with the tables
+    let plain = dir.path().join("plain");
+    std::fs::create_dir_all(&plain).unwrap();
+    db::export(&conn, &db::db_path(&plain), "").unwrap();
+    drop(conn);
+    let conn = db::open(&plain, None).unwrap();
+    assert_eq!(entry_ids(&conn, "ionic", &SearchFilters::default()), [id]);
+}
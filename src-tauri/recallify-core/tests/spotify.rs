@@ -0,0 +1,40 @@
+mod common;
+
+use common::memory_db;
+use recallify_core::spotify;
+
+#[test]
+fn login_is_stored_until_logout() {
+    let conn = memory_db();
+    assert!(spotify::get_auth(&conn).unwrap().is_none());
+
+    spotify::save_auth(&conn, "access", "refresh", "2030-01-01T00:00:00Z", 0).unwrap();
+    spotify::update_device(&conn, "device-1").unwrap();
+    spotify::save_playback_state(&conn, Some("spotify:playlist:1"), None, 42_000).unwrap();
+
+    let auth = spotify::get_auth(&conn).unwrap().unwrap();
+    assert_eq!(auth.access_token, "access");
+    assert_eq!(auth.is_premium, 0);
+    assert_eq!(auth.device_id.as_deref(), Some("device-1"));
+    assert_eq!(auth.last_playlist_uri.as_deref(), Some("spotify:playlist:1"));
+    assert_eq!(auth.last_position_ms, 42_000);
+
+    let json = serde_json::to_value(&auth).unwrap();
+    assert_eq!(json["accessToken"], "access");
+    assert_eq!(json["lastPositionMs"], 42_000);
+
+    spotify::logout(&conn).unwrap();
+    assert!(spotify::get_auth(&conn).unwrap().is_none());
+}
+
+#[test]
+fn saving_a_new_login_resets_playback_state() {
+    let conn = memory_db();
+    spotify::save_auth(&conn, "a", "r", "2030-01-01T00:00:00Z", 1).unwrap();
+    spotify::update_device(&conn, "device-1").unwrap();
+
+    spotify::save_auth(&conn, "b", "r", "2030-01-01T00:00:00Z", 1).unwrap();
+    let auth = spotify::get_auth(&conn).unwrap().unwrap();
+    assert_eq!(auth.access_token, "b");
+    assert_eq!(auth.device_id, None);
+}
@@ -0,0 +1,303 @@
+use crate::{backup, db};
+use recallify_core::error::{AppError, ResultExt};
+use recallify_core::{files, queries};
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+// Scheduled backups. A background thread wakes up every minute, reads the
+// schedule from the settings table and takes a snapshot when one is due.
+// The first successful run in any seven days is kept as a weekly copy, the
+// rest as daily copies; each kind is rotated separately. Manual backups
+// taken from Settings are recorded too but never rotated.
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+// How long to wait before retrying after a failed scheduled run
+const RETRY_AFTER: &str = "-1 hours";
+
+// Failed runs are only kept for troubleshooting
+const KEEP_FAILURES_FOR: &str = "-30 days";
+
+#[derive(Serialize)]
+pub struct BackupRun {
+    pub id: i64,
+    pub kind: String,
+    pub status: String,
+    pub file_path: Option<String>,
+    pub pdfs_path: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub error: Option<String>,
+    pub started_at: String,
+    pub finished_at: String,
+}
+
+impl BackupRun {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(BackupRun {
+            id: row.get("id")?,
+            kind: row.get("kind")?,
+            status: row.get("status")?,
+            file_path: row.get("file_path")?,
+            pdfs_path: row.get("pdfs_path")?,
+            size_bytes: row.get("size_bytes")?,
+            error: row.get("error")?,
+            started_at: row.get("started_at")?,
+            finished_at: row.get("finished_at")?,
+        })
+    }
+}
+
+struct Schedule {
+    enabled: bool,
+    interval_hours: i64,
+    keep_daily: i64,
+    keep_weekly: i64,
+    include_pdfs: bool,
+}
+
+fn setting<T: std::str::FromStr>(conn: &Connection, key: &str, default: T) -> rusqlite::Result<T> {
+    Ok(queries::get_setting(conn, key)?
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default))
+}
+
+fn load_schedule(conn: &Connection) -> rusqlite::Result<Schedule> {
+    Ok(Schedule {
+        enabled: setting(conn, "auto_backup_enabled", true)?,
+        interval_hours: setting(conn, "auto_backup_interval_hours", 24i64)?.max(1),
+        keep_daily: setting(conn, "auto_backup_keep_daily", 7i64)?.max(1),
+        keep_weekly: setting(conn, "auto_backup_keep_weekly", 4i64)?.max(0),
+        include_pdfs: setting(conn, "auto_backup_include_pdfs", false)?,
+    })
+}
+
+fn backups_dir() -> PathBuf {
+    files::backups_dir(&crate::get_app_data_dir())
+}
+
+fn pdfs_dir() -> PathBuf {
+    files::pdfs_dir(&crate::get_app_data_dir())
+}
+
+fn is_due(conn: &Connection, interval_hours: i64) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT NOT EXISTS (
+            SELECT 1 FROM backup_runs
+            WHERE kind != 'manual'
+              AND ((status = 'success' AND started_at > datetime('now', ?1))
+                OR (status = 'failed' AND started_at > datetime('now', ?2)))
+        )",
+        [format!("-{} hours", interval_hours), RETRY_AFTER.to_string()],
+        |row| row.get(0),
+    )
+}
+
+fn weekly_due(conn: &Connection) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT NOT EXISTS (
+            SELECT 1 FROM backup_runs
+            WHERE kind = 'weekly' AND status = 'success' AND started_at > datetime('now', '-7 days')
+        )",
+        [],
+        |row| row.get(0),
+    )
+}
+
+// Copies the files of `src` into `dest`, overwriting files with the same name.
+fn copy_files(src: &Path, dest: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dest)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            fs::copy(entry.path(), dest.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+fn remove_backup_files(file_path: Option<&str>, pdfs_path: Option<&str>) {
+    if let Some(file_path) = file_path {
+        if let Err(e) = fs::remove_file(file_path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                eprintln!("Failed to remove old backup {}: {}", file_path, e);
+            }
+        }
+    }
+    if let Some(pdfs_path) = pdfs_path {
+        if let Err(e) = fs::remove_dir_all(pdfs_path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                eprintln!("Failed to remove old PDF backup {}: {}", pdfs_path, e);
+            }
+        }
+    }
+}
+
+fn snapshot(file_path: &Path, pdfs_path: Option<&Path>) -> Result<i64, AppError> {
+    fs::create_dir_all(backups_dir()).context("Failed to create backups directory")?;
+    backup::backup_database(file_path)?;
+    if let Some(pdfs_path) = pdfs_path {
+        if pdfs_dir().exists() {
+            copy_files(&pdfs_dir(), pdfs_path).context("Failed to copy PDFs")?;
+        }
+    }
+    let size = fs::metadata(file_path)?.len();
+    Ok(size as i64)
+}
+
+// Takes a backup of the given kind, records the run and rotates old copies.
+// A failed run is recorded as well and reported back as the error.
+pub fn run_backup(kind: &str) -> Result<BackupRun, AppError> {
+    let (started_at, stamp, schedule) = {
+        let conn = db::reader()?;
+        let (started_at, stamp): (String, String) =
+            conn.query_row("SELECT CURRENT_TIMESTAMP, strftime('%Y%m%d-%H%M%S', 'now')", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?;
+        (started_at, stamp, load_schedule(&conn)?)
+    };
+
+    let file_path = backups_dir().join(format!("recallify-{}-{}.db", stamp, kind));
+    let pdfs_path = schedule
+        .include_pdfs
+        .then(|| backups_dir().join(format!("recallify-{}-{}-pdfs", stamp, kind)));
+
+    let result = snapshot(&file_path, pdfs_path.as_deref());
+    let (status, size_bytes, error) = match &result {
+        Ok(size) => ("success", Some(*size), None),
+        Err(e) => {
+            remove_backup_files(file_path.to_str(), pdfs_path.as_deref().and_then(Path::to_str));
+            ("failed", None, Some(e.to_string()))
+        }
+    };
+    let succeeded = result.is_ok();
+    let file_path = succeeded.then(|| file_path.to_string_lossy().into_owned());
+    let pdfs_path = pdfs_path.filter(|_| succeeded).map(|p| p.to_string_lossy().into_owned());
+
+    let conn = db::writer()?;
+    conn.execute(
+        "INSERT INTO backup_runs (kind, status, file_path, pdfs_path, size_bytes, error, started_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![kind, status, file_path, pdfs_path, size_bytes, error, started_at],
+    )?;
+    let run = get_run(&conn, conn.last_insert_rowid())?
+        .ok_or_else(|| AppError::database("Backup run was not recorded"))?;
+
+    if let Err(e) = rotate(&conn, &schedule) {
+        eprintln!("Failed to rotate backups: {}", e);
+    }
+
+    result.map(|_| run)
+}
+
+// Deletes daily and weekly copies beyond the configured counts, newest kept.
+fn rotate(conn: &Connection, schedule: &Schedule) -> rusqlite::Result<()> {
+    for (kind, keep) in [("daily", schedule.keep_daily), ("weekly", schedule.keep_weekly)] {
+        let expired = {
+            let mut stmt = conn.prepare(
+                "SELECT id, file_path, pdfs_path FROM backup_runs
+                 WHERE kind = ?1 AND status = 'success'
+                 ORDER BY started_at DESC, id DESC
+                 LIMIT -1 OFFSET ?2",
+            )?;
+            let rows = stmt.query_map(rusqlite::params![kind, keep], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                ))
+            })?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        };
+
+        for (id, file_path, pdfs_path) in expired {
+            remove_backup_files(file_path.as_deref(), pdfs_path.as_deref());
+            conn.execute("DELETE FROM backup_runs WHERE id = ?1", [id])?;
+        }
+    }
+
+    conn.execute(
+        "DELETE FROM backup_runs WHERE status = 'failed' AND started_at < datetime('now', ?1)",
+        [KEEP_FAILURES_FOR],
+    )?;
+    Ok(())
+}
+
+fn get_run(conn: &Connection, id: i64) -> rusqlite::Result<Option<BackupRun>> {
+    conn.query_row("SELECT * FROM backup_runs WHERE id = ?1", [id], BackupRun::from_row)
+        .optional()
+}
+
+pub fn list_runs() -> Result<Vec<BackupRun>, AppError> {
+    let conn = db::reader()?;
+    let mut stmt = conn.prepare("SELECT * FROM backup_runs ORDER BY started_at DESC, id DESC")?;
+    let runs = stmt
+        .query_map([], BackupRun::from_row)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(runs)
+}
+
+// Restores the database from a recorded run, then copies its PDFs back
+// into the pdfs directory. Existing PDFs with other names are left alone.
+pub fn restore_run(id: i64, passphrase: Option<&str>) -> Result<(), AppError> {
+    let run = {
+        let conn = db::reader()?;
+        get_run(&conn, id)?.ok_or_else(|| AppError::not_found(format!("Backup run {} not found", id)))?
+    };
+    let file_path = match (run.status.as_str(), &run.file_path) {
+        ("success", Some(file_path)) => PathBuf::from(file_path),
+        _ => return Err(AppError::validation("This backup run did not produce a backup")),
+    };
+    if !file_path.exists() {
+        return Err(AppError::not_found(format!(
+            "Backup file {} no longer exists",
+            file_path.display()
+        )));
+    }
+
+    backup::restore_database(&file_path, passphrase)?;
+
+    if let Some(pdfs_path) = run.pdfs_path.as_deref().map(Path::new) {
+        if pdfs_path.exists() {
+            copy_files(pdfs_path, &pdfs_dir()).context("Database restored, but copying PDFs back failed")?;
+        }
+    }
+    Ok(())
+}
+
+fn tick() -> Result<(), AppError> {
+    let kind = {
+        // Nothing to back up while an encrypted database is still locked
+        let conn = match db::reader() {
+            Ok(conn) => conn,
+            Err(_) => return Ok(()),
+        };
+        let schedule = load_schedule(&conn)?;
+        if !schedule.enabled || !is_due(&conn, schedule.interval_hours)? {
+            return Ok(());
+        }
+        if weekly_due(&conn)? && schedule.keep_weekly > 0 {
+            "weekly"
+        } else {
+            "daily"
+        }
+    };
+    run_backup(kind).map(|run| println!("Automatic {} backup written to {:?}", run.kind, run.file_path))
+}
+
+pub fn start() {
+    let spawned = thread::Builder::new()
+        .name("auto-backup".to_string())
+        .spawn(|| loop {
+            thread::sleep(CHECK_INTERVAL);
+            if let Err(e) = tick() {
+                eprintln!("Automatic backup failed: {}", e);
+            }
+        });
+    if let Err(e) = spawned {
+        eprintln!("Failed to start automatic backups: {}", e);
+    }
+}
@@ -0,0 +1,91 @@
+use crate::db;
+use recallify_core::error::{AppError, ResultExt};
+use recallify_core::migrations;
+use rusqlite::{Connection, OpenFlags};
+use std::fs;
+use std::path::Path;
+
+// Backups are made with SQLite's online backup API, so they are consistent
+// even while the app keeps writing. The copy carries the same key as the
+// live database: an encrypted database produces an encrypted backup.
+
+// Tables every Recallify database has had since the first schema version
+const REQUIRED_TABLES: [&str; 5] = ["subjects", "entries", "revision_intervals", "revisions", "settings"];
+
+pub fn backup_database(dest: &Path) -> Result<(), AppError> {
+    if dest == db::db_path() {
+        return Err(AppError::validation("Choose a location other than the live database"));
+    }
+    let key = db::current_key()?;
+    let source = db::reader()?;
+    db::copy_database(&source, dest, key.as_deref())
+}
+
+fn table_exists(conn: &Connection, table: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1)",
+        [table],
+        |row| row.get(0),
+    )
+}
+
+// Checks that `conn` holds a Recallify database this build can open.
+fn validate(conn: &Connection) -> Result<(), AppError> {
+    let check: String = conn
+        .query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .context("Backup could not be read")?;
+    if check != "ok" {
+        return Err(AppError::validation(check).context("Backup is damaged"));
+    }
+
+    let version = migrations::current_version(conn)?;
+    if version > migrations::latest_version() {
+        return Err(AppError::validation(format!(
+            "Backup uses schema version {}, but this version of Recallify only supports up to {}",
+            version,
+            migrations::latest_version()
+        )));
+    }
+
+    for table in REQUIRED_TABLES {
+        if !table_exists(conn, table)? {
+            return Err(AppError::validation(format!(
+                "Backup is not a Recallify database (missing table {})",
+                table
+            )));
+        }
+    }
+    Ok(())
+}
+
+// Replaces the live database with the one at `source` and reopens it.
+// Encrypted backups need the passphrase they were made with, which then
+// becomes the passphrase of the restored database. Older backups are
+// migrated when reopened.
+pub fn restore_database(source: &Path, passphrase: Option<&str>) -> Result<(), AppError> {
+    let encrypted = db::is_encrypted(source).context(source.display().to_string())?;
+    let key = match (encrypted, passphrase) {
+        (true, Some(passphrase)) if !passphrase.is_empty() => Some(passphrase),
+        (true, _) => return Err(AppError::validation("Backup is encrypted; enter its passphrase")),
+        (false, _) => None,
+    };
+
+    let conn = Connection::open_with_flags(source, OpenFlags::SQLITE_OPEN_READ_ONLY).context("Failed to open backup")?;
+    db::apply_key(&conn, key).map_err(|e| match key {
+        Some(_) => db::key_error(e),
+        None => AppError::validation(e.to_string()).context("Backup is not a readable database"),
+    })?;
+    validate(&conn)?;
+
+    // Copy through the backup API rather than the file system so pages
+    // still sitting in the backup's WAL are included.
+    let staged = db::db_path().with_extension("db.restore");
+    db::copy_database(&conn, &staged, key)?;
+    drop(conn);
+
+    let replaced = db::replace_file(&staged, key);
+    if replaced.is_err() {
+        let _ = fs::remove_file(&staged);
+    }
+    replaced
+}
@@ -0,0 +1,421 @@
+use crate::db;
+use recallify_core::error::AppError;
+use recallify_core::models::*;
+use recallify_core::fsrs::Optimization;
+use recallify_core::load_balance::LoadBalancing;
+use recallify_core::clock::{self, TimezoneInfo};
+use recallify_core::{catch_up, forecast, queries, review_log, scheduler, search};
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+
+// Typed Tauri commands. These are the only database entry points the
+// webview gets in release builds; see `raw_sql_enabled` in main.rs.
+//
+// Commands that only read use `with_read_db` so they run on the read pool
+// instead of queueing behind writes.
+
+fn with_db<T>(f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, AppError> {
+    let db = db::writer()?;
+    Ok(f(&db)?)
+}
+
+fn with_read_db<T>(f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, AppError> {
+    let db = db::reader()?;
+    Ok(f(&db)?)
+}
+
+// Subjects
+
+#[tauri::command]
+pub fn get_subjects() -> Result<Vec<SubjectWithStats>, AppError> {
+    with_read_db(queries::get_subjects)
+}
+
+#[tauri::command]
+pub fn get_subject_names() -> Result<Vec<SubjectName>, AppError> {
+    with_read_db(queries::get_subject_names)
+}
+
+#[tauri::command]
+pub fn get_subject(id: i64) -> Result<Subject, AppError> {
+    with_read_db(|db| queries::get_subject(db, id))
+}
+
+#[tauri::command]
+pub fn create_subject(name: String) -> Result<Subject, AppError> {
+    with_db(|db| queries::create_subject(db, &name))
+}
+
+#[tauri::command]
+pub fn update_subject(id: i64, name: String) -> Result<Subject, AppError> {
+    with_db(|db| queries::update_subject(db, id, &name))
+}
+
+#[tauri::command]
+pub fn delete_subject(id: i64) -> Result<(), AppError> {
+    with_db(|db| queries::delete_subject(db, id))
+}
+
+// Entries
+
+#[tauri::command]
+pub fn get_entries_by_subject(subject_id: i64) -> Result<Vec<EntryWithDetails>, AppError> {
+    with_read_db(|db| queries::get_entries_by_subject(db, subject_id))
+}
+
+#[tauri::command]
+pub fn get_entry(id: i64) -> Result<EntryWithDetails, AppError> {
+    with_read_db(|db| queries::get_entry(db, id))
+}
+
+#[tauri::command]
+pub fn create_entry(entry: NewEntry) -> Result<EntryWithDetails, AppError> {
+    with_db(|db| queries::create_entry(db, &entry))
+}
+
+#[tauri::command]
+pub fn update_entry(id: i64, update: EntryUpdate) -> Result<EntryWithDetails, AppError> {
+    with_db(|db| queries::update_entry(db, id, &update))
+}
+
+#[tauri::command]
+pub fn delete_entry(id: i64) -> Result<(), AppError> {
+    with_db(|db| queries::delete_entry(db, id))
+}
+
+// Revisions
+
+#[tauri::command]
+pub fn get_revisions_due_today() -> Result<Vec<RevisionWithDetails>, AppError> {
+    with_read_db(queries::get_revisions_due_today)
+}
+
+#[tauri::command]
+pub fn get_revisions_by_date(date: String) -> Result<Vec<RevisionWithDetails>, AppError> {
+    with_read_db(|db| queries::get_revisions_by_date(db, &date))
+}
+
+#[tauri::command]
+pub fn get_revisions_between(start_date: String, end_date: String) -> Result<Vec<Revision>, AppError> {
+    with_read_db(|db| queries::get_revisions_between(db, &start_date, &end_date))
+}
+
+#[tauri::command]
+pub fn complete_revision(id: i64) -> Result<(), AppError> {
+    with_db(|db| queries::complete_revision(db, id))
+}
+
+// Completes a revision with a 0-5 grade and schedules the next one
+#[tauri::command]
+pub fn grade_revision(id: i64, quality: u8, duration_seconds: Option<u32>) -> Result<RevisionGrade, AppError> {
+    let db = db::writer()?;
+    scheduler::grade_revision(&db, id, quality, duration_seconds)
+}
+
+// Takes back a grade and reopens the revision
+#[tauri::command]
+pub fn undo_review(id: i64) -> Result<Revision, AppError> {
+    let db = db::writer()?;
+    scheduler::undo_review(&db, id)
+}
+
+#[tauri::command]
+pub fn get_review_log(filters: Option<ReviewLogFilters>) -> Result<Vec<ReviewLog>, AppError> {
+    with_read_db(|db| review_log::get_review_log(db, &filters.unwrap_or_default()))
+}
+
+#[tauri::command]
+pub fn get_retention_stats(filters: Option<ReviewLogFilters>) -> Result<RetentionStats, AppError> {
+    with_read_db(|db| review_log::retention_stats(db, &filters.unwrap_or_default()))
+}
+
+// Reviews due per day for the next `days` days, with estimated minutes
+#[tauri::command]
+pub fn forecast_reviews(days: u32, subject_id: Option<i64>) -> Result<ReviewForecast, AppError> {
+    let db = db::reader()?;
+    forecast::forecast_reviews(&db, days, subject_id)
+}
+
+// Spreads the overdue revisions over the coming days; a dry run only
+// returns the plan
+#[tauri::command]
+pub fn plan_catch_up(max_per_day: i64, strategy: CatchUpStrategy, dry_run: bool) -> Result<CatchUpPlan, AppError> {
+    if dry_run {
+        let db = db::reader()?;
+        catch_up::plan_catch_up(&db, max_per_day, strategy, true)
+    } else {
+        let db = db::writer()?;
+        catch_up::plan_catch_up(&db, max_per_day, strategy, false)
+    }
+}
+
+// Fits the FSRS parameters to the revision history and saves them
+#[tauri::command]
+pub fn optimize_fsrs_parameters() -> Result<Optimization, AppError> {
+    // Writes carry on while the fit runs; the writer is only taken to save
+    let optimization = {
+        let db = db::reader()?;
+        scheduler::fit_fsrs(&db)?
+    };
+    let db = db::writer()?;
+    scheduler::save_fsrs_parameters(&db, &optimization)?;
+    Ok(optimization)
+}
+
+#[tauri::command]
+pub fn reset_fsrs_parameters() -> Result<(), AppError> {
+    let db = db::writer()?;
+    scheduler::reset_fsrs(&db)
+}
+
+#[tauri::command]
+pub fn get_load_balancing() -> Result<LoadBalancing, AppError> {
+    with_read_db(LoadBalancing::from_settings)
+}
+
+// Applies to revisions created from now on
+#[tauri::command]
+pub fn set_load_balancing(settings: LoadBalancing) -> Result<LoadBalancing, AppError> {
+    let db = db::writer()?;
+    settings.save(&db)?;
+    Ok(LoadBalancing::from_settings(&db)?)
+}
+
+#[tauri::command]
+pub fn uncomplete_revision(id: i64) -> Result<(), AppError> {
+    let db = db::writer()?;
+    scheduler::uncomplete_revision(&db, id).map(|_| ())
+}
+
+#[tauri::command]
+pub fn reschedule_revision(id: i64, new_date: String) -> Result<Revision, AppError> {
+    with_db(|db| queries::reschedule_revision(db, id, &new_date))
+}
+
+#[tauri::command]
+pub fn update_overdue_revisions() -> Result<usize, AppError> {
+    with_db(queries::update_overdue_revisions)
+}
+
+// Search
+
+#[tauri::command]
+pub fn search_entries(query: String, filters: Option<SearchFilters>) -> Result<Vec<SearchHit>, AppError> {
+    let filters = filters.unwrap_or_default();
+    with_read_db(|db| search::search_entries(db, &query, &filters))
+}
+
+// Activity log
+
+#[tauri::command]
+pub fn get_activity_log(filters: Option<ActivityLogFilters>) -> Result<Vec<ActivityLogWithDetails>, AppError> {
+    let filters = filters.unwrap_or_default();
+    with_read_db(|db| queries::get_activity_log(db, &filters))
+}
+
+// Settings
+
+#[tauri::command]
+pub fn get_settings() -> Result<BTreeMap<String, String>, AppError> {
+    with_read_db(queries::get_settings)
+}
+
+#[tauri::command]
+pub fn update_setting(key: String, value: String) -> Result<(), AppError> {
+    // Connections hold on to the zone, so they have to follow the setting
+    if key == clock::TIMEZONE_SETTING {
+        return set_timezone(Some(value)).map(|_| ());
+    }
+    with_db(|db| queries::update_setting(db, &key, &value))
+}
+
+#[tauri::command]
+pub fn get_timezone() -> Result<TimezoneInfo, AppError> {
+    with_read_db(clock::info)
+}
+
+// None or an empty name follows the system time zone again
+#[tauri::command]
+pub fn set_timezone(timezone: Option<String>) -> Result<TimezoneInfo, AppError> {
+    let info = {
+        let db = db::writer()?;
+        clock::set_timezone(&db, timezone.as_deref())?
+    };
+    // The readers still have the previous zone
+    db::reopen_readers()?;
+    Ok(info)
+}
+
+// Syllabus
+
+#[tauri::command]
+pub fn get_syllabus_items(subject_id: i64) -> Result<Vec<SyllabusItemWithCount>, AppError> {
+    with_read_db(|db| queries::get_syllabus_items(db, subject_id))
+}
+
+#[tauri::command]
+pub fn create_syllabus_item(item: NewSyllabusItem) -> Result<i64, AppError> {
+    with_db(|db| queries::create_syllabus_item(db, &item))
+}
+
+#[tauri::command]
+pub fn update_syllabus_item(id: i64, update: SyllabusItemUpdate) -> Result<(), AppError> {
+    with_db(|db| queries::update_syllabus_item(db, id, &update))
+}
+
+#[tauri::command]
+pub fn delete_syllabus_item(id: i64) -> Result<(), AppError> {
+    with_db(|db| queries::delete_syllabus_item(db, id))
+}
+
+#[tauri::command]
+pub fn link_entry_to_syllabus(entry_id: i64, syllabus_item_ids: Vec<i64>) -> Result<(), AppError> {
+    with_db(|db| queries::link_entry_to_syllabus(db, entry_id, &syllabus_item_ids))
+}
+
+#[tauri::command]
+pub fn get_entry_syllabus_links(entry_id: i64) -> Result<Vec<SyllabusItem>, AppError> {
+    with_read_db(|db| queries::get_entry_syllabus_links(db, entry_id))
+}
+
+// PDF attachments
+
+#[tauri::command]
+pub fn get_pdf_attachments(entry_id: i64) -> Result<Vec<PdfAttachment>, AppError> {
+    with_read_db(|db| queries::get_pdf_attachments(db, entry_id))
+}
+
+#[tauri::command]
+pub fn get_pdf_attachment(id: i64) -> Result<Option<PdfAttachment>, AppError> {
+    with_read_db(|db| queries::get_pdf_attachment(db, id))
+}
+
+#[tauri::command]
+pub fn create_pdf_attachment(pdf: NewPdfAttachment) -> Result<i64, AppError> {
+    with_db(|db| queries::create_pdf_attachment(db, &pdf))
+}
+
+#[tauri::command]
+pub fn update_pdf_last_viewed_page(id: i64, page_number: i64) -> Result<(), AppError> {
+    with_db(|db| queries::update_pdf_last_viewed_page(db, id, page_number))
+}
+
+#[tauri::command]
+pub fn delete_pdf_attachment(id: i64) -> Result<(), AppError> {
+    with_db(|db| queries::delete_pdf_attachment(db, id))
+}
+
+// Pomodoro
+
+#[tauri::command]
+pub fn record_pomodoro_session(session: NewPomodoroSession) -> Result<i64, AppError> {
+    with_db(|db| queries::record_pomodoro_session(db, &session))
+}
+
+#[tauri::command]
+pub fn get_pomodoro_sessions() -> Result<Vec<PomodoroSessionWithSubject>, AppError> {
+    with_read_db(queries::get_pomodoro_sessions)
+}
+
+#[tauri::command]
+pub fn get_pomodoro_state() -> Result<PomodoroState, AppError> {
+    with_read_db(queries::get_pomodoro_state)
+}
+
+#[tauri::command]
+pub fn update_pomodoro_state(update: PomodoroStateUpdate) -> Result<PomodoroState, AppError> {
+    with_db(|db| queries::update_pomodoro_state(db, &update))
+}
+
+#[tauri::command]
+pub fn get_pomodoro_stats(range: StatsRange) -> Result<PomodoroStats, AppError> {
+    with_read_db(|db| queries::get_pomodoro_stats(db, range))
+}
+
+#[tauri::command]
+pub fn get_subject_pomodoro_totals() -> Result<Vec<SubjectPomodoroTotal>, AppError> {
+    with_read_db(queries::get_subject_pomodoro_totals)
+}
+
+#[tauri::command]
+pub fn get_study_time_by_subject(subject_id: i64, days: i64) -> Result<StudyTime, AppError> {
+    with_read_db(|db| queries::get_study_time_by_subject(db, subject_id, days))
+}
+
+#[tauri::command]
+pub fn get_all_subjects_study_time(days: i64) -> Result<Vec<SubjectStudyTime>, AppError> {
+    with_read_db(|db| queries::get_all_subjects_study_time(db, days))
+}
+
+#[tauri::command]
+pub fn get_today_pomodoro_summary() -> Result<PomodoroSummary, AppError> {
+    with_read_db(queries::get_today_pomodoro_summary)
+}
+
+// Tags
+
+#[tauri::command]
+pub fn get_all_tags() -> Result<Vec<Tag>, AppError> {
+    with_read_db(queries::get_all_tags)
+}
+
+#[tauri::command]
+pub fn get_or_create_tag(name: String) -> Result<i64, AppError> {
+    if name.trim().is_empty() {
+        return Err(AppError::validation("Tag name cannot be empty"));
+    }
+    with_db(|db| queries::get_or_create_tag(db, &name))
+}
+
+#[tauri::command]
+pub fn link_tags_to_entry(entry_id: i64, tag_names: Vec<String>) -> Result<(), AppError> {
+    with_db(|db| queries::link_tags_to_entry(db, entry_id, &tag_names))
+}
+
+#[tauri::command]
+pub fn get_entry_tags(entry_id: i64) -> Result<Vec<Tag>, AppError> {
+    with_read_db(|db| queries::get_entry_tags(db, entry_id))
+}
+
+// Daily activity and streaks
+
+#[tauri::command]
+pub fn update_daily_activity(date: Option<String>) -> Result<(), AppError> {
+    with_db(|db| {
+        let date = match date {
+            Some(date) => date,
+            None => clock::today(db)?,
+        };
+        queries::update_daily_activity(db, &date)
+    })
+}
+
+#[tauri::command]
+pub fn get_daily_activities(start_date: String, end_date: String) -> Result<Vec<DailyActivity>, AppError> {
+    with_read_db(|db| queries::get_daily_activities(db, &start_date, &end_date))
+}
+
+#[tauri::command]
+pub fn get_active_dates() -> Result<Vec<String>, AppError> {
+    with_read_db(queries::get_active_dates)
+}
+
+#[tauri::command]
+pub fn get_streak_stats() -> Result<StreakStats, AppError> {
+    with_read_db(queries::streak_stats)
+}
+
+#[tauri::command]
+pub fn record_milestone(current_streak: i64) -> Result<bool, AppError> {
+    with_db(|db| queries::record_milestone(db, current_streak))
+}
+
+#[tauri::command]
+pub fn mark_milestone_shown(milestone_days: i64) -> Result<(), AppError> {
+    with_db(|db| queries::mark_milestone_shown(db, milestone_days))
+}
+
+#[tauri::command]
+pub fn get_milestone_achievements() -> Result<Vec<MilestoneCelebration>, AppError> {
+    with_read_db(queries::get_milestone_achievements)
+}
@@ -0,0 +1,81 @@
+use crate::db;
+use recallify_core::error::AppError;
+use recallify_core::{clock, queries};
+use serde::Serialize;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+// Local midnight rollover. Revisions are marked overdue whenever a database
+// is opened (see db::open); this thread does it again when the local date
+// changes and tells the webview, so open pages refresh their due lists.
+//
+// It sleeps until midnight but wakes at least every minute to compare
+// dates, since sleeping through a suspend or a clock or time zone change
+// would otherwise miss the rollover.
+
+pub const DAY_CHANGED_EVENT: &str = "day-changed";
+
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DayChanged {
+    // The new local date, YYYY-MM-DD
+    date: String,
+    // Revisions that just became overdue
+    overdue: usize,
+}
+
+// The writer is only taken when there is something to mark overdue, so
+// the check never waits behind a long write.
+fn roll_over(app: &AppHandle, date: String) -> Result<(), AppError> {
+    let overdue = match db::reader() {
+        Ok(reader) if queries::has_revisions_to_mark_overdue(&reader)? => {
+            drop(reader);
+            let db = db::writer()?;
+            queries::update_overdue_revisions(&db)?
+        }
+        Ok(_) => 0,
+        // Locked: statuses are brought up to date when it is unlocked
+        Err(_) => 0,
+    };
+    app.emit_all(DAY_CHANGED_EVENT, DayChanged { date, overdue })
+        .map_err(|e| AppError::internal(e.to_string()))
+}
+
+// The local date of the open database and how long until its midnight;
+// None while it is locked
+fn local_clock() -> Option<(String, Duration)> {
+    let db = db::reader().ok()?;
+    let today = clock::today(&db).ok()?;
+    let timezone = clock::timezone(&db).ok()?;
+    Some((today, clock::until_midnight(&timezone)))
+}
+
+// Midnight in the time zone of the open database (see clock.rs), so
+// changing the zone or switching profiles can roll the date over too
+fn run(app: AppHandle) {
+    let mut today = local_clock().map(|(date, _)| date);
+    loop {
+        thread::sleep(local_clock().map_or(MAX_SLEEP, |(_, wait)| wait.min(MAX_SLEEP)));
+        let Some((date, _)) = local_clock() else {
+            continue;
+        };
+        if today.as_ref().is_some_and(|today| *today != date) {
+            if let Err(e) = roll_over(&app, date.clone()) {
+                eprintln!("Day rollover failed: {}", e);
+            }
+        }
+        today = Some(date);
+    }
+}
+
+pub fn start(app: AppHandle) {
+    let spawned = thread::Builder::new()
+        .name("day-rollover".to_string())
+        .spawn(move || run(app));
+    if let Err(e) = spawned {
+        eprintln!("Failed to start day rollover: {}", e);
+    }
+}
@@ -0,0 +1,465 @@
+use crate::pool::{PooledConnection, ReadPool};
+use recallify_core::data_dir::{self, DataDirSource, DataLocation};
+use recallify_core::db::{export, open_read_connection};
+use recallify_core::error::{AppError, ResultExt};
+use recallify_core::{profiles, queries};
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+
+pub use recallify_core::db::{apply_key, copy_database, integrity_problems, is_encrypted, key_error};
+
+// Connection management. The database starts closed; `open_at_startup`
+// opens a plaintext database straight away, while an encrypted one stays
+// locked until the frontend calls `unlock_database` with the passphrase.
+
+const READ_POOL_SIZE: usize = 4;
+
+const LOCKED: &str = "Database is locked; unlock it with the passphrase first";
+
+fn locked() -> AppError {
+    AppError::locked(LOCKED)
+}
+
+// The open database: the writer connection, through which all writes go
+// one at a time, and the key it was opened with (needed to open further
+// connections such as backup targets).
+struct Database {
+    conn: Connection,
+    key: Option<String>,
+}
+
+static DB: Mutex<Option<Database>> = Mutex::new(None);
+
+// Why the database could not be opened, if it couldn't. Kept so the
+// frontend can offer recovery instead of the app failing to start; the key
+// is kept so recovery can still read an encrypted file.
+struct Problem {
+    message: String,
+    key: Option<String>,
+}
+
+static PROBLEM: Mutex<Option<Problem>> = Mutex::new(None);
+
+// Read-only connections for queries, replaced whenever the writer is
+// reopened so they always use the current key.
+static READ_POOL: RwLock<Option<Arc<ReadPool>>> = RwLock::new(None);
+
+pub fn db_path() -> PathBuf {
+    recallify_core::db::db_path(&crate::get_app_data_dir())
+}
+
+// Opens the writer and the read pool with `key` and publishes them. The
+// writer is opened like the CLI opens its connection (see
+// recallify_core::db::open), then checked for damage.
+fn open(db: &mut Option<Database>, key: Option<&str>) -> Result<(), AppError> {
+    let conn = recallify_core::db::open(&crate::get_app_data_dir(), key)?;
+
+    let problems = integrity_problems(&conn, false).context("Database is damaged")?;
+    if !problems.is_empty() {
+        return Err(AppError::database(problems.join("; ")).context("Database is damaged"));
+    }
+
+    // Statuses go stale while the app is closed; day_rollover keeps them
+    // current from here on
+    if let Err(e) = queries::update_overdue_revisions(&conn) {
+        eprintln!("Failed to update overdue revisions: {}", e);
+    }
+
+    let readers = open_readers(key)?;
+
+    *db = Some(Database {
+        conn,
+        key: key.map(str::to_string),
+    });
+    *READ_POOL.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(readers));
+    *PROBLEM.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    Ok(())
+}
+
+fn open_readers(key: Option<&str>) -> Result<ReadPool, AppError> {
+    let path = db_path();
+    let readers = (0..READ_POOL_SIZE)
+        .map(|_| open_read_connection(&path, key))
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to open read connection")?;
+    Ok(ReadPool::new(readers))
+}
+
+// Replaces the read pool with fresh connections, which pick up what
+// connections only read when opened, such as the time zone (see clock.rs).
+pub fn reopen_readers() -> Result<(), AppError> {
+    let db = DB.lock()?;
+    let key = db.as_ref().ok_or_else(locked)?.key.clone();
+    let readers = open_readers(key.as_deref())?;
+    *READ_POOL.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(readers));
+    Ok(())
+}
+
+fn record_problem(message: String, key: Option<&str>) {
+    eprintln!("{}", message);
+    *PROBLEM.lock().unwrap_or_else(|e| e.into_inner()) = Some(Problem {
+        message,
+        key: key.map(str::to_string),
+    });
+}
+
+// Closes the read pool, waiting for checked-out connections to be returned,
+// so nothing still has the file open once the writer is dropped too.
+fn close(db: &mut Option<Database>) {
+    close_readers();
+    db.take();
+}
+
+fn close_readers() {
+    let pool = READ_POOL.write().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some(pool) = pool {
+        pool.close();
+    }
+}
+
+// Deletes the `-wal` and `-shm` files next to the database. After a clean
+// close these are gone; anything left belongs to the old file and must not
+// be replayed into the one that replaces it.
+fn remove_sidecars(path: &Path) -> Result<(), AppError> {
+    for suffix in ["-wal", "-shm"] {
+        let sidecar = PathBuf::from(format!("{}{}", path.display(), suffix));
+        match fs::remove_file(&sidecar) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(AppError::from(e).context(format!("Failed to remove {}", sidecar.display()))),
+        }
+    }
+    Ok(())
+}
+
+// Never fails: a database that cannot be opened is recorded as a problem
+// and reported through `status` so the frontend can offer recovery.
+pub fn open_at_startup() {
+    let path = db_path();
+    println!("Database location: {:?}", path);
+    match is_encrypted(&path) {
+        Ok(true) => {
+            println!("Database is encrypted; waiting for unlock");
+            return;
+        }
+        Ok(false) => {}
+        Err(e) => return record_problem(format!("Failed to read database file: {}", e), None),
+    }
+    let mut db = DB.lock().unwrap_or_else(|e| e.into_inner());
+    if let Err(e) = open(&mut db, None) {
+        record_problem(e.to_string(), None);
+    }
+}
+
+pub struct Writer(MutexGuard<'static, Option<Database>>);
+
+impl Deref for Writer {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        &self.0.as_ref().expect("writer checked when locked").conn
+    }
+}
+
+pub fn writer() -> Result<Writer, AppError> {
+    let db = DB.lock()?;
+    if db.is_none() {
+        return Err(locked());
+    }
+    Ok(Writer(db))
+}
+
+// Key of the open database; `None` for plaintext.
+pub fn current_key() -> Result<Option<String>, AppError> {
+    let db = DB.lock()?;
+    db.as_ref().map(|db| db.key.clone()).ok_or_else(locked)
+}
+
+// Key for reading the database file directly, for recovery: the open
+// database's key, or the one it failed to open with.
+pub fn recovery_key() -> Result<Option<String>, AppError> {
+    if let Ok(key) = current_key() {
+        return Ok(key);
+    }
+    let problem = PROBLEM.lock()?;
+    problem.as_ref().map(|problem| problem.key.clone()).ok_or_else(locked)
+}
+
+pub fn reader() -> Result<PooledConnection, AppError> {
+    let pool = READ_POOL.read()?.clone().ok_or_else(locked)?;
+    pool.get().ok_or_else(locked)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseStatus {
+    pub encrypted: bool,
+    pub unlocked: bool,
+    // Set when the database could not be opened
+    pub problem: Option<String>,
+}
+
+pub fn status() -> Result<DatabaseStatus, AppError> {
+    let db = DB.lock()?;
+    let problem = PROBLEM.lock()?;
+    Ok(DatabaseStatus {
+        // An unreadable header also looks encrypted, so a problem takes precedence
+        encrypted: is_encrypted(&db_path()).unwrap_or(false),
+        unlocked: db.is_some(),
+        problem: problem.as_ref().map(|p| p.message.clone()),
+    })
+}
+
+// A wrong passphrase is returned as an error. Any other failure to open is
+// recorded as a problem, the same as at startup.
+pub fn unlock(passphrase: &str) -> Result<(), AppError> {
+    let mut db = DB.lock()?;
+    if db.is_some() {
+        return Ok(());
+    }
+    verify_passphrase(passphrase)?;
+    if let Err(e) = open(&mut db, Some(passphrase)) {
+        record_problem(e.to_string(), Some(passphrase));
+    }
+    Ok(())
+}
+
+fn check_passphrase(passphrase: &str) -> Result<(), AppError> {
+    if passphrase.is_empty() {
+        Err(AppError::validation("Passphrase cannot be empty"))
+    } else {
+        Ok(())
+    }
+}
+
+// Confirms `passphrase` against the file on a throwaway connection, so a
+// change or removal can't be made from an unlocked session alone.
+fn verify_passphrase(passphrase: &str) -> Result<(), AppError> {
+    let conn = Connection::open_with_flags(db_path(), OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    apply_key(&conn, Some(passphrase)).map_err(key_error)
+}
+
+fn replace_with_export(db: &mut Option<Database>, key: Option<&str>) -> Result<(), AppError> {
+    let path = db_path();
+    let temp = path.with_extension("db.rekey");
+    if temp.exists() {
+        fs::remove_file(&temp)?;
+    }
+
+    let (conn, current_key) = {
+        let db = db.as_ref().ok_or_else(locked)?;
+        (&db.conn, db.key.clone())
+    };
+    if let Err(e) = export(conn, &temp, key.unwrap_or("")) {
+        let _ = fs::remove_file(&temp);
+        return Err(AppError::from(e).context("Failed to export database"));
+    }
+
+    // With no reader left on the file the checkpoint can empty the WAL, and
+    // the sidecars are removed so none of it is replayed into the export.
+    close_readers();
+    let checkpoint = db
+        .as_ref()
+        .ok_or_else(locked)?
+        .conn
+        .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()));
+    close(db);
+    if let Err(e) = checkpoint {
+        let _ = fs::remove_file(&temp);
+        open(db, current_key.as_deref())?;
+        return Err(AppError::from(e).context("Failed to checkpoint database"));
+    }
+    remove_sidecars(&path)?;
+    fs::rename(&temp, &path).context("Failed to replace database")?;
+    open(db, key)
+}
+
+pub fn enable_encryption(passphrase: &str) -> Result<(), AppError> {
+    check_passphrase(passphrase)?;
+    let mut db = DB.lock()?;
+    if db.is_none() {
+        return Err(locked());
+    }
+    if is_encrypted(&db_path())? {
+        return Err(AppError::validation("Database is already encrypted"));
+    }
+    replace_with_export(&mut db, Some(passphrase))
+}
+
+pub fn remove_encryption(current_passphrase: &str) -> Result<(), AppError> {
+    let mut db = DB.lock()?;
+    if db.is_none() {
+        return Err(locked());
+    }
+    if !is_encrypted(&db_path())? {
+        return Err(AppError::validation("Database is not encrypted"));
+    }
+    verify_passphrase(current_passphrase)?;
+    replace_with_export(&mut db, None)
+}
+
+// Re-encrypts the file in place with PRAGMA rekey. The read pool holds the
+// old key, so it is closed first and everything is reopened afterwards.
+pub fn change_passphrase(current_passphrase: &str, new_passphrase: &str) -> Result<(), AppError> {
+    check_passphrase(new_passphrase)?;
+    let mut db = DB.lock()?;
+    if db.is_none() {
+        return Err(locked());
+    }
+    if !is_encrypted(&db_path())? {
+        return Err(AppError::validation("Database is not encrypted"));
+    }
+    verify_passphrase(current_passphrase)?;
+
+    close_readers();
+    let rekeyed = {
+        let conn = &db.as_ref().ok_or_else(locked)?.conn;
+        conn.pragma_update(None, "journal_mode", "DELETE")
+            .and_then(|()| conn.pragma_update(None, "rekey", new_passphrase))
+    };
+    close(&mut db);
+    match rekeyed {
+        Ok(()) => open(&mut db, Some(new_passphrase)),
+        Err(e) => {
+            open(&mut db, Some(current_passphrase))?;
+            Err(AppError::from(e).context("Failed to change passphrase"))
+        }
+    }
+}
+
+// Swaps in `replacement`, a complete database file encrypted with `key`,
+// and reopens everything on it. The previous file is kept as
+// `recallify.db.pre-restore` and put back if the new one fails to open.
+// Works whether or not the current database is unlocked.
+pub fn replace_file(replacement: &Path, key: Option<&str>) -> Result<(), AppError> {
+    let mut db = DB.lock()?;
+    let previous_key = db.as_ref().and_then(|db| db.key.clone());
+    let was_open = db.is_some();
+
+    let path = db_path();
+    let previous = path.with_extension("db.pre-restore");
+    close(&mut db);
+    remove_sidecars(&path)?;
+
+    if path.exists() {
+        fs::rename(&path, &previous).context("Failed to move current database aside")?;
+    }
+    fs::rename(replacement, &path).context("Failed to move restored database into place")?;
+
+    if let Err(e) = open(&mut db, key) {
+        let _ = fs::remove_file(&path);
+        if previous.exists() {
+            fs::rename(&previous, &path).context("Failed to put previous database back")?;
+        }
+        if was_open {
+            open(&mut db, previous_key.as_deref())?;
+        }
+        return Err(e);
+    }
+    Ok(())
+}
+
+fn open_found(db: &mut Option<Database>, key: Option<&str>) -> Result<(), AppError> {
+    if key.is_none() && is_encrypted(&db_path())? {
+        *PROBLEM.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        return Ok(());
+    }
+    open(db, key)
+}
+
+// Closes the database, runs `repoint` to change where db_path leads and
+// opens the database found there: with `key` if given, otherwise as at
+// startup, leaving an encrypted database locked for `unlock`. If opening
+// fails, `undo` reverts the change and the previous database is reopened.
+fn reopen_elsewhere(
+    db: &mut Option<Database>,
+    key: Option<&str>,
+    repoint: impl FnOnce() -> Result<(), AppError>,
+    undo: impl FnOnce() -> Result<(), AppError>,
+) -> Result<(), AppError> {
+    let previous_key = db.as_ref().and_then(|db| db.key.clone());
+    let was_open = db.is_some();
+
+    repoint()?;
+    close(db);
+    if let Err(e) = open_found(db, key) {
+        close(db);
+        undo()?;
+        if was_open {
+            open(db, previous_key.as_deref())?;
+        }
+        return Err(e);
+    }
+    Ok(())
+}
+
+fn restore_location(previous: &DataLocation) -> Result<(), AppError> {
+    let path = (previous.source == DataDirSource::Custom).then_some(previous.path.as_path());
+    data_dir::set_custom_dir(path)
+}
+
+// Copies all data, every profile included, to the empty folder `target`,
+// switches to it and then removes it from the old location. Nothing is
+// removed until the copy has opened cleanly. Returns what could not be
+// removed from the old location.
+pub fn move_data_dir(target: &Path) -> Result<Vec<String>, AppError> {
+    let mut db = DB.lock()?;
+    let location = data_dir::locate();
+    data_dir::check_movable(&location)?;
+    let current = db.as_ref().ok_or_else(locked)?;
+    let key = current.key.clone();
+
+    let active = crate::get_app_data_dir();
+    data_dir::copy_data(&current.conn, key.as_deref(), &active, &location.path, target)?;
+    let switched = reopen_elsewhere(
+        &mut db,
+        key.as_deref(),
+        || data_dir::set_custom_dir(Some(target)),
+        || restore_location(&location),
+    );
+    if let Err(e) = switched {
+        data_dir::remove_data(target);
+        return Err(e.context("Failed to open the moved database"));
+    }
+
+    let leftovers = data_dir::remove_data(&location.path);
+    for leftover in &leftovers {
+        eprintln!("Failed to remove old data: {}", leftover);
+    }
+    Ok(leftovers)
+}
+
+// Switches to data that is already in `target`, such as a synced folder set
+// up on another computer; a folder without a database starts empty.
+pub fn use_data_dir(target: &Path) -> Result<(), AppError> {
+    let mut db = DB.lock()?;
+    let location = data_dir::locate();
+    data_dir::check_movable(&location)?;
+    reopen_elsewhere(
+        &mut db,
+        None,
+        || data_dir::set_custom_dir(Some(target)),
+        || restore_location(&location),
+    )
+}
+
+// Makes `id` the active profile and opens its database, which is created
+// on first use.
+pub fn switch_profile(id: &str) -> Result<(), AppError> {
+    let mut db = DB.lock()?;
+    let root = data_dir::app_data_dir();
+    let previous = profiles::load(&root)?.active;
+    if previous == id {
+        return Ok(());
+    }
+    reopen_elsewhere(
+        &mut db,
+        None,
+        || profiles::set_active(&root, id),
+        || profiles::set_active(&root, &previous),
+    )
+}
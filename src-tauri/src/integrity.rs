@@ -0,0 +1,225 @@
+use crate::{backup, db};
+use recallify_core::error::{AppError, ResultExt};
+use recallify_core::{files, migrations, orphans, search};
+use rusqlite::types::Value;
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Integrity checking and recovery for damaged databases, typically after a
+// forced shutdown. Recovery either restores the newest usable backup or
+// salvages whatever rows can still be read into a freshly created file.
+// Both keep the damaged file as recallify.db.pre-restore.
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub ok: bool,
+    pub full: bool,
+    pub problems: Vec<String>,
+}
+
+pub fn check_database(full: bool) -> Result<IntegrityReport, AppError> {
+    let conn = db::reader()?;
+    let problems = db::integrity_problems(&conn, full)?;
+    Ok(IntegrityReport {
+        ok: problems.is_empty(),
+        full,
+        problems,
+    })
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableSalvage {
+    pub table: String,
+    pub recovered: usize,
+    // Set when reading stopped early; rows before the damage are kept
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SalvageReport {
+    pub tables: Vec<TableSalvage>,
+    pub orphans_removed: i64,
+}
+
+fn column_names(conn: &Connection, table: &str) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", quote(table)))?;
+    let names = stmt.query_map([], |row| row.get::<_, String>(1))?;
+    names.collect()
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn copy_rows(
+    src: &Connection,
+    insert: &mut rusqlite::Statement,
+    select_sql: &str,
+    column_count: usize,
+    recovered: &mut usize,
+) -> rusqlite::Result<()> {
+    let mut select = src.prepare(select_sql)?;
+    let mut rows = select.query([])?;
+    while let Some(row) = rows.next()? {
+        let values = (0..column_count)
+            .map(|i| row.get::<_, Value>(i))
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        insert.execute(rusqlite::params_from_iter(values))?;
+        *recovered += 1;
+    }
+    Ok(())
+}
+
+// Copies rows one at a time until the first unreadable one. Only columns
+// present in both databases are copied, so salvaging a file from an older
+// schema version works.
+fn salvage_table(src: &Connection, dest: &Connection, table: &str) -> rusqlite::Result<TableSalvage> {
+    let dest_columns = column_names(dest, table)?;
+    let columns: Vec<String> = column_names(src, table)?
+        .into_iter()
+        .filter(|c| dest_columns.contains(c))
+        .map(|c| quote(&c))
+        .collect();
+
+    let mut salvage = TableSalvage {
+        table: table.to_string(),
+        recovered: 0,
+        error: None,
+    };
+    if columns.is_empty() {
+        return Ok(salvage);
+    }
+
+    let column_list = columns.join(", ");
+    let placeholders = vec!["?"; columns.len()].join(", ");
+    let mut insert = dest.prepare(&format!(
+        "INSERT OR REPLACE INTO {} ({}) VALUES ({})",
+        quote(table),
+        column_list,
+        placeholders
+    ))?;
+    let select_sql = format!("SELECT {} FROM {}", column_list, quote(table));
+
+    if let Err(e) = copy_rows(src, &mut insert, &select_sql, columns.len(), &mut salvage.recovered) {
+        salvage.error = Some(e.to_string());
+    }
+    Ok(salvage)
+}
+
+fn salvage_into(dest_path: &Path, key: Option<&str>) -> Result<SalvageReport, AppError> {
+    let src = Connection::open_with_flags(db::db_path(), OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    db::apply_key(&src, key).context("Database cannot be read at all; restore a backup instead")?;
+
+    // The search index is rebuilt from the salvaged entries instead
+    let tables: Vec<String> = {
+        let mut stmt = src
+            .prepare(
+                "SELECT name FROM sqlite_master
+                 WHERE type = 'table' AND name NOT LIKE 'sqlite_%'
+                   AND name <> 'entry_search' AND name NOT LIKE 'entry_search_%'",
+            )
+            .context("Database schema cannot be read; restore a backup instead")?;
+        let names = stmt.query_map([], |row| row.get(0))?;
+        names.collect::<rusqlite::Result<_>>()?
+    };
+
+    let dest = recallify_core::db::open_connection(dest_path, key)?;
+    migrations::run(&dest)?;
+
+    // Rows are copied table by table in arbitrary order; references are
+    // checked once everything is in.
+    dest.pragma_update(None, "foreign_keys", "OFF")?;
+    let tx = dest.unchecked_transaction()?;
+    let mut report = SalvageReport {
+        tables: Vec::new(),
+        orphans_removed: 0,
+    };
+    for table in tables {
+        if column_names(&tx, &table)?.is_empty() {
+            // Not part of the current schema
+            continue;
+        }
+        match salvage_table(&src, &tx, &table) {
+            Ok(salvage) => report.tables.push(salvage),
+            Err(e) => report.tables.push(TableSalvage {
+                table,
+                recovered: 0,
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+    tx.commit()?;
+
+    report.orphans_removed = orphans::repair(&dest, true)?.total;
+    search::rebuild_index(&dest)?;
+    dest.pragma_update(None, "foreign_keys", "ON")?;
+    Ok(report)
+}
+
+// Builds a new database from the readable rows of the current one and
+// switches to it.
+pub fn salvage_database() -> Result<SalvageReport, AppError> {
+    let key = db::recovery_key()?;
+    let salvaged = db::db_path().with_extension("db.salvage");
+    if salvaged.exists() {
+        fs::remove_file(&salvaged)?;
+    }
+
+    let report = match salvage_into(&salvaged, key.as_deref()) {
+        Ok(report) => report,
+        Err(e) => {
+            let _ = fs::remove_file(&salvaged);
+            return Err(e);
+        }
+    };
+
+    if let Err(e) = db::replace_file(&salvaged, key.as_deref()) {
+        let _ = fs::remove_file(&salvaged);
+        return Err(e);
+    }
+    Ok(report)
+}
+
+fn backup_files() -> Vec<PathBuf> {
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = fs::read_dir(files::backups_dir(&crate::get_app_data_dir()))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension() == Some(OsStr::new("db")))
+                .filter_map(|path| Some((fs::metadata(&path).ok()?.modified().ok()?, path)))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    files.into_iter().map(|(_, path)| path).collect()
+}
+
+// Restores the newest backup in the backups directory that passes
+// validation. The run history lives in the database being replaced, so the
+// directory is scanned instead. Encrypted backups are tried with
+// `passphrase`, or with the current database's key when none is given.
+pub fn restore_latest_backup(passphrase: Option<&str>) -> Result<String, AppError> {
+    let fallback_key = db::recovery_key().ok().flatten();
+    let passphrase = passphrase.or(fallback_key.as_deref());
+
+    let mut failures = Vec::new();
+    for path in backup_files() {
+        match backup::restore_database(&path, passphrase) {
+            Ok(()) => return Ok(path.to_string_lossy().into_owned()),
+            Err(e) => failures.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    if failures.is_empty() {
+        Err(AppError::not_found("No backups found"))
+    } else {
+        Err(AppError::validation(failures.join("; ")).context("No usable backup found"))
+    }
+}
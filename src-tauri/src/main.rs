@@ -1,515 +1,259 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use once_cell::sync::Lazy;
-use rusqlite::{Connection, Result};
-use std::sync::Mutex;
-use std::path::PathBuf;
+mod auto_backup;
+mod backup;
+mod commands;
+mod day_rollover;
+mod db;
+mod integrity;
+mod pool;
+
+use recallify_core::error::AppError;
+use recallify_core::data_dir::{self, DataLocation};
+use recallify_core::profiles::{self, Profile, Profiles};
+use recallify_core::{bridge, files, orphans, spotify};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
 use std::fs;
 
+// The active profile's directory, holding its database, pdfs and backups.
+// Resolved on every call, so a moved data directory or a profile switch
+// takes effect at once.
 fn get_app_data_dir() -> PathBuf {
-    #[cfg(target_os = "windows")]
-    {
-        use directories::BaseDirs;
-        if let Some(base_dirs) = BaseDirs::new() {
-            let app_data = base_dirs.data_local_dir().join("Recallify");
-            fs::create_dir_all(&app_data).expect("Failed to create app data directory");
-            return app_data;
-        }
+    profiles::active_dir(&data_dir::app_data_dir())
+}
+
+// The raw SQL bridge lets the webview run arbitrary statements, so it is only
+// exposed in debug builds or when built with the `raw-sql` feature. The app
+// itself goes through the typed commands in commands.rs.
+fn raw_sql_enabled() -> Result<(), AppError> {
+    if cfg!(any(debug_assertions, feature = "raw-sql")) {
+        Ok(())
+    } else {
+        Err(AppError::validation("Raw SQL commands are disabled in release builds"))
     }
+}
+
+#[tauri::command]
+fn db_execute(sql: String, params: Vec<serde_json::Value>) -> Result<serde_json::Value, AppError> {
+    raw_sql_enabled()?;
+    let db = db::writer()?;
+    bridge::execute(&db, &sql, &params)
+}
+
+#[tauri::command]
+fn db_transaction(statements: Vec<bridge::BatchStatement>) -> Result<Vec<serde_json::Value>, AppError> {
+    raw_sql_enabled()?;
+    let db = db::writer()?;
+    bridge::transaction(&db, &statements)
+}
+
+#[tauri::command]
+fn db_select(
+    sql: String,
+    params: Vec<serde_json::Value>,
+    with_columns: Option<bool>,
+) -> Result<serde_json::Value, AppError> {
+    raw_sql_enabled()?;
+    let db = db::reader()?;
+    bridge::select(&db, &sql, &params, with_columns.unwrap_or(false))
+}
+
+#[tauri::command]
+fn read_pdf_file(file_path: String) -> Result<Vec<u8>, AppError> {
+    files::read_pdf(&get_app_data_dir(), Path::new(&file_path))
+}
+
+// Copies the PDF picked in the file dialog into the pdfs directory.
+#[tauri::command]
+fn import_pdf_file(source_path: String, file_name: String) -> Result<files::ImportedPdf, AppError> {
+    files::import_pdf(&get_app_data_dir(), Path::new(&source_path), &file_name)
+}
+
+#[tauri::command]
+fn delete_pdf_file(file_path: String) -> Result<(), AppError> {
+    files::delete_pdf(&get_app_data_dir(), Path::new(&file_path))
+}
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        use directories::ProjectDirs;
-        if let Some(proj_dirs) = ProjectDirs::from("com", "Recallify", "Recallify") {
-            let app_data = proj_dirs.data_dir().to_path_buf();
-            fs::create_dir_all(&app_data).expect("Failed to create app data directory");
-            return app_data;
+// Finds rows orphaned before foreign keys were enforced. With `remove` set,
+// deletes them along with the PDF files of orphaned attachments.
+#[tauri::command]
+fn repair_orphans(remove: bool) -> Result<orphans::OrphanReport, AppError> {
+    let report = {
+        let db = db::writer()?;
+        orphans::repair(&db, remove)?
+    };
+
+    if report.removed {
+        for file_path in &report.pdf_files {
+            match fs::remove_file(file_path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => eprintln!("Failed to delete orphaned PDF {}: {}", file_path, e),
+            }
         }
     }
 
-    // Fallback to current directory if all else fails
-    PathBuf::from(".")
-}
-
-static DB: Lazy<Mutex<Connection>> = Lazy::new(|| {
-    let db_path = get_app_data_dir().join("recallify.db");
-    println!("Database location: {:?}", db_path);
-    let conn = Connection::open(&db_path).expect("Failed to open database");
-    init_database(&conn).expect("Failed to initialize database");
-    Mutex::new(conn)
-});
-
-fn init_database(conn: &Connection) -> Result<()> {
-    conn.execute_batch(
-        "
-        CREATE TABLE IF NOT EXISTS subjects (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            name TEXT NOT NULL,
-            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-        );
-
-        CREATE TABLE IF NOT EXISTS entries (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            subject_id INTEGER NOT NULL,
-            study_date DATE NOT NULL,
-            study_notes TEXT NOT NULL,
-            morning_recall_notes TEXT,
-            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-            FOREIGN KEY (subject_id) REFERENCES subjects(id) ON DELETE CASCADE
-        );
-
-        CREATE TABLE IF NOT EXISTS revision_intervals (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            entry_id INTEGER NOT NULL,
-            interval_days INTEGER NOT NULL,
-            FOREIGN KEY (entry_id) REFERENCES entries(id) ON DELETE CASCADE
-        );
-
-        CREATE TABLE IF NOT EXISTS revisions (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            entry_id INTEGER NOT NULL,
-            interval_days INTEGER NOT NULL,
-            due_date DATE NOT NULL,
-            status TEXT NOT NULL CHECK(status IN ('pending', 'completed', 'overdue', 'rescheduled')),
-            completed_at TIMESTAMP,
-            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-            FOREIGN KEY (entry_id) REFERENCES entries(id) ON DELETE CASCADE
-        );
-
-        CREATE TABLE IF NOT EXISTS settings (
-            key TEXT PRIMARY KEY,
-            value TEXT NOT NULL,
-            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-        );
-
-        CREATE TABLE IF NOT EXISTS activity_log (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            entry_id INTEGER NOT NULL,
-            activity_type TEXT NOT NULL CHECK(activity_type IN ('study', 'revision_completed', 'entry_created')),
-            activity_date DATE NOT NULL,
-            details TEXT,
-            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-            FOREIGN KEY (entry_id) REFERENCES entries(id) ON DELETE CASCADE
-        );
-
-        CREATE TABLE IF NOT EXISTS pomodoro_sessions (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            session_type TEXT NOT NULL CHECK(session_type IN ('work', 'short_break', 'long_break')),
-            duration_minutes INTEGER NOT NULL,
-            subject_id INTEGER,
-            syllabus_item_id INTEGER,
-            completed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-            FOREIGN KEY (subject_id) REFERENCES subjects(id) ON DELETE SET NULL,
-            FOREIGN KEY (syllabus_item_id) REFERENCES syllabus_items(id) ON DELETE SET NULL
-        );
-
-        CREATE TABLE IF NOT EXISTS pomodoro_state (
-            id INTEGER PRIMARY KEY CHECK(id = 1),
-            session_type TEXT NOT NULL CHECK(session_type IN ('work', 'short_break', 'long_break')),
-            start_timestamp INTEGER,
-            duration_seconds INTEGER NOT NULL,
-            remaining_seconds INTEGER NOT NULL,
-            is_running INTEGER DEFAULT 0,
-            pomodoro_count INTEGER DEFAULT 0 CHECK(pomodoro_count >= 0 AND pomodoro_count <= 4),
-            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-        );
-
-        CREATE TABLE IF NOT EXISTS syllabus_items (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            subject_id INTEGER NOT NULL,
-            parent_id INTEGER,
-            title TEXT NOT NULL,
-            description TEXT,
-            estimated_hours REAL,
-            due_date DATE,
-            is_completed INTEGER DEFAULT 0,
-            sort_order INTEGER DEFAULT 0,
-            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-            FOREIGN KEY (subject_id) REFERENCES subjects(id) ON DELETE CASCADE,
-            FOREIGN KEY (parent_id) REFERENCES syllabus_items(id) ON DELETE CASCADE
-        );
-
-        CREATE TABLE IF NOT EXISTS entry_syllabus_links (
-            entry_id INTEGER NOT NULL,
-            syllabus_item_id INTEGER NOT NULL,
-            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-            PRIMARY KEY (entry_id, syllabus_item_id),
-            FOREIGN KEY (entry_id) REFERENCES entries(id) ON DELETE CASCADE,
-            FOREIGN KEY (syllabus_item_id) REFERENCES syllabus_items(id) ON DELETE CASCADE
-        );
-
-        CREATE TABLE IF NOT EXISTS pdf_attachments (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            entry_id INTEGER NOT NULL,
-            file_name TEXT NOT NULL,
-            file_path TEXT NOT NULL,
-            file_size INTEGER NOT NULL,
-            page_count INTEGER,
-            last_viewed_page INTEGER DEFAULT 1,
-            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-            FOREIGN KEY (entry_id) REFERENCES entries(id) ON DELETE CASCADE
-        );
-
-        CREATE TABLE IF NOT EXISTS export_history (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            export_type TEXT NOT NULL,
-            file_name TEXT NOT NULL,
-            file_path TEXT NOT NULL,
-            file_size INTEGER,
-            exported_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-        );
-
-        CREATE TABLE IF NOT EXISTS tags (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            name TEXT UNIQUE NOT NULL,
-            color TEXT DEFAULT '#3B82F6',
-            usage_count INTEGER DEFAULT 0,
-            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-        );
-
-        CREATE TABLE IF NOT EXISTS entry_tags (
-            entry_id INTEGER NOT NULL,
-            tag_id INTEGER NOT NULL,
-            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-            PRIMARY KEY (entry_id, tag_id),
-            FOREIGN KEY (entry_id) REFERENCES entries(id) ON DELETE CASCADE,
-            FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
-        );
-
-        CREATE INDEX IF NOT EXISTS idx_entries_subject_id ON entries(subject_id);
-        CREATE INDEX IF NOT EXISTS idx_entries_study_date ON entries(study_date);
-        CREATE INDEX IF NOT EXISTS idx_revisions_entry_id ON revisions(entry_id);
-        CREATE INDEX IF NOT EXISTS idx_revisions_due_date ON revisions(due_date);
-        CREATE INDEX IF NOT EXISTS idx_revisions_status ON revisions(status);
-        CREATE INDEX IF NOT EXISTS idx_activity_log_entry_id ON activity_log(entry_id);
-        CREATE INDEX IF NOT EXISTS idx_activity_log_activity_date ON activity_log(activity_date);
-        CREATE INDEX IF NOT EXISTS idx_pomodoro_sessions_completed_at ON pomodoro_sessions(completed_at);
-        CREATE INDEX IF NOT EXISTS idx_pomodoro_sessions_subject_id ON pomodoro_sessions(subject_id);
-        CREATE INDEX IF NOT EXISTS idx_syllabus_items_subject_id ON syllabus_items(subject_id);
-        CREATE INDEX IF NOT EXISTS idx_syllabus_items_parent_id ON syllabus_items(parent_id);
-        CREATE INDEX IF NOT EXISTS idx_pdf_attachments_entry_id ON pdf_attachments(entry_id);
-        CREATE INDEX IF NOT EXISTS idx_export_history_exported_at ON export_history(exported_at);
-        ",
-    )?;
-
-    // Insert default settings if not exists
-    conn.execute(
-        "INSERT OR IGNORE INTO settings (key, value) VALUES ('default_intervals', '3,7')",
-        [],
-    )?;
-    conn.execute(
-        "INSERT OR IGNORE INTO settings (key, value) VALUES ('notification_enabled', 'true')",
-        [],
-    )?;
-    conn.execute(
-        "INSERT OR IGNORE INTO settings (key, value) VALUES ('notification_time', '10:00')",
-        [],
-    )?;
-
-    // Pomodoro settings
-    conn.execute(
-        "INSERT OR IGNORE INTO settings (key, value) VALUES ('pomodoro_work_duration', '25')",
-        [],
-    )?;
-    conn.execute(
-        "INSERT OR IGNORE INTO settings (key, value) VALUES ('pomodoro_short_break', '5')",
-        [],
-    )?;
-    conn.execute(
-        "INSERT OR IGNORE INTO settings (key, value) VALUES ('pomodoro_long_break_default', '20')",
-        [],
-    )?;
-
-    // v3.0 Migration: Add topics column to entries table
-    // Use ALTER TABLE with error handling - ignore if column already exists
-    let _ = conn.execute(
-        "ALTER TABLE entries ADD COLUMN topics TEXT",
-        [],
-    );
-
-    // v3.2 Migration: Add daily_activity table for streak tracking
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS daily_activity (
-            activity_date DATE PRIMARY KEY,
-            study_minutes INTEGER DEFAULT 0,
-            pomodoro_count INTEGER DEFAULT 0,
-            entry_count INTEGER DEFAULT 0,
-            subjects_studied TEXT,
-            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-        )",
-        [],
-    )?;
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_daily_activity_date ON daily_activity(activity_date)",
-        [],
-    )?;
-
-    // v3.2 Migration: Add milestone_celebrations table
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS milestone_celebrations (
-            milestone_days INTEGER PRIMARY KEY,
-            achieved_at TIMESTAMP NOT NULL,
-            celebration_shown INTEGER DEFAULT 0,
-            shown_at TIMESTAMP
-        )",
-        [],
-    )?;
-
-    conn.execute(
-        "INSERT OR IGNORE INTO settings (key, value) VALUES ('pomodoro_sound_enabled', 'true')",
-        [],
-    )?;
-    conn.execute(
-        "INSERT OR IGNORE INTO settings (key, value) VALUES ('pomodoro_sound_choice', 'gentle_bell')",
-        [],
-    )?;
-
-    // Dark mode setting
-    conn.execute(
-        "INSERT OR IGNORE INTO settings (key, value) VALUES ('dark_mode_enabled', 'false')",
-        [],
-    )?;
-
-    // Initialize pomodoro state
-    conn.execute(
-        "INSERT OR IGNORE INTO pomodoro_state (id, session_type, duration_seconds, remaining_seconds, pomodoro_count) VALUES (1, 'work', 1500, 1500, 0)",
-        [],
-    )?;
-
-    // v3.3 Migration: Add spotify_auth table for music integration
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS spotify_auth (
-            id INTEGER PRIMARY KEY CHECK(id = 1),
-            access_token TEXT NOT NULL,
-            refresh_token TEXT NOT NULL,
-            expires_at TIMESTAMP NOT NULL,
-            device_id TEXT,
-            last_playlist_uri TEXT,
-            last_track_uri TEXT,
-            last_position_ms INTEGER DEFAULT 0,
-            is_premium INTEGER DEFAULT 1,
-            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-        )",
-        [],
-    )?;
-
-    Ok(())
-}
-
-#[tauri::command]
-fn db_execute(sql: String, params: Vec<serde_json::Value>) -> Result<serde_json::Value, String> {
-    let db = DB.lock().map_err(|e| e.to_string())?;
-
-    // Convert JSON values to rusqlite parameters
-    let sql_params: Vec<Box<dyn rusqlite::ToSql>> = params
-        .iter()
-        .map(|v| -> Box<dyn rusqlite::ToSql> {
-            match v {
-                serde_json::Value::String(s) => Box::new(s.clone()),
-                serde_json::Value::Number(n) => {
-                    if let Some(i) = n.as_i64() {
-                        Box::new(i)
-                    } else if let Some(f) = n.as_f64() {
-                        Box::new(f)
-                    } else {
-                        Box::new(n.to_string())
-                    }
-                }
-                serde_json::Value::Bool(b) => Box::new(*b),
-                serde_json::Value::Null => Box::new(None::<String>),
-                _ => Box::new(v.to_string()),
-            }
-        })
-        .collect();
-
-    let sql_params_refs: Vec<&dyn rusqlite::ToSql> = sql_params
-        .iter()
-        .map(|b| b.as_ref())
-        .collect();
-
-    db.execute(&sql, sql_params_refs.as_slice())
-        .map_err(|e| e.to_string())?;
-
-    let last_id = db.last_insert_rowid();
-
-    Ok(serde_json::json!({
-        "lastInsertId": last_id,
-        "rowsAffected": db.changes()
-    }))
-}
-
-#[tauri::command]
-fn db_select(sql: String, params: Vec<serde_json::Value>) -> Result<Vec<serde_json::Value>, String> {
-    let db = DB.lock().map_err(|e| e.to_string())?;
-
-    let mut stmt = db.prepare(&sql).map_err(|e| e.to_string())?;
-
-    let column_count = stmt.column_count();
-    let column_names: Vec<String> = (0..column_count)
-        .map(|i| stmt.column_name(i).unwrap_or("").to_string())
-        .collect();
-
-    // Convert JSON values to rusqlite parameters
-    let sql_params: Vec<Box<dyn rusqlite::ToSql>> = params
-        .iter()
-        .map(|v| -> Box<dyn rusqlite::ToSql> {
-            match v {
-                serde_json::Value::String(s) => Box::new(s.clone()),
-                serde_json::Value::Number(n) => {
-                    if let Some(i) = n.as_i64() {
-                        Box::new(i)
-                    } else if let Some(f) = n.as_f64() {
-                        Box::new(f)
-                    } else {
-                        Box::new(n.to_string())
-                    }
-                }
-                serde_json::Value::Bool(b) => Box::new(*b),
-                serde_json::Value::Null => Box::new(None::<String>),
-                _ => Box::new(v.to_string()),
-            }
-        })
-        .collect();
-
-    let sql_params_refs: Vec<&dyn rusqlite::ToSql> = sql_params
-        .iter()
-        .map(|b| b.as_ref())
-        .collect();
-
-    let rows = stmt
-        .query_map(sql_params_refs.as_slice(), |row| {
-            let mut obj = serde_json::Map::new();
-            for (i, name) in column_names.iter().enumerate() {
-                // Try different types
-                let value = if let Ok(s) = row.get::<_, Option<String>>(i) {
-                    match s {
-                        Some(v) => serde_json::Value::String(v),
-                        None => serde_json::Value::Null,
-                    }
-                } else if let Ok(n) = row.get::<_, Option<i64>>(i) {
-                    match n {
-                        Some(v) => serde_json::Value::Number(v.into()),
-                        None => serde_json::Value::Null,
-                    }
-                } else if let Ok(f) = row.get::<_, Option<f64>>(i) {
-                    match f {
-                        Some(v) => serde_json::Number::from_f64(v)
-                            .map(serde_json::Value::Number)
-                            .unwrap_or(serde_json::Value::Null),
-                        None => serde_json::Value::Null,
-                    }
-                } else {
-                    serde_json::Value::Null
-                };
-
-                obj.insert(name.clone(), value);
-            }
-            Ok(serde_json::Value::Object(obj))
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<std::result::Result<Vec<_>, _>>()
-        .map_err(|e: rusqlite::Error| e.to_string())?;
+    Ok(report)
+}
 
-    Ok(rows)
+// Database encryption. While an encrypted database is locked every other
+// database command fails until `unlock_database` succeeds.
+#[tauri::command]
+fn database_status() -> Result<db::DatabaseStatus, AppError> {
+    db::status()
 }
 
 #[tauri::command]
-fn read_pdf_file(file_path: String) -> Result<Vec<u8>, String> {
-    std::fs::read(&file_path).map_err(|e| format!("Failed to read PDF file: {}", e))
+fn unlock_database(passphrase: String) -> Result<(), AppError> {
+    db::unlock(&passphrase)
 }
 
 #[tauri::command]
-fn save_pdf_file(file_name: String, file_data: Vec<u8>) -> Result<String, String> {
-    // Create pdfs directory in app data folder (same location as database)
-    let pdfs_dir = get_app_data_dir().join("pdfs");
+fn enable_encryption(passphrase: String) -> Result<(), AppError> {
+    db::enable_encryption(&passphrase)
+}
 
-    // Create directory if it doesn't exist
-    fs::create_dir_all(&pdfs_dir)
-        .map_err(|e| format!("Failed to create pdfs directory: {}", e))?;
+#[tauri::command]
+fn change_passphrase(current_passphrase: String, new_passphrase: String) -> Result<(), AppError> {
+    db::change_passphrase(&current_passphrase, &new_passphrase)
+}
 
-    // Generate unique file path
-    let file_path = pdfs_dir.join(&file_name);
+#[tauri::command]
+fn remove_encryption(current_passphrase: String) -> Result<(), AppError> {
+    db::remove_encryption(&current_passphrase)
+}
 
-    // Write file
-    fs::write(&file_path, file_data)
-        .map_err(|e| format!("Failed to write PDF file: {}", e))?;
+// Writes a consistent copy of the live database to `dest_path`.
+#[tauri::command]
+fn backup_database(dest_path: String) -> Result<(), AppError> {
+    backup::backup_database(Path::new(&dest_path))
+}
 
-    // Return absolute path
-    file_path
-        .to_str()
-        .ok_or_else(|| "Failed to convert path to string".to_string())
-        .map(|s| s.to_string())
+// Replaces the database with the backup at `src_path` and reopens it; no
+// restart needed. `passphrase` is only needed for encrypted backups.
+#[tauri::command]
+fn restore_database(src_path: String, passphrase: Option<String>) -> Result<(), AppError> {
+    backup::restore_database(Path::new(&src_path), passphrase.as_deref())
 }
 
+// Scheduled backups, newest first, including failed runs
 #[tauri::command]
-fn delete_pdf_file(file_path: String) -> Result<(), String> {
-    std::fs::remove_file(&file_path)
-        .map_err(|e| format!("Failed to delete PDF file: {}", e))
+fn list_backup_runs() -> Result<Vec<auto_backup::BackupRun>, AppError> {
+    auto_backup::list_runs()
 }
 
-// Spotify Authentication Commands
 #[tauri::command]
-fn spotify_get_auth() -> Result<Option<serde_json::Value>, String> {
-    let db = DB.lock().map_err(|e| e.to_string())?;
-    
-    let result = db.query_row(
-        "SELECT access_token, refresh_token, expires_at, device_id, last_playlist_uri, last_track_uri, last_position_ms, is_premium FROM spotify_auth WHERE id = 1",
-        [],
-        |row| {
-            Ok(serde_json::json!({
-                "accessToken": row.get::<_, String>(0)?,
-                "refreshToken": row.get::<_, String>(1)?,
-                "expiresAt": row.get::<_, String>(2)?,
-                "deviceId": row.get::<_, Option<String>>(3)?,
-                "lastPlaylistUri": row.get::<_, Option<String>>(4)?,
-                "lastTrackUri": row.get::<_, Option<String>>(5)?,
-                "lastPositionMs": row.get::<_, i64>(6)?,
-                "isPremium": row.get::<_, i64>(7)?
-            }))
-        }
-    );
+fn run_backup_now() -> Result<auto_backup::BackupRun, AppError> {
+    auto_backup::run_backup("manual")
+}
+
+#[tauri::command]
+fn restore_backup_run(id: i64, passphrase: Option<String>) -> Result<(), AppError> {
+    auto_backup::restore_run(id, passphrase.as_deref())
+}
 
-    match result {
-        Ok(data) => Ok(Some(data)),
-        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-        Err(e) => Err(e.to_string())
+// Runs quick_check, or the slower integrity_check when `full` is set.
+#[tauri::command]
+fn check_database(full: Option<bool>) -> Result<integrity::IntegrityReport, AppError> {
+    integrity::check_database(full.unwrap_or(false))
+}
+
+// Recovery for a database that failed to open (see database_status).
+#[tauri::command]
+fn salvage_database() -> Result<integrity::SalvageReport, AppError> {
+    integrity::salvage_database()
+}
+
+// Returns the path of the backup that was restored.
+#[tauri::command]
+fn restore_latest_backup(passphrase: Option<String>) -> Result<String, AppError> {
+    integrity::restore_latest_backup(passphrase.as_deref())
+}
+
+// Where the data directory is: portable (next to the executable), custom
+// or the platform default.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DataLocationInfo {
+    #[serde(flatten)]
+    location: DataLocation,
+    default_path: PathBuf,
+}
+
+#[tauri::command]
+fn get_data_location() -> DataLocationInfo {
+    DataLocationInfo {
+        location: data_dir::locate(),
+        default_path: data_dir::platform_data_dir(),
     }
 }
 
+// Moves all data into the empty folder `target_path` and keeps using it
+// from there. Returns old files that could not be removed.
+#[tauri::command]
+fn move_data_directory(target_path: String) -> Result<Vec<String>, AppError> {
+    db::move_data_dir(Path::new(&target_path))
+}
+
+// Switches to data already in `target_path` without moving anything.
+#[tauri::command]
+fn use_data_directory(target_path: String) -> Result<(), AppError> {
+    db::use_data_dir(Path::new(&target_path))
+}
+
+// Study profiles, each with its own database, pdfs and backups
+#[tauri::command]
+fn list_profiles() -> Result<Profiles, AppError> {
+    profiles::load(&data_dir::app_data_dir())
+}
+
+#[tauri::command]
+fn create_profile(name: String) -> Result<Profile, AppError> {
+    profiles::create(&data_dir::app_data_dir(), &name)
+}
+
+#[tauri::command]
+fn rename_profile(id: String, name: String) -> Result<Profile, AppError> {
+    profiles::rename(&data_dir::app_data_dir(), &id, &name)
+}
+
+// Deletes an inactive profile along with all of its data.
+#[tauri::command]
+fn delete_profile(id: String) -> Result<(), AppError> {
+    profiles::delete(&data_dir::app_data_dir(), &id)
+}
+
+// Closes the current profile's database and opens the one of `id`. An
+// encrypted profile is left locked, as at startup.
+#[tauri::command]
+fn switch_profile(id: String) -> Result<(), AppError> {
+    db::switch_profile(&id)
+}
+
+// Spotify Authentication Commands
+#[tauri::command]
+fn spotify_get_auth() -> Result<Option<spotify::SpotifyAuth>, AppError> {
+    let db = db::reader()?;
+    Ok(spotify::get_auth(&db)?)
+}
+
 #[tauri::command]
 fn spotify_save_auth(
     access_token: String,
     refresh_token: String,
     expires_at: String,
     is_premium: i64
-) -> Result<(), String> {
-    let db = DB.lock().map_err(|e| e.to_string())?;
-    
-    db.execute(
-        "INSERT OR REPLACE INTO spotify_auth (id, access_token, refresh_token, expires_at, is_premium, updated_at) VALUES (1, ?, ?, ?, ?, CURRENT_TIMESTAMP)",
-        &[&access_token, &refresh_token, &expires_at, &is_premium.to_string()]
-    ).map_err(|e| e.to_string())?;
-    
-    Ok(())
+) -> Result<(), AppError> {
+    let db = db::writer()?;
+    Ok(spotify::save_auth(&db, &access_token, &refresh_token, &expires_at, is_premium)?)
 }
 
 #[tauri::command]
-fn spotify_update_device(device_id: String) -> Result<(), String> {
-    let db = DB.lock().map_err(|e| e.to_string())?;
-    
-    db.execute(
-        "UPDATE spotify_auth SET device_id = ?, updated_at = CURRENT_TIMESTAMP WHERE id = 1",
-        &[&device_id]
-    ).map_err(|e| e.to_string())?;
-    
-    Ok(())
+fn spotify_update_device(device_id: String) -> Result<(), AppError> {
+    let db = db::writer()?;
+    Ok(spotify::update_device(&db, &device_id)?)
 }
 
 #[tauri::command]
@@ -517,38 +261,122 @@ fn spotify_save_playback_state(
     playlist_uri: Option<String>,
     track_uri: Option<String>,
     position_ms: i64
-) -> Result<(), String> {
-    let db = DB.lock().map_err(|e| e.to_string())?;
-    
-    db.execute(
-        "UPDATE spotify_auth SET last_playlist_uri = ?, last_track_uri = ?, last_position_ms = ?, updated_at = CURRENT_TIMESTAMP WHERE id = 1",
-        rusqlite::params![playlist_uri, track_uri, position_ms]
-    ).map_err(|e| e.to_string())?;
-    
-    Ok(())
+) -> Result<(), AppError> {
+    let db = db::writer()?;
+    Ok(spotify::save_playback_state(&db, playlist_uri.as_deref(), track_uri.as_deref(), position_ms)?)
 }
 
 #[tauri::command]
-fn spotify_logout() -> Result<(), String> {
-    let db = DB.lock().map_err(|e| e.to_string())?;
-    
-    db.execute("DELETE FROM spotify_auth WHERE id = 1", [])
-        .map_err(|e| e.to_string())?;
-    
-    Ok(())
+fn spotify_logout() -> Result<(), AppError> {
+    let db = db::writer()?;
+    Ok(spotify::logout(&db)?)
 }
 
 fn main() {
-    // Initialize database at startup
-    drop(DB.lock());
+    // Initialize database at startup. Failures are reported to the
+    // frontend through database_status rather than aborting.
+    db::open_at_startup();
+    auto_backup::start();
 
     tauri::Builder::default()
+        .setup(|app| {
+            day_rollover::start(app.handle());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             db_execute,
             db_select,
+            db_transaction,
+            commands::get_subjects,
+            commands::get_subject_names,
+            commands::get_subject,
+            commands::create_subject,
+            commands::update_subject,
+            commands::delete_subject,
+            commands::get_entries_by_subject,
+            commands::get_entry,
+            commands::create_entry,
+            commands::update_entry,
+            commands::delete_entry,
+            commands::get_revisions_due_today,
+            commands::get_revisions_by_date,
+            commands::get_revisions_between,
+            commands::complete_revision,
+            commands::grade_revision,
+            commands::undo_review,
+            commands::get_review_log,
+            commands::get_retention_stats,
+            commands::forecast_reviews,
+            commands::plan_catch_up,
+            commands::optimize_fsrs_parameters,
+            commands::reset_fsrs_parameters,
+            commands::get_load_balancing,
+            commands::set_load_balancing,
+            commands::uncomplete_revision,
+            commands::reschedule_revision,
+            commands::update_overdue_revisions,
+            commands::search_entries,
+            commands::get_activity_log,
+            commands::get_settings,
+            commands::update_setting,
+            commands::get_timezone,
+            commands::set_timezone,
+            commands::get_syllabus_items,
+            commands::create_syllabus_item,
+            commands::update_syllabus_item,
+            commands::delete_syllabus_item,
+            commands::link_entry_to_syllabus,
+            commands::get_entry_syllabus_links,
+            commands::get_pdf_attachments,
+            commands::get_pdf_attachment,
+            commands::create_pdf_attachment,
+            commands::update_pdf_last_viewed_page,
+            commands::delete_pdf_attachment,
+            commands::record_pomodoro_session,
+            commands::get_pomodoro_sessions,
+            commands::get_pomodoro_state,
+            commands::update_pomodoro_state,
+            commands::get_pomodoro_stats,
+            commands::get_subject_pomodoro_totals,
+            commands::get_study_time_by_subject,
+            commands::get_all_subjects_study_time,
+            commands::get_today_pomodoro_summary,
+            commands::get_all_tags,
+            commands::get_or_create_tag,
+            commands::link_tags_to_entry,
+            commands::get_entry_tags,
+            commands::update_daily_activity,
+            commands::get_daily_activities,
+            commands::get_active_dates,
+            commands::get_streak_stats,
+            commands::record_milestone,
+            commands::mark_milestone_shown,
+            commands::get_milestone_achievements,
             read_pdf_file,
-            save_pdf_file,
+            import_pdf_file,
             delete_pdf_file,
+            repair_orphans,
+            database_status,
+            unlock_database,
+            enable_encryption,
+            change_passphrase,
+            remove_encryption,
+            backup_database,
+            restore_database,
+            list_backup_runs,
+            run_backup_now,
+            restore_backup_run,
+            check_database,
+            salvage_database,
+            restore_latest_backup,
+            get_data_location,
+            move_data_directory,
+            use_data_directory,
+            list_profiles,
+            create_profile,
+            rename_profile,
+            delete_profile,
+            switch_profile,
             spotify_get_auth,
             spotify_save_auth,
             spotify_update_device,
@@ -0,0 +1,86 @@
+use rusqlite::Connection;
+use std::ops::Deref;
+use std::sync::{Arc, Condvar, Mutex};
+
+// A fixed set of read-only connections. With the database in WAL mode these
+// read a consistent snapshot while the writer connection in `DB` keeps
+// committing, so long analytics queries no longer hold up timer saves.
+// Checked-out connections keep the pool alive, so it can be replaced while
+// queries are still running.
+pub struct ReadPool {
+    state: Mutex<State>,
+    returned: Condvar,
+    size: usize,
+}
+
+struct State {
+    idle: Vec<Connection>,
+    closed: bool,
+}
+
+impl ReadPool {
+    pub fn new(connections: Vec<Connection>) -> Self {
+        ReadPool {
+            size: connections.len(),
+            state: Mutex::new(State {
+                idle: connections,
+                closed: false,
+            }),
+            returned: Condvar::new(),
+        }
+    }
+
+    // Blocks until a connection is free; `None` once the pool is closed. A
+    // panic while a connection was checked out cannot leave the list itself
+    // inconsistent, so poisoning is ignored rather than shrinking the pool.
+    pub fn get(self: &Arc<Self>) -> Option<PooledConnection> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            if state.closed {
+                return None;
+            }
+            if let Some(conn) = state.idle.pop() {
+                return Some(PooledConnection {
+                    pool: Arc::clone(self),
+                    conn: Some(conn),
+                });
+            }
+            state = self.returned.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    // Stops handing out connections, waits for the checked-out ones to come
+    // back and closes them all, so none is left reading the file.
+    pub fn close(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.closed = true;
+        self.returned.notify_all();
+        while state.idle.len() < self.size {
+            state = self.returned.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+        state.idle.clear();
+    }
+}
+
+pub struct PooledConnection {
+    pool: Arc<ReadPool>,
+    conn: Option<Connection>,
+}
+
+impl Deref for PooledConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("pooled connection already returned")
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            let mut state = self.pool.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.idle.push(conn);
+            self.pool.returned.notify_all();
+        }
+    }
+}
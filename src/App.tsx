@@ -1,4 +1,4 @@
-import { useEffect } from "react";
+import { useEffect, useState } from "react";
 import { Routes, Route } from "react-router-dom";
 import { Box } from "@chakra-ui/react";
 import Sidebar from "./components/Sidebar";
@@ -11,24 +11,45 @@ import PomodoroHistoryPage from "./pages/PomodoroHistoryPage";
 import TagManagementPage from "./pages/TagManagementPage";
 import CalendarPage from "./pages/CalendarPage";
 import HistoryPage from "./pages/HistoryPage";
+import SearchPage from "./pages/SearchPage";
 import SettingsPage from "./pages/SettingsPage";
 import SpotifyCallbackPage from "./pages/SpotifyCallbackPage";
 import SpotifyButton from "./components/spotify/SpotifyButton";
-import { updateOverdueRevisions } from "./services/database";
+import UnlockScreen from "./components/UnlockScreen";
+import RecoveryScreen from "./components/RecoveryScreen";
+import { DatabaseStatus, getDatabaseStatus } from "./services/database";
 
 function App() {
-  useEffect(() => {
-    // Update overdue revisions on app launch
-    updateOverdueRevisions();
+  // null until the backend reports whether the database could be opened
+  const [status, setStatus] = useState<DatabaseStatus | null>(null);
+  const unlocked = status !== null && status.unlocked && !status.problem;
 
-    // Update overdue revisions every hour
-    const interval = setInterval(() => {
-      updateOverdueRevisions();
-    }, 60 * 60 * 1000);
+  async function refreshStatus() {
+    try {
+      setStatus(await getDatabaseStatus());
+    } catch (error) {
+      console.error("Failed to read database status:", error);
+      setStatus({ encrypted: false, unlocked: true, problem: null });
+    }
+  }
 
-    return () => clearInterval(interval);
+  useEffect(() => {
+    refreshStatus();
   }, []);
 
+  if (status === null) {
+    return null;
+  }
+
+  if (status.problem) {
+    return <RecoveryScreen problem={status.problem} onRecovered={refreshStatus} />;
+  }
+
+  if (!unlocked) {
+    // Unlocking can still fail to open a damaged database
+    return <UnlockScreen onUnlocked={refreshStatus} />;
+  }
+
   return (
     <Box display="flex" minH="100vh">
       <Sidebar />
@@ -43,6 +64,7 @@ function App() {
           <Route path="/tags" element={<TagManagementPage />} />
           <Route path="/calendar" element={<CalendarPage />} />
           <Route path="/history" element={<HistoryPage />} />
+          <Route path="/search" element={<SearchPage />} />
           <Route path="/settings" element={<SettingsPage />} />
           <Route path="/spotify-callback" element={<SpotifyCallbackPage />} />
         </Routes>
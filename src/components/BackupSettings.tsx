@@ -0,0 +1,270 @@
+import { useEffect, useState } from "react";
+import {
+  Badge,
+  Button,
+  Card,
+  CardBody,
+  Divider,
+  FormControl,
+  FormLabel,
+  Heading,
+  HStack,
+  Input,
+  Switch,
+  Text,
+  VStack,
+  useToast,
+} from "@chakra-ui/react";
+import { open, save } from "@tauri-apps/api/dialog";
+import { format, parseISO } from "date-fns";
+import {
+  BackupRun,
+  backupDatabase,
+  getSettings,
+  listBackupRuns,
+  restoreBackupRun,
+  restoreDatabase,
+  runBackupNow,
+  updateSetting,
+} from "../services/database";
+
+const DATABASE_FILTERS = [{ name: "Recallify database", extensions: ["db"] }];
+
+// Backup timestamps are stored in UTC ("YYYY-MM-DD HH:MM:SS")
+function formatRunTime(timestamp: string): string {
+  return format(parseISO(timestamp.replace(" ", "T") + "Z"), "MMM d, yyyy h:mm a");
+}
+
+function formatSize(bytes: number | null): string {
+  if (bytes === null) return "";
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+export default function BackupSettings() {
+  const [autoBackupEnabled, setAutoBackupEnabled] = useState(true);
+  const [intervalHours, setIntervalHours] = useState(24);
+  const [keepDaily, setKeepDaily] = useState(7);
+  const [keepWeekly, setKeepWeekly] = useState(4);
+  const [includePdfs, setIncludePdfs] = useState(false);
+  const [runs, setRuns] = useState<BackupRun[]>([]);
+  const [restorePassphrase, setRestorePassphrase] = useState("");
+  const [busy, setBusy] = useState(false);
+  const toast = useToast();
+
+  useEffect(() => {
+    loadSchedule();
+    loadRuns();
+  }, []);
+
+  async function loadSchedule() {
+    try {
+      const settings = await getSettings();
+      if (settings.auto_backup_enabled) {
+        setAutoBackupEnabled(settings.auto_backup_enabled === "true");
+      }
+      if (settings.auto_backup_interval_hours) {
+        setIntervalHours(parseInt(settings.auto_backup_interval_hours));
+      }
+      if (settings.auto_backup_keep_daily) {
+        setKeepDaily(parseInt(settings.auto_backup_keep_daily));
+      }
+      if (settings.auto_backup_keep_weekly) {
+        setKeepWeekly(parseInt(settings.auto_backup_keep_weekly));
+      }
+      if (settings.auto_backup_include_pdfs) {
+        setIncludePdfs(settings.auto_backup_include_pdfs === "true");
+      }
+    } catch (error) {
+      console.error("Failed to load backup schedule:", error);
+    }
+  }
+
+  async function loadRuns() {
+    try {
+      setRuns(await listBackupRuns());
+    } catch (error) {
+      console.error("Failed to load backups:", error);
+    }
+  }
+
+  async function handleSaveSchedule() {
+    try {
+      await updateSetting("auto_backup_enabled", autoBackupEnabled.toString());
+      await updateSetting("auto_backup_interval_hours", intervalHours.toString());
+      await updateSetting("auto_backup_keep_daily", keepDaily.toString());
+      await updateSetting("auto_backup_keep_weekly", keepWeekly.toString());
+      await updateSetting("auto_backup_include_pdfs", includePdfs.toString());
+      toast({ title: "Backup schedule saved", status: "success", duration: 3000 });
+    } catch (error) {
+      toast({ title: "Error saving schedule", description: String(error), status: "error", duration: 5000 });
+    }
+  }
+
+  async function handleBackupNow() {
+    try {
+      setBusy(true);
+      await runBackupNow();
+      toast({ title: "Backup complete", status: "success", duration: 3000 });
+    } catch (error) {
+      toast({ title: "Backup failed", description: String(error), status: "error", duration: 5000 });
+    } finally {
+      setBusy(false);
+      loadRuns();
+    }
+  }
+
+  async function handleExport() {
+    const destPath = await save({
+      defaultPath: `recallify-${format(new Date(), "yyyy-MM-dd")}.db`,
+      filters: DATABASE_FILTERS,
+    });
+    if (!destPath) return;
+
+    try {
+      setBusy(true);
+      await backupDatabase(destPath);
+      toast({ title: "Backup saved", description: destPath, status: "success", duration: 3000 });
+    } catch (error) {
+      toast({ title: "Backup failed", description: String(error), status: "error", duration: 5000 });
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function restore(action: () => Promise<void>) {
+    if (!window.confirm("Replace all current data with this backup?")) return;
+
+    try {
+      setBusy(true);
+      await action();
+      setRestorePassphrase("");
+      // Every page caches data from the old database
+      window.location.reload();
+    } catch (error) {
+      toast({ title: "Restore failed", description: String(error), status: "error", duration: 5000 });
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function handleRestoreFromFile() {
+    const selected = await open({ filters: DATABASE_FILTERS, multiple: false });
+    if (!selected || Array.isArray(selected)) return;
+    restore(() => restoreDatabase(selected, restorePassphrase));
+  }
+
+  return (
+    <Card>
+      <CardBody>
+        <Heading size="md" mb={4}>
+          Backup & Restore
+        </Heading>
+
+        <VStack spacing={4} align="stretch">
+          <FormControl display="flex" alignItems="center">
+            <FormLabel mb="0" flex="1">
+              Automatic Backups
+            </FormLabel>
+            <Switch
+              colorScheme="primary"
+              isChecked={autoBackupEnabled}
+              onChange={(e) => setAutoBackupEnabled(e.target.checked)}
+            />
+          </FormControl>
+
+          <HStack spacing={4} align="end">
+            <FormControl>
+              <FormLabel>Every (hours)</FormLabel>
+              <Input
+                type="number"
+                value={intervalHours}
+                onChange={(e) => setIntervalHours(parseInt(e.target.value) || 24)}
+                min={1}
+              />
+            </FormControl>
+            <FormControl>
+              <FormLabel>Daily copies kept</FormLabel>
+              <Input
+                type="number"
+                value={keepDaily}
+                onChange={(e) => setKeepDaily(parseInt(e.target.value) || 1)}
+                min={1}
+              />
+            </FormControl>
+            <FormControl>
+              <FormLabel>Weekly copies kept</FormLabel>
+              <Input
+                type="number"
+                value={keepWeekly}
+                onChange={(e) => setKeepWeekly(Math.max(0, parseInt(e.target.value) || 0))}
+                min={0}
+              />
+            </FormControl>
+          </HStack>
+
+          <FormControl display="flex" alignItems="center">
+            <FormLabel mb="0" flex="1">
+              Include PDF attachments
+            </FormLabel>
+            <Switch colorScheme="primary" isChecked={includePdfs} onChange={(e) => setIncludePdfs(e.target.checked)} />
+          </FormControl>
+
+          <HStack>
+            <Button onClick={handleSaveSchedule}>Save Schedule</Button>
+            <Button variant="outline" onClick={handleBackupNow} isLoading={busy}>
+              Back Up Now
+            </Button>
+            <Button variant="outline" onClick={handleExport} isLoading={busy}>
+              Export Copy...
+            </Button>
+          </HStack>
+
+          <Divider />
+
+          <FormControl>
+            <FormLabel>Backup Passphrase (encrypted backups only)</FormLabel>
+            <Input
+              type="password"
+              value={restorePassphrase}
+              onChange={(e) => setRestorePassphrase(e.target.value)}
+            />
+          </FormControl>
+
+          {runs.length === 0 ? (
+            <Text fontSize="sm" color="text.tertiary">
+              No backups yet.
+            </Text>
+          ) : (
+            <VStack spacing={2} align="stretch">
+              {runs.map((run) => (
+                <HStack key={run.id} justify="space-between">
+                  <HStack>
+                    <Badge colorScheme={run.status === "success" ? "green" : "red"}>{run.kind}</Badge>
+                    <Text fontSize="sm">{formatRunTime(run.started_at)}</Text>
+                    <Text fontSize="sm" color="text.tertiary">
+                      {run.status === "success" ? formatSize(run.size_bytes) : run.error}
+                    </Text>
+                  </HStack>
+                  {run.status === "success" && (
+                    <Button
+                      size="sm"
+                      variant="ghost"
+                      isDisabled={busy}
+                      onClick={() => restore(() => restoreBackupRun(run.id, restorePassphrase))}
+                    >
+                      Restore
+                    </Button>
+                  )}
+                </HStack>
+              ))}
+            </VStack>
+          )}
+
+          <Button variant="outline" colorScheme="red" onClick={handleRestoreFromFile} isLoading={busy} alignSelf="start">
+            Restore from File...
+          </Button>
+        </VStack>
+      </CardBody>
+    </Card>
+  );
+}
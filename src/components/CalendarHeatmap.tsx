@@ -1,5 +1,5 @@
 import { Box, Text, HStack, VStack, Tooltip, useColorModeValue } from "@chakra-ui/react";
-import { format, eachDayOfInterval, subMonths } from "date-fns";
+import { format, eachDayOfInterval, parseISO, subMonths } from "date-fns";
 
 interface DailyActivity {
   activity_date: string;
@@ -11,9 +11,11 @@ interface DailyActivity {
 interface CalendarHeatmapProps {
   activities: DailyActivity[];
   monthsToShow?: number;
+  // YYYY-MM-DD in the time zone of the settings; the webview's date without it
+  today?: string;
 }
 
-export default function CalendarHeatmap({ activities, monthsToShow = 6 }: CalendarHeatmapProps) {
+export default function CalendarHeatmap({ activities, monthsToShow = 6, today }: CalendarHeatmapProps) {
   const cellSize = 12;
   const cellGap = 3;
 
@@ -33,7 +35,7 @@ export default function CalendarHeatmap({ activities, monthsToShow = 6 }: Calend
   });
 
   // Get the date range
-  const endDate = new Date();
+  const endDate = today ? parseISO(today) : new Date();
   const startDate = subMonths(endDate, monthsToShow);
 
   // Get all days in range
@@ -0,0 +1,154 @@
+import { useEffect, useState } from "react";
+import {
+  Modal,
+  ModalOverlay,
+  ModalContent,
+  ModalHeader,
+  ModalBody,
+  ModalFooter,
+  ModalCloseButton,
+  Button,
+  FormControl,
+  FormLabel,
+  HStack,
+  Input,
+  Select,
+  Table,
+  Tbody,
+  Td,
+  Text,
+  Th,
+  Thead,
+  Tr,
+  VStack,
+  useToast,
+} from "@chakra-ui/react";
+import { format, parseISO } from "date-fns";
+import { planCatchUp } from "../services/database";
+import { CatchUpPlan, CatchUpStrategy } from "../types";
+
+interface CatchUpModalProps {
+  isOpen: boolean;
+  onClose: () => void;
+  onApplied: () => void;
+}
+
+export default function CatchUpModal({ isOpen, onClose, onApplied }: CatchUpModalProps) {
+  const [maxPerDay, setMaxPerDay] = useState(10);
+  const [strategy, setStrategy] = useState<CatchUpStrategy>("interval");
+  const [preview, setPreview] = useState<CatchUpPlan | null>(null);
+  const [applying, setApplying] = useState(false);
+  const toast = useToast();
+
+  // Dry run whenever the options change
+  useEffect(() => {
+    if (!isOpen || maxPerDay < 1) return;
+    planCatchUp(maxPerDay, strategy, true)
+      .then(setPreview)
+      .catch((error) => console.error("Error planning catch-up:", error));
+  }, [isOpen, maxPerDay, strategy]);
+
+  const afterExam = preview?.moves.filter((m) => m.examDate && m.toDate > m.examDate).length ?? 0;
+
+  async function handleApply() {
+    try {
+      setApplying(true);
+      const plan = await planCatchUp(maxPerDay, strategy, false);
+      const lastDay = plan.days[plan.days.length - 1];
+      toast({
+        title: "Backlog rescheduled",
+        description: lastDay
+          ? `${plan.moves.length} revisions spread until ${format(parseISO(lastDay.date), "MMM d")}`
+          : "Nothing was overdue",
+        status: "success",
+        duration: 4000,
+      });
+      onApplied();
+      onClose();
+    } catch (error) {
+      toast({ title: "Could not reschedule", description: String(error), status: "error", duration: 5000 });
+    } finally {
+      setApplying(false);
+    }
+  }
+
+  return (
+    <Modal isOpen={isOpen} onClose={onClose} size="lg" scrollBehavior="inside">
+      <ModalOverlay />
+      <ModalContent>
+        <ModalHeader>Catch Up on Overdue Revisions</ModalHeader>
+        <ModalCloseButton />
+        <ModalBody>
+          <VStack spacing={4} align="stretch">
+            <HStack spacing={4} align="flex-start">
+              <FormControl>
+                <FormLabel>Revisions per Day</FormLabel>
+                <Input
+                  type="number"
+                  value={maxPerDay}
+                  onChange={(e) => setMaxPerDay(parseInt(e.target.value) || 0)}
+                  min={1}
+                />
+              </FormControl>
+              <FormControl>
+                <FormLabel>Do First</FormLabel>
+                <Select value={strategy} onChange={(e) => setStrategy(e.target.value as CatchUpStrategy)}>
+                  <option value="interval">Shortest intervals</option>
+                  <option value="subject">One subject at a time</option>
+                  <option value="exam_date">Nearest exam date</option>
+                </Select>
+              </FormControl>
+            </HStack>
+
+            {preview && preview.moves.length === 0 && <Text color="text.secondary">Nothing is overdue.</Text>}
+
+            {preview && preview.moves.length > 0 && (
+              <>
+                <Text color="text.secondary">
+                  {preview.moves.length} overdue revision{preview.moves.length !== 1 ? "s" : ""} over{" "}
+                  {preview.days.length} day{preview.days.length !== 1 ? "s" : ""}, counting what is already due.
+                </Text>
+                {afterExam > 0 && (
+                  <Text color="status.overdue" fontSize="sm">
+                    {afterExam} would land after their exam date. Allow more per day or put exams first.
+                  </Text>
+                )}
+                <Table size="sm">
+                  <Thead>
+                    <Tr>
+                      <Th>Day</Th>
+                      <Th isNumeric>Moved</Th>
+                      <Th isNumeric>Total</Th>
+                    </Tr>
+                  </Thead>
+                  <Tbody>
+                    {preview.days.map((day) => (
+                      <Tr key={day.date}>
+                        <Td>{format(parseISO(day.date), "EEE, MMM d")}</Td>
+                        <Td isNumeric>{day.moved}</Td>
+                        <Td isNumeric>{day.total}</Td>
+                      </Tr>
+                    ))}
+                  </Tbody>
+                </Table>
+              </>
+            )}
+          </VStack>
+        </ModalBody>
+        <ModalFooter>
+          <Button variant="ghost" mr={3} onClick={onClose}>
+            Cancel
+          </Button>
+          <Button
+            colorScheme="teal"
+            onClick={handleApply}
+            isLoading={applying}
+            isDisabled={!preview || preview.moves.length === 0 || maxPerDay < 1}
+          >
+            Reschedule
+          </Button>
+        </ModalFooter>
+      </ModalContent>
+    </Modal>
+  );
+}
@@ -0,0 +1,117 @@
+import { useEffect, useState } from "react";
+import { Badge, Button, Card, CardBody, Code, Heading, HStack, Text, VStack, useToast } from "@chakra-ui/react";
+import { open } from "@tauri-apps/api/dialog";
+import { DataLocation, getDataLocation, moveDataDirectory, switchDataDirectory } from "../services/database";
+
+const SOURCE_LABELS: Record<DataLocation["source"], string> = {
+  portable: "Portable",
+  custom: "Custom",
+  default: "Default",
+};
+
+async function pickFolder(title: string): Promise<string | null> {
+  const selected = await open({ directory: true, multiple: false, title });
+  return typeof selected === "string" ? selected : null;
+}
+
+export default function DataLocationSettings() {
+  const [location, setLocation] = useState<DataLocation | null>(null);
+  const [busy, setBusy] = useState(false);
+  const toast = useToast();
+
+  useEffect(() => {
+    getDataLocation()
+      .then(setLocation)
+      .catch((error) => console.error("Failed to load data location:", error));
+  }, []);
+
+  async function handleMove() {
+    const target = await pickFolder("Choose an empty folder for your data");
+    if (!target) return;
+    if (!window.confirm(`Move the database, PDFs and backups to ${target}?`)) return;
+
+    try {
+      setBusy(true);
+      const leftovers = await moveDataDirectory(target);
+      if (leftovers.length > 0) {
+        window.alert(`Data moved, but some old files could not be removed:\n\n${leftovers.join("\n")}`);
+      }
+      // Every page caches data, including PDF paths
+      window.location.reload();
+    } catch (error) {
+      toast({ title: "Move failed", description: String(error), status: "error", duration: 5000 });
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function handleSwitch(target: string | null) {
+    if (!target) return;
+    if (!window.confirm(`Use the data in ${target}? Your current data stays where it is.`)) return;
+
+    try {
+      setBusy(true);
+      await switchDataDirectory(target);
+      window.location.reload();
+    } catch (error) {
+      toast({ title: "Switch failed", description: String(error), status: "error", duration: 5000 });
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  if (!location) return null;
+  const portable = location.source === "portable";
+
+  return (
+    <Card>
+      <CardBody>
+        <Heading size="md" mb={4}>
+          Data Location
+        </Heading>
+
+        <VStack spacing={4} align="stretch">
+          <HStack>
+            <Code flex={1} p={2} wordBreak="break-all">
+              {location.path}
+            </Code>
+            <Badge colorScheme={portable ? "purple" : location.source === "custom" ? "blue" : "gray"}>
+              {SOURCE_LABELS[location.source]}
+            </Badge>
+          </HStack>
+
+          {portable ? (
+            <Text fontSize="sm" color="text.tertiary">
+              Running in portable mode: data is kept next to the app because a recallify.portable file is
+              there. Remove that file to use another location.
+            </Text>
+          ) : (
+            <>
+              <Text fontSize="sm" color="text.tertiary">
+                Keep your data in another folder, such as a synced folder or a USB stick. Moving copies every
+                profile's database, PDFs and backups into an empty folder and removes them here once the copy opens.
+              </Text>
+              <HStack wrap="wrap">
+                <Button variant="outline" onClick={handleMove} isLoading={busy}>
+                  Move Data…
+                </Button>
+                <Button
+                  variant="outline"
+                  onClick={async () => handleSwitch(await pickFolder("Choose a folder with Recallify data"))}
+                  isLoading={busy}
+                >
+                  Use Existing Folder…
+                </Button>
+                {location.source === "custom" && (
+                  <Button variant="ghost" onClick={() => handleSwitch(location.defaultPath)} isLoading={busy}>
+                    Use Default Location
+                  </Button>
+                )}
+              </HStack>
+            </>
+          )}
+        </VStack>
+      </CardBody>
+    </Card>
+  );
+}
@@ -0,0 +1,77 @@
+import { useState } from "react";
+import { Button, Card, CardBody, Code, Heading, HStack, Text, VStack, useToast } from "@chakra-ui/react";
+import { IntegrityReport, checkDatabase, salvageDatabase } from "../services/database";
+
+export default function DatabaseHealthSettings() {
+  const [report, setReport] = useState<IntegrityReport | null>(null);
+  const [busy, setBusy] = useState(false);
+  const toast = useToast();
+
+  async function handleCheck(full: boolean) {
+    try {
+      setBusy(true);
+      setReport(await checkDatabase(full));
+    } catch (error) {
+      toast({ title: "Check failed", description: String(error), status: "error", duration: 5000 });
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function handleSalvage() {
+    if (!window.confirm("Rebuild the database from every row that can still be read?")) return;
+
+    try {
+      setBusy(true);
+      await salvageDatabase();
+      // Every page caches data from the old database
+      window.location.reload();
+    } catch (error) {
+      toast({ title: "Salvage failed", description: String(error), status: "error", duration: 5000 });
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  return (
+    <Card>
+      <CardBody>
+        <Heading size="md" mb={4}>
+          Database Health
+        </Heading>
+
+        <VStack spacing={4} align="stretch">
+          <Text fontSize="sm" color="text.tertiary">
+            A quick check runs every time Recallify starts. The full check also verifies indexes and can take
+            a while on large databases.
+          </Text>
+
+          <HStack>
+            <Button variant="outline" onClick={() => handleCheck(false)} isLoading={busy}>
+              Quick Check
+            </Button>
+            <Button variant="outline" onClick={() => handleCheck(true)} isLoading={busy}>
+              Full Check
+            </Button>
+          </HStack>
+
+          {report &&
+            (report.ok ? (
+              <Text fontSize="sm" color="green.500">
+                No problems found.
+              </Text>
+            ) : (
+              <VStack spacing={2} align="stretch">
+                <Code whiteSpace="pre-wrap" maxH="160px" overflowY="auto" p={2}>
+                  {report.problems.join("\n")}
+                </Code>
+                <Button colorScheme="red" variant="outline" onClick={handleSalvage} isLoading={busy} alignSelf="start">
+                  Salvage Readable Data
+                </Button>
+              </VStack>
+            ))}
+        </VStack>
+      </CardBody>
+    </Card>
+  );
+}
@@ -0,0 +1,151 @@
+import { useEffect, useState } from "react";
+import {
+  Button,
+  Card,
+  CardBody,
+  FormControl,
+  FormLabel,
+  Heading,
+  HStack,
+  Input,
+  Text,
+  VStack,
+  useToast,
+} from "@chakra-ui/react";
+import {
+  changePassphrase,
+  enableEncryption,
+  getDatabaseStatus,
+  removeEncryption,
+} from "../services/database";
+
+export default function EncryptionSettings() {
+  const [encrypted, setEncrypted] = useState(false);
+  const [currentPassphrase, setCurrentPassphrase] = useState("");
+  const [newPassphrase, setNewPassphrase] = useState("");
+  const [confirmPassphrase, setConfirmPassphrase] = useState("");
+  const [loading, setLoading] = useState(false);
+  const toast = useToast();
+
+  useEffect(() => {
+    loadStatus();
+  }, []);
+
+  async function loadStatus() {
+    try {
+      const status = await getDatabaseStatus();
+      setEncrypted(status.encrypted);
+    } catch (error) {
+      console.error("Failed to read database status:", error);
+    }
+  }
+
+  function resetFields() {
+    setCurrentPassphrase("");
+    setNewPassphrase("");
+    setConfirmPassphrase("");
+  }
+
+  function checkNewPassphrase(): boolean {
+    if (!newPassphrase) {
+      toast({ title: "Enter a new passphrase", status: "warning", duration: 3000 });
+      return false;
+    }
+    if (newPassphrase !== confirmPassphrase) {
+      toast({ title: "Passphrases do not match", status: "warning", duration: 3000 });
+      return false;
+    }
+    return true;
+  }
+
+  async function run(action: () => Promise<void>, successTitle: string) {
+    try {
+      setLoading(true);
+      await action();
+      toast({ title: successTitle, status: "success", duration: 3000 });
+      resetFields();
+      await loadStatus();
+    } catch (error) {
+      toast({
+        title: "Encryption change failed",
+        description: String(error),
+        status: "error",
+        duration: 5000,
+      });
+    } finally {
+      setLoading(false);
+    }
+  }
+
+  function handleEnable() {
+    if (!checkNewPassphrase()) return;
+    run(() => enableEncryption(newPassphrase), "Database encrypted");
+  }
+
+  function handleChange() {
+    if (!checkNewPassphrase()) return;
+    run(() => changePassphrase(currentPassphrase, newPassphrase), "Passphrase changed");
+  }
+
+  function handleRemove() {
+    run(() => removeEncryption(currentPassphrase), "Encryption removed");
+  }
+
+  return (
+    <Card>
+      <CardBody>
+        <Heading size="md" mb={4}>
+          Encryption
+        </Heading>
+
+        <VStack spacing={4} align="stretch">
+          <Text fontSize="sm" color="text.tertiary">
+            {encrypted
+              ? "Your database is encrypted. The passphrase is required every time Recallify starts."
+              : "Encrypt your notes and Spotify login on disk. There is no way to recover a forgotten passphrase."}
+          </Text>
+
+          {encrypted && (
+            <FormControl>
+              <FormLabel>Current Passphrase</FormLabel>
+              <Input
+                type="password"
+                value={currentPassphrase}
+                onChange={(e) => setCurrentPassphrase(e.target.value)}
+              />
+            </FormControl>
+          )}
+
+          <FormControl>
+            <FormLabel>New Passphrase</FormLabel>
+            <Input type="password" value={newPassphrase} onChange={(e) => setNewPassphrase(e.target.value)} />
+          </FormControl>
+
+          <FormControl>
+            <FormLabel>Confirm New Passphrase</FormLabel>
+            <Input
+              type="password"
+              value={confirmPassphrase}
+              onChange={(e) => setConfirmPassphrase(e.target.value)}
+            />
+          </FormControl>
+
+          {encrypted ? (
+            <HStack>
+              <Button onClick={handleChange} isLoading={loading}>
+                Change Passphrase
+              </Button>
+              <Button variant="outline" colorScheme="red" onClick={handleRemove} isLoading={loading}>
+                Remove Encryption
+              </Button>
+            </HStack>
+          ) : (
+            <Button onClick={handleEnable} isLoading={loading} alignSelf="start">
+              Enable Encryption
+            </Button>
+          )}
+        </VStack>
+      </CardBody>
+    </Card>
+  );
+}
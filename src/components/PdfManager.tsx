@@ -18,7 +18,7 @@ import {
   ModalCloseButton,
   useDisclosure,
 } from "@chakra-ui/react";
-import { invoke } from "@tauri-apps/api/tauri";
+import { invokeCommand, isCommandError } from "../services/errors";
 import { open } from "@tauri-apps/api/dialog";
 import {
   getPdfAttachments,
@@ -78,26 +78,21 @@ export default function PdfManager({ entryId, onUpdate }: PdfManagerProps) {
         return;
       }
 
-      // Read file
-      const fileData: number[] = await invoke("read_pdf_file", {
-        filePath: selected,
-      });
-
       // Get file name from path
       const fileName = selected.split(/[/\\]/).pop() || "document.pdf";
 
-      // Save file
-      const savedPath: string = await invoke("save_pdf_file", {
+      // Copy the file into the app's pdfs directory
+      const imported: { filePath: string; fileSize: number } = await invokeCommand("import_pdf_file", {
+        sourcePath: selected,
         fileName: `${Date.now()}_${fileName}`,
-        fileData,
       });
 
       // Create database record
       await createPdfAttachment({
         entry_id: entryId,
         file_name: fileName,
-        file_path: savedPath,
-        file_size: fileData.length,
+        file_path: imported.filePath,
+        file_size: imported.fileSize,
         page_count: null, // We'll calculate this later if needed
       });
 
@@ -123,8 +118,12 @@ export default function PdfManager({ entryId, onUpdate }: PdfManagerProps) {
 
   async function handleDelete(pdfId: number, filePath: string) {
     try {
-      // Delete file
-      await invoke("delete_pdf_file", { filePath });
+      // Delete file; a file that is already gone shouldn't keep the record around
+      try {
+        await invokeCommand("delete_pdf_file", { filePath });
+      } catch (error) {
+        if (!isCommandError(error, "not_found")) throw error;
+      }
 
       // Delete database record
       await deletePdfAttachment(pdfId);
@@ -0,0 +1,174 @@
+import { useEffect, useState } from "react";
+import {
+  Badge,
+  Button,
+  Card,
+  CardBody,
+  Heading,
+  HStack,
+  IconButton,
+  Input,
+  Text,
+  VStack,
+  useToast,
+} from "@chakra-ui/react";
+import { FaCheck, FaPen, FaTimes, FaTrash } from "react-icons/fa";
+import {
+  Profiles,
+  createProfile,
+  deleteProfile,
+  listProfiles,
+  renameProfile,
+  switchProfile,
+} from "../services/database";
+
+export default function ProfileSettings() {
+  const [profiles, setProfiles] = useState<Profiles | null>(null);
+  const [newName, setNewName] = useState("");
+  const [editing, setEditing] = useState<{ id: string; name: string } | null>(null);
+  const [busy, setBusy] = useState(false);
+  const toast = useToast();
+
+  async function load() {
+    try {
+      setProfiles(await listProfiles());
+    } catch (error) {
+      console.error("Failed to load profiles:", error);
+    }
+  }
+
+  useEffect(() => {
+    load();
+  }, []);
+
+  async function run(title: string, action: () => Promise<unknown>) {
+    try {
+      setBusy(true);
+      await action();
+      await load();
+    } catch (error) {
+      toast({ title, description: String(error), status: "error", duration: 5000 });
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function handleCreate() {
+    if (!newName.trim()) return;
+    await run("Could not create profile", async () => {
+      await createProfile(newName);
+      setNewName("");
+    });
+  }
+
+  async function handleRename() {
+    if (!editing) return;
+    await run("Could not rename profile", async () => {
+      await renameProfile(editing.id, editing.name);
+      setEditing(null);
+    });
+  }
+
+  async function handleDelete(id: string, name: string) {
+    if (!window.confirm(`Delete the profile "${name}" with all of its entries, PDFs and backups?`)) return;
+    await run("Could not delete profile", () => deleteProfile(id));
+  }
+
+  async function handleSwitch(id: string) {
+    try {
+      setBusy(true);
+      await switchProfile(id);
+      // Every page caches data from the previous profile
+      window.location.reload();
+    } catch (error) {
+      toast({ title: "Could not switch profile", description: String(error), status: "error", duration: 5000 });
+      setBusy(false);
+    }
+  }
+
+  if (!profiles) return null;
+
+  return (
+    <Card>
+      <CardBody>
+        <Heading size="md" mb={4}>
+          Profiles
+        </Heading>
+
+        <VStack spacing={4} align="stretch">
+          <Text fontSize="sm" color="text.tertiary">
+            Each profile has its own subjects, revisions, PDFs, backups and settings — useful for keeping
+            separate studies apart or sharing a computer.
+          </Text>
+
+          <VStack spacing={2} align="stretch">
+            {profiles.profiles.map((profile) => {
+              const active = profile.id === profiles.active;
+              return (
+                <HStack key={profile.id}>
+                  {editing?.id === profile.id ? (
+                    <>
+                      <Input
+                        size="sm"
+                        autoFocus
+                        value={editing.name}
+                        onChange={(e) => setEditing({ id: profile.id, name: e.target.value })}
+                        onKeyPress={(e) => {
+                          if (e.key === "Enter") handleRename();
+                        }}
+                      />
+                      <IconButton aria-label="Save name" icon={<FaCheck />} size="sm" onClick={handleRename} />
+                      <IconButton aria-label="Cancel" icon={<FaTimes />} size="sm" onClick={() => setEditing(null)} />
+                    </>
+                  ) : (
+                    <>
+                      <Text flex={1}>{profile.name}</Text>
+                      {active ? (
+                        <Badge colorScheme="green">Active</Badge>
+                      ) : (
+                        <Button size="sm" variant="outline" onClick={() => handleSwitch(profile.id)} isLoading={busy}>
+                          Switch
+                        </Button>
+                      )}
+                      <IconButton
+                        aria-label="Rename profile"
+                        icon={<FaPen />}
+                        size="sm"
+                        variant="ghost"
+                        onClick={() => setEditing({ id: profile.id, name: profile.name })}
+                      />
+                      {!active && profile.id !== "default" && (
+                        <IconButton
+                          aria-label="Delete profile"
+                          icon={<FaTrash />}
+                          size="sm"
+                          variant="ghost"
+                          colorScheme="red"
+                          onClick={() => handleDelete(profile.id, profile.name)}
+                        />
+                      )}
+                    </>
+                  )}
+                </HStack>
+              );
+            })}
+          </VStack>
+
+          <HStack>
+            <Input
+              placeholder="New profile name"
+              value={newName}
+              onChange={(e) => setNewName(e.target.value)}
+              onKeyPress={(e) => {
+                if (e.key === "Enter") handleCreate();
+              }}
+            />
+            <Button onClick={handleCreate} isLoading={busy} isDisabled={!newName.trim()}>
+              Add Profile
+            </Button>
+          </HStack>
+        </VStack>
+      </CardBody>
+    </Card>
+  );
+}
@@ -0,0 +1,79 @@
+import { useState } from "react";
+import {
+  Box,
+  Button,
+  Card,
+  CardBody,
+  Code,
+  FormControl,
+  FormLabel,
+  Heading,
+  HStack,
+  Input,
+  Text,
+  VStack,
+} from "@chakra-ui/react";
+import { restoreLatestBackup, salvageDatabase } from "../services/database";
+
+interface RecoveryScreenProps {
+  problem: string;
+  onRecovered: () => void;
+}
+
+export default function RecoveryScreen({ problem, onRecovered }: RecoveryScreenProps) {
+  const [passphrase, setPassphrase] = useState("");
+  const [error, setError] = useState<string | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  async function recover(action: () => Promise<unknown>) {
+    try {
+      setLoading(true);
+      setError(null);
+      await action();
+      onRecovered();
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setLoading(false);
+    }
+  }
+
+  return (
+    <Box minH="100vh" display="flex" alignItems="center" justifyContent="center" p={8}>
+      <Card w="100%" maxW="640px">
+        <CardBody>
+          <VStack spacing={4} align="stretch">
+            <Heading size="md">Recallify couldn't open your data</Heading>
+            <Code whiteSpace="pre-wrap" maxH="160px" overflowY="auto" p={2}>
+              {problem}
+            </Code>
+            <Text fontSize="sm" color="text.tertiary">
+              Restore the most recent automatic backup, or rescue every row that can still be read into
+              a new database. Either way the damaged file is kept next to it as recallify.db.pre-restore.
+            </Text>
+
+            <FormControl>
+              <FormLabel>Passphrase (encrypted databases only)</FormLabel>
+              <Input type="password" value={passphrase} onChange={(e) => setPassphrase(e.target.value)} />
+            </FormControl>
+
+            {error && (
+              <Text fontSize="sm" color="red.500">
+                {error}
+              </Text>
+            )}
+
+            <HStack>
+              <Button onClick={() => recover(() => restoreLatestBackup(passphrase))} isLoading={loading}>
+                Restore Latest Backup
+              </Button>
+              <Button variant="outline" onClick={() => recover(salvageDatabase)} isLoading={loading}>
+                Salvage Readable Data
+              </Button>
+            </HStack>
+          </VStack>
+        </CardBody>
+      </Card>
+    </Box>
+  );
+}
@@ -0,0 +1,151 @@
+import { useEffect, useState } from "react";
+import {
+  Box,
+  Card,
+  CardBody,
+  HStack,
+  Select,
+  SimpleGrid,
+  Text,
+  Tooltip,
+  VStack,
+  useColorModeValue,
+} from "@chakra-ui/react";
+import { format, parseISO } from "date-fns";
+import { forecastReviews, getSubjectNames } from "../services/database";
+import { ReviewForecast } from "../types";
+
+const CHART_HEIGHT = 160;
+
+function formatMinutes(minutes: number) {
+  const rounded = Math.round(minutes);
+  const hours = Math.floor(rounded / 60);
+  return hours > 0 ? `${hours}h ${rounded % 60}m` : `${rounded}m`;
+}
+
+export default function ReviewForecastChart() {
+  const [days, setDays] = useState(30);
+  const [subjectId, setSubjectId] = useState<number | undefined>();
+  const [subjects, setSubjects] = useState<{ id: number; name: string }[]>([]);
+  const [forecast, setForecast] = useState<ReviewForecast | null>(null);
+
+  const cardBg = useColorModeValue("white", "#1a1a1a");
+  const textColor = useColorModeValue("text.primary", "#ffffff");
+  const secondaryTextColor = useColorModeValue("text.secondary", "#b0b0b0");
+  const scheduledColor = useColorModeValue("#1EA896", "#1EA896");
+  const projectedColor = useColorModeValue("#9ee0d6", "#0e5a50");
+  const overdueColor = useColorModeValue("#e53e3e", "#fc8181");
+
+  useEffect(() => {
+    getSubjectNames()
+      .then(setSubjects)
+      .catch((error) => console.error("Error loading subjects:", error));
+  }, []);
+
+  useEffect(() => {
+    forecastReviews(days, subjectId)
+      .then(setForecast)
+      .catch((error) => console.error("Error loading review forecast:", error));
+  }, [days, subjectId]);
+
+  const maxReviews = Math.max(...(forecast?.days.map((day) => day.reviews) ?? []), 1);
+  const busiest = forecast?.days.reduce((a, b) => (b.reviews > a.reviews ? b : a), forecast.days[0]);
+
+  return (
+    <VStack spacing={6} align="stretch">
+      <HStack spacing={4}>
+        <Select value={days} onChange={(e) => setDays(Number(e.target.value))} maxW="200px">
+          <option value={30}>Next 30 days</option>
+          <option value={90}>Next 90 days</option>
+        </Select>
+        <Select
+          value={subjectId ?? ""}
+          onChange={(e) => setSubjectId(e.target.value ? Number(e.target.value) : undefined)}
+          maxW="250px"
+        >
+          <option value="">All subjects</option>
+          {subjects.map((subject) => (
+            <option key={subject.id} value={subject.id}>
+              {subject.name}
+            </option>
+          ))}
+        </Select>
+      </HStack>
+
+      {forecast && (
+        <>
+          <SimpleGrid columns={{ base: 1, md: 3 }} spacing={6}>
+            <Card bg={cardBg}>
+              <CardBody>
+                <Text fontSize="sm" color={secondaryTextColor}>Reviews</Text>
+                <Text fontSize="2xl" fontWeight="bold" color={textColor}>{forecast.totalReviews}</Text>
+              </CardBody>
+            </Card>
+            <Card bg={cardBg}>
+              <CardBody>
+                <Text fontSize="sm" color={secondaryTextColor}>Estimated Time</Text>
+                <Text fontSize="2xl" fontWeight="bold" color={textColor}>{formatMinutes(forecast.totalMinutes)}</Text>
+              </CardBody>
+            </Card>
+            <Card bg={cardBg}>
+              <CardBody>
+                <Text fontSize="sm" color={secondaryTextColor}>Busiest Day</Text>
+                <Text fontSize="2xl" fontWeight="bold" color={textColor}>
+                  {busiest && busiest.reviews > 0
+                    ? `${format(parseISO(busiest.date), "MMM d")} (${busiest.reviews})`
+                    : "—"}
+                </Text>
+              </CardBody>
+            </Card>
+          </SimpleGrid>
+
+          <Card bg={cardBg}>
+            <CardBody>
+              <HStack align="flex-end" spacing="2px" h={`${CHART_HEIGHT}px`}>
+                {forecast.days.map((day) => (
+                  <Tooltip
+                    key={day.date}
+                    label={`${format(parseISO(day.date), "EEE, MMM d")}: ${day.reviews} review${day.reviews !== 1 ? "s" : ""}${
+                      day.overdue > 0 ? ` (${day.overdue} overdue)` : ""
+                    }, ~${formatMinutes(day.minutes)}`}
+                  >
+                    <VStack flex={1} spacing={0} justify="flex-end" h="100%" minW="2px">
+                      <Box w="100%" h={`${(day.projected / maxReviews) * CHART_HEIGHT}px`} bg={projectedColor} />
+                      <Box
+                        w="100%"
+                        h={`${(day.scheduled / maxReviews) * CHART_HEIGHT}px`}
+                        bg={day.overdue > 0 ? overdueColor : scheduledColor}
+                      />
+                    </VStack>
+                  </Tooltip>
+                ))}
+              </HStack>
+              <HStack spacing={4} mt={3} fontSize="sm" color={secondaryTextColor}>
+                <HStack><Box w={3} h={3} bg={scheduledColor} /><Text>Scheduled</Text></HStack>
+                <HStack><Box w={3} h={3} bg={projectedColor} /><Text>Projected</Text></HStack>
+                <HStack><Box w={3} h={3} bg={overdueColor} /><Text>Overdue</Text></HStack>
+              </HStack>
+              <Text fontSize="sm" color={secondaryTextColor} mt={2}>
+                Projected reviews assume each revision is recalled and graded 4 with the selected scheduler.
+              </Text>
+            </CardBody>
+          </Card>
+
+          {forecast.subjects.map((subject) => (
+            <Card key={subject.subjectId} bg={cardBg}>
+              <CardBody>
+                <HStack justify="space-between">
+                  <Text fontWeight="semibold" color={textColor}>{subject.subjectName}</Text>
+                  <Text color={secondaryTextColor}>
+                    {subject.reviews} review{subject.reviews !== 1 ? "s" : ""} · ~{formatMinutes(subject.minutes)}
+                    {" "}({formatMinutes(subject.minutesPerReview)} each)
+                  </Text>
+                </HStack>
+              </CardBody>
+            </Card>
+          ))}
+        </>
+      )}
+    </VStack>
+  );
+}
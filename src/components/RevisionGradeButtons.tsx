@@ -0,0 +1,90 @@
+import { useRef, useState } from "react";
+import { Button, HStack, Text, Tooltip, useToast } from "@chakra-ui/react";
+import { format, parseISO } from "date-fns";
+import { gradeRevision, undoReview } from "../services/database";
+
+// SM-2 quality scale, see scheduler.rs
+const GRADES = [
+  { quality: 0, label: "Total blackout", color: "red" },
+  { quality: 1, label: "Wrong, but the answer seemed familiar", color: "red" },
+  { quality: 2, label: "Wrong, but easy to recall once seen", color: "orange" },
+  { quality: 3, label: "Right, with serious difficulty", color: "yellow" },
+  { quality: 4, label: "Right, after some hesitation", color: "green" },
+  { quality: 5, label: "Perfect recall", color: "green" },
+];
+
+interface RevisionGradeButtonsProps {
+  revisionId: number;
+  // Called after grading and after undoing a grade
+  onChange: () => void;
+  size?: "xs" | "sm";
+}
+
+export default function RevisionGradeButtons({ revisionId, onChange, size = "sm" }: RevisionGradeButtonsProps) {
+  const [grading, setGrading] = useState<number | null>(null);
+  // Time taken is counted from when the revision was shown
+  const shownAt = useRef(Date.now());
+  const toast = useToast();
+
+  async function handleUndo(reviewId: number, toastId: string | number) {
+    toast.close(toastId);
+    try {
+      await undoReview(reviewId);
+      shownAt.current = Date.now();
+      onChange();
+    } catch (error) {
+      toast({ title: "Could not undo grade", description: String(error), status: "error", duration: 5000 });
+    }
+  }
+
+  async function handleGrade(quality: number) {
+    try {
+      setGrading(quality);
+      const seconds = Math.round((Date.now() - shownAt.current) / 1000);
+      const grade = await gradeRevision(revisionId, quality, seconds);
+      const toastId = toast({
+        title: "Revision graded",
+        description: (
+          <HStack justify="space-between">
+            <Text>Next review on {format(parseISO(grade.next_revision.due_date), "MMM dd, yyyy")}</Text>
+            <Button size="xs" variant="outline" colorScheme="whiteAlpha" onClick={() => handleUndo(grade.review.id, toastId)}>
+              Undo
+            </Button>
+          </HStack>
+        ),
+        status: "success",
+        duration: 6000,
+      });
+      onChange();
+    } catch (error) {
+      toast({
+        title: "Error grading revision",
+        description: String(error),
+        status: "error",
+        duration: 5000,
+      });
+    } finally {
+      setGrading(null);
+    }
+  }
+
+  return (
+    <HStack spacing={1}>
+      {GRADES.map(({ quality, label, color }) => (
+        <Tooltip key={quality} label={label} hasArrow>
+          <Button
+            size={size}
+            variant="outline"
+            colorScheme={color}
+            minW={size === "xs" ? 6 : 8}
+            isLoading={grading === quality}
+            isDisabled={grading !== null && grading !== quality}
+            onClick={() => handleGrade(quality)}
+          >
+            {quality}
+          </Button>
+        </Tooltip>
+      ))}
+    </HStack>
+  );
+}
@@ -0,0 +1,212 @@
+import { useEffect, useState } from "react";
+import {
+  Button,
+  Card,
+  CardBody,
+  FormControl,
+  FormLabel,
+  Heading,
+  HStack,
+  Input,
+  Select,
+  Switch,
+  Text,
+  VStack,
+  useToast,
+} from "@chakra-ui/react";
+import {
+  LoadBalancing,
+  getLoadBalancing,
+  getSettings,
+  optimizeFsrsParameters,
+  resetFsrsParameters,
+  setLoadBalancing,
+  updateSetting,
+} from "../services/database";
+
+// Keys match the settings read by scheduler.rs
+const SCHEDULER_SETTING = "scheduler";
+const RETENTION_SETTING = "fsrs_desired_retention";
+const PARAMETERS_SETTING = "fsrs_parameters";
+
+export default function SchedulerSettings() {
+  const [scheduler, setScheduler] = useState("sm2");
+  const [retention, setRetention] = useState(90);
+  const [customParameters, setCustomParameters] = useState(false);
+  const [busy, setBusy] = useState(false);
+  const [balancing, setBalancing] = useState<LoadBalancing>({ enabled: false, maxDailyReviews: null, tolerancePercent: 10 });
+  const toast = useToast();
+
+  async function load() {
+    try {
+      const settings = await getSettings();
+      setScheduler(settings[SCHEDULER_SETTING] || "sm2");
+      const saved = parseFloat(settings[RETENTION_SETTING]);
+      setRetention(Number.isFinite(saved) ? Math.round(saved * 100) : 90);
+      setCustomParameters(Boolean(settings[PARAMETERS_SETTING]));
+      setBalancing(await getLoadBalancing());
+    } catch (error) {
+      console.error("Failed to load scheduler settings:", error);
+    }
+  }
+
+  useEffect(() => {
+    load();
+  }, []);
+
+  async function handleSchedulerChange(value: string) {
+    try {
+      setScheduler(value);
+      await updateSetting(SCHEDULER_SETTING, value);
+    } catch (error) {
+      toast({ title: "Could not change scheduler", description: String(error), status: "error", duration: 5000 });
+    }
+  }
+
+  async function handleRetentionSave() {
+    const clamped = Math.min(97, Math.max(70, retention || 90));
+    try {
+      setRetention(clamped);
+      await updateSetting(RETENTION_SETTING, (clamped / 100).toString());
+    } catch (error) {
+      toast({ title: "Could not save retention", description: String(error), status: "error", duration: 5000 });
+    }
+  }
+
+  async function saveBalancing(changed: LoadBalancing) {
+    try {
+      setBalancing(await setLoadBalancing(changed));
+    } catch (error) {
+      toast({ title: "Could not save load balancing", description: String(error), status: "error", duration: 5000 });
+      await load();
+    }
+  }
+
+  async function handleOptimize() {
+    try {
+      setBusy(true);
+      const result = await optimizeFsrsParameters();
+      toast({
+        title: "FSRS parameters optimized",
+        description: `Fitted to ${result.reviews} reviews, log loss ${result.lossBefore.toFixed(3)} → ${result.lossAfter.toFixed(3)}`,
+        status: "success",
+        duration: 5000,
+      });
+      await load();
+    } catch (error) {
+      toast({ title: "Could not optimize", description: String(error), status: "error", duration: 5000 });
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function handleReset() {
+    try {
+      setBusy(true);
+      await resetFsrsParameters();
+      await load();
+    } catch (error) {
+      toast({ title: "Could not reset parameters", description: String(error), status: "error", duration: 5000 });
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  return (
+    <Card>
+      <CardBody>
+        <Heading size="md" mb={4}>
+          Scheduler
+        </Heading>
+
+        <VStack spacing={4} align="stretch">
+          <FormControl>
+            <FormLabel>Algorithm</FormLabel>
+            <Select value={scheduler} onChange={(e) => handleSchedulerChange(e.target.value)}>
+              <option value="sm2">SM-2</option>
+              <option value="fsrs">FSRS</option>
+            </Select>
+            <Text fontSize="sm" color="text.tertiary" mt={2}>
+              Decides when a graded revision comes back. Switching applies from the next grade.
+            </Text>
+          </FormControl>
+
+          {scheduler === "fsrs" && (
+            <>
+              <FormControl>
+                <FormLabel>Desired Retention (%)</FormLabel>
+                <Input
+                  type="number"
+                  value={retention}
+                  onChange={(e) => setRetention(parseInt(e.target.value) || 0)}
+                  onBlur={handleRetentionSave}
+                  min={70}
+                  max={97}
+                />
+                <Text fontSize="sm" color="text.tertiary" mt={2}>
+                  Chance of still remembering an entry when it comes due. Higher means more frequent revisions.
+                </Text>
+              </FormControl>
+
+              <HStack>
+                <Button onClick={handleOptimize} isLoading={busy}>
+                  Optimize Parameters
+                </Button>
+                <Button variant="outline" onClick={handleReset} isDisabled={busy || !customParameters}>
+                  Reset to Defaults
+                </Button>
+              </HStack>
+              <Text fontSize="sm" color="text.tertiary">
+                {customParameters
+                  ? "Using parameters fitted to your revision history."
+                  : "Using the default FSRS parameters. Optimize once you have graded a few dozen revisions."}
+              </Text>
+            </>
+          )}
+
+          <FormControl display="flex" alignItems="center">
+            <FormLabel mb={0}>Spread revisions over lighter days</FormLabel>
+            <Switch
+              isChecked={balancing.enabled}
+              onChange={(e) => saveBalancing({ ...balancing, enabled: e.target.checked })}
+              colorScheme="teal"
+            />
+          </FormControl>
+
+          {balancing.enabled && (
+            <HStack spacing={4} align="flex-start">
+              <FormControl>
+                <FormLabel>Maximum Reviews per Day</FormLabel>
+                <Input
+                  type="number"
+                  value={balancing.maxDailyReviews ?? ""}
+                  placeholder="No limit"
+                  onChange={(e) =>
+                    setBalancing({ ...balancing, maxDailyReviews: parseInt(e.target.value) > 0 ? parseInt(e.target.value) : null })
+                  }
+                  onBlur={() => saveBalancing(balancing)}
+                  min={1}
+                />
+              </FormControl>
+              <FormControl>
+                <FormLabel>Tolerance (%)</FormLabel>
+                <Input
+                  type="number"
+                  value={balancing.tolerancePercent}
+                  onChange={(e) => setBalancing({ ...balancing, tolerancePercent: parseInt(e.target.value) || 0 })}
+                  onBlur={() => saveBalancing({ ...balancing, tolerancePercent: Math.min(50, Math.max(0, balancing.tolerancePercent)) })}
+                  min={0}
+                  max={50}
+                />
+              </FormControl>
+            </HStack>
+          )}
+          <Text fontSize="sm" color="text.tertiary">
+            New revisions may move by up to the tolerance (at least a day for intervals of 3 days or more) to the day
+            with the fewest reviews, and skip days already at the maximum.
+          </Text>
+        </VStack>
+      </CardBody>
+    </Card>
+  );
+}
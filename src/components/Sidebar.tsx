@@ -5,6 +5,7 @@ import { getRevisionsDueToday } from "../services/database";
 
 const menuItems = [
   { path: "/", label: "Subjects", icon: "📚" },
+  { path: "/search", label: "Search", icon: "🔍" },
   { path: "/pomodoro", label: "Pomodoro", icon: "🍅" },
   { path: "/streaks", label: "Streaks", icon: "🔥" },
   { path: "/analytics", label: "Analytics", icon: "📊" },
@@ -0,0 +1,61 @@
+import { useEffect, useState } from "react";
+import { Card, CardBody, FormControl, FormLabel, Heading, Select, Text, useToast } from "@chakra-ui/react";
+import { getTimezone, setTimezone } from "../services/database";
+import { TimezoneInfo } from "../types";
+
+// Empty value: follow the system time zone
+const SYSTEM = "";
+
+function timezoneNames(): string[] {
+  const intl = Intl as typeof Intl & { supportedValuesOf?: (key: string) => string[] };
+  return intl.supportedValuesOf ? intl.supportedValuesOf("timeZone") : [];
+}
+
+export default function TimezoneSettings() {
+  const [info, setInfo] = useState<TimezoneInfo | null>(null);
+  const [names] = useState(timezoneNames);
+  const toast = useToast();
+
+  useEffect(() => {
+    getTimezone()
+      .then(setInfo)
+      .catch((error) => console.error("Failed to load time zone:", error));
+  }, []);
+
+  async function handleChange(value: string) {
+    try {
+      setInfo(await setTimezone(value === SYSTEM ? null : value));
+    } catch (error) {
+      toast({ title: "Could not change time zone", description: String(error), status: "error", duration: 5000 });
+    }
+  }
+
+  const selected = info?.timezone ?? SYSTEM;
+
+  return (
+    <Card>
+      <CardBody>
+        <Heading size="md" mb={4}>
+          Time Zone
+        </Heading>
+
+        <FormControl>
+          <FormLabel>Days start at midnight in</FormLabel>
+          <Select value={selected} onChange={(e) => handleChange(e.target.value)} isDisabled={!info}>
+            <option value={SYSTEM}>Follow system{info?.system ? ` (${info.system})` : ""}</option>
+            {selected !== SYSTEM && !names.includes(selected) && <option value={selected}>{selected}</option>}
+            {names.map((name) => (
+              <option key={name} value={name}>
+                {name}
+              </option>
+            ))}
+          </Select>
+          <Text fontSize="sm" color="text.tertiary" mt={2}>
+            Due dates, streaks and the activity heatmap use this time zone.
+            {info && ` Today is ${info.today} there.`}
+          </Text>
+        </FormControl>
+      </CardBody>
+    </Card>
+  );
+}
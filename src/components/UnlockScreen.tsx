@@ -0,0 +1,102 @@
+import { useEffect, useState } from "react";
+import {
+  Box,
+  Button,
+  Card,
+  CardBody,
+  FormControl,
+  FormErrorMessage,
+  FormLabel,
+  Heading,
+  Input,
+  Select,
+  Text,
+  VStack,
+} from "@chakra-ui/react";
+import { Profiles, listProfiles, switchProfile, unlockDatabase } from "../services/database";
+
+interface UnlockScreenProps {
+  onUnlocked: () => void;
+}
+
+export default function UnlockScreen({ onUnlocked }: UnlockScreenProps) {
+  const [passphrase, setPassphrase] = useState("");
+  const [error, setError] = useState<string | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [profiles, setProfiles] = useState<Profiles | null>(null);
+
+  useEffect(() => {
+    listProfiles()
+      .then(setProfiles)
+      .catch((e) => console.error("Failed to load profiles:", e));
+  }, []);
+
+  async function handleSwitchProfile(id: string) {
+    try {
+      await switchProfile(id);
+      // The other profile may not be encrypted at all
+      window.location.reload();
+    } catch (e) {
+      setError(String(e));
+    }
+  }
+
+  async function handleUnlock() {
+    if (!passphrase) return;
+    try {
+      setLoading(true);
+      setError(null);
+      await unlockDatabase(passphrase);
+      onUnlocked();
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setLoading(false);
+    }
+  }
+
+  return (
+    <Box minH="100vh" display="flex" alignItems="center" justifyContent="center" p={8}>
+      <Card w="100%" maxW="420px">
+        <CardBody>
+          <VStack spacing={4} align="stretch">
+            <Heading size="md">Unlock Recallify</Heading>
+            <Text fontSize="sm" color="text.tertiary">
+              Your study data is encrypted. Enter your passphrase to continue.
+            </Text>
+            {profiles && profiles.profiles.length > 1 && (
+              <FormControl>
+                <FormLabel>Profile</FormLabel>
+                <Select value={profiles.active} onChange={(e) => handleSwitchProfile(e.target.value)}>
+                  {profiles.profiles.map((profile) => (
+                    <option key={profile.id} value={profile.id}>
+                      {profile.name}
+                    </option>
+                  ))}
+                </Select>
+              </FormControl>
+            )}
+            <FormControl isInvalid={error !== null}>
+              <FormLabel>Passphrase</FormLabel>
+              <Input
+                type="password"
+                autoFocus
+                value={passphrase}
+                onChange={(e) => setPassphrase(e.target.value)}
+                onKeyPress={(e) => {
+                  if (e.key === "Enter") {
+                    handleUnlock();
+                  }
+                }}
+              />
+              {error && <FormErrorMessage>{error}</FormErrorMessage>}
+            </FormControl>
+            <Button onClick={handleUnlock} isLoading={loading} isDisabled={!passphrase}>
+              Unlock
+            </Button>
+          </VStack>
+        </CardBody>
+      </Card>
+    </Box>
+  );
+}
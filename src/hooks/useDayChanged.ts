@@ -0,0 +1,25 @@
+import { useEffect, useRef } from "react";
+import { listen } from "@tauri-apps/api/event";
+
+// Emitted by the backend at local midnight, after it has marked revisions
+// that just became overdue (see day_rollover.rs)
+export const DAY_CHANGED_EVENT = "day-changed";
+
+export interface DayChanged {
+  date: string;
+  overdue: number;
+}
+
+// Calls `onDayChanged` whenever the local date rolls over, so pages can
+// reload their due lists and calendars.
+export function useDayChanged(onDayChanged: (event: DayChanged) => void) {
+  const callback = useRef(onDayChanged);
+  callback.current = onDayChanged;
+
+  useEffect(() => {
+    const unlisten = listen<DayChanged>(DAY_CHANGED_EVENT, (event) => callback.current(event.payload));
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+}
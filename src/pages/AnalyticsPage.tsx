@@ -20,7 +20,8 @@ import {
   Tab,
   TabPanel,
 } from "@chakra-ui/react";
-import { invoke } from "@tauri-apps/api/tauri";
+import { getPomodoroStats, getSubjectPomodoroTotals } from "../services/database";
+import ReviewForecastChart from "../components/ReviewForecastChart";
 
 interface SubjectStats {
   subject_id: number;
@@ -61,21 +62,7 @@ export default function AnalyticsPage() {
 
   async function loadSubjectStats() {
     try {
-      const result = await invoke<any[]>("db_select", {
-        sql: `
-          SELECT
-            s.id as subject_id,
-            s.name as subject_name,
-            SUM(ps.duration_minutes) as total_minutes,
-            COUNT(ps.id) as session_count
-          FROM pomodoro_sessions ps
-          INNER JOIN subjects s ON ps.subject_id = s.id
-          WHERE ps.session_type = 'work'
-          GROUP BY s.id, s.name
-          ORDER BY total_minutes DESC
-        `,
-        params: [],
-      });
+      const result = await getSubjectPomodoroTotals();
       setSubjectStats(result);
     } catch (error) {
       console.error("Error loading subject stats:", error);
@@ -84,92 +71,16 @@ export default function AnalyticsPage() {
 
   async function loadTimeRangeStats() {
     try {
-      // Today
-      const today = await invoke<any[]>("db_select", {
-        sql: `
-          SELECT
-            COUNT(*) as total_sessions,
-            SUM(duration_minutes) as total_minutes,
-            SUM(CASE WHEN session_type = 'work' THEN 1 ELSE 0 END) as work_sessions,
-            SUM(CASE WHEN session_type != 'work' THEN 1 ELSE 0 END) as break_sessions
-          FROM pomodoro_sessions
-          WHERE DATE(completed_at) = DATE('now')
-        `,
-        params: [],
-      });
-      if (today.length > 0) {
-        setTodayStats({
-          total_sessions: today[0].total_sessions || 0,
-          total_minutes: today[0].total_minutes || 0,
-          work_sessions: today[0].work_sessions || 0,
-          break_sessions: today[0].break_sessions || 0,
-        });
-      }
-
-      // This Week
-      const week = await invoke<any[]>("db_select", {
-        sql: `
-          SELECT
-            COUNT(*) as total_sessions,
-            SUM(duration_minutes) as total_minutes,
-            SUM(CASE WHEN session_type = 'work' THEN 1 ELSE 0 END) as work_sessions,
-            SUM(CASE WHEN session_type != 'work' THEN 1 ELSE 0 END) as break_sessions
-          FROM pomodoro_sessions
-          WHERE DATE(completed_at) >= DATE('now', '-7 days')
-        `,
-        params: [],
-      });
-      if (week.length > 0) {
-        setWeekStats({
-          total_sessions: week[0].total_sessions || 0,
-          total_minutes: week[0].total_minutes || 0,
-          work_sessions: week[0].work_sessions || 0,
-          break_sessions: week[0].break_sessions || 0,
-        });
-      }
-
-      // This Month
-      const month = await invoke<any[]>("db_select", {
-        sql: `
-          SELECT
-            COUNT(*) as total_sessions,
-            SUM(duration_minutes) as total_minutes,
-            SUM(CASE WHEN session_type = 'work' THEN 1 ELSE 0 END) as work_sessions,
-            SUM(CASE WHEN session_type != 'work' THEN 1 ELSE 0 END) as break_sessions
-          FROM pomodoro_sessions
-          WHERE DATE(completed_at) >= DATE('now', '-30 days')
-        `,
-        params: [],
-      });
-      if (month.length > 0) {
-        setMonthStats({
-          total_sessions: month[0].total_sessions || 0,
-          total_minutes: month[0].total_minutes || 0,
-          work_sessions: month[0].work_sessions || 0,
-          break_sessions: month[0].break_sessions || 0,
-        });
-      }
-
-      // All Time
-      const allTime = await invoke<any[]>("db_select", {
-        sql: `
-          SELECT
-            COUNT(*) as total_sessions,
-            SUM(duration_minutes) as total_minutes,
-            SUM(CASE WHEN session_type = 'work' THEN 1 ELSE 0 END) as work_sessions,
-            SUM(CASE WHEN session_type != 'work' THEN 1 ELSE 0 END) as break_sessions
-          FROM pomodoro_sessions
-        `,
-        params: [],
-      });
-      if (allTime.length > 0) {
-        setAllTimeStats({
-          total_sessions: allTime[0].total_sessions || 0,
-          total_minutes: allTime[0].total_minutes || 0,
-          work_sessions: allTime[0].work_sessions || 0,
-          break_sessions: allTime[0].break_sessions || 0,
-        });
-      }
+      const [today, week, month, allTime] = await Promise.all([
+        getPomodoroStats("today"),
+        getPomodoroStats("week"),
+        getPomodoroStats("month"),
+        getPomodoroStats("all"),
+      ]);
+      setTodayStats(today);
+      setWeekStats(week);
+      setMonthStats(month);
+      setAllTimeStats(allTime);
     } catch (error) {
       console.error("Error loading time range stats:", error);
     }
@@ -222,6 +133,7 @@ export default function AnalyticsPage() {
         <TabList mb={6}>
           <Tab>Overview</Tab>
           <Tab>By Subject</Tab>
+          <Tab>Review Forecast</Tab>
         </TabList>
 
         <TabPanels>
@@ -314,6 +226,11 @@ export default function AnalyticsPage() {
               )}
             </VStack>
           </TabPanel>
+
+          {/* Review Forecast Tab */}
+          <TabPanel>
+            <ReviewForecastChart />
+          </TabPanel>
         </TabPanels>
       </Tabs>
     </Box>
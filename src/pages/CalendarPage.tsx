@@ -15,6 +15,7 @@ import {
   useToast,
   Checkbox,
   Button,
+  useDisclosure,
 } from "@chakra-ui/react";
 import {
   format,
@@ -24,16 +25,20 @@ import {
   getDay,
   addMonths,
   subMonths,
+  parseISO,
 } from "date-fns";
 import {
   getCalendarData,
   getRevisionsByDate,
   getEntryById,
   getSubjectById,
-  completeRevision,
+  getTimezone,
   uncompleteRevision,
 } from "../services/database";
 import { CalendarDay, Revision, EntryWithDetails, Subject } from "../types";
+import RevisionGradeButtons from "../components/RevisionGradeButtons";
+import CatchUpModal from "../components/CatchUpModal";
+import { useDayChanged } from "../hooks/useDayChanged";
 
 export default function CalendarPage() {
   const [currentDate, setCurrentDate] = useState(new Date());
@@ -43,19 +48,31 @@ export default function CalendarPage() {
     (Revision & { entry: EntryWithDetails; subject: Subject })[]
   >([]);
   const [loading, setLoading] = useState(true);
+  // In the user's time zone, from the backend
+  const [today, setToday] = useState<string | null>(null);
+  const catchUp = useDisclosure();
   const toast = useToast();
 
   useEffect(() => {
     loadCalendarData();
   }, [currentDate]);
 
+  // Yesterday's pending revisions are overdue now
+  useDayChanged(() => {
+    loadCalendarData();
+    if (selectedDate) {
+      handleDateClick(selectedDate);
+    }
+  });
+
   async function loadCalendarData() {
     try {
       setLoading(true);
       const month = currentDate.getMonth() + 1;
       const year = currentDate.getFullYear();
-      const data = await getCalendarData(month, year);
+      const [data, timezone] = await Promise.all([getCalendarData(month, year), getTimezone()]);
       setCalendarDays(data);
+      setToday(timezone.today);
     } catch (error) {
       toast({
         title: "Error loading calendar",
@@ -92,13 +109,9 @@ export default function CalendarPage() {
     }
   }
 
-  async function toggleRevision(revisionId: number, isCompleted: boolean) {
+  async function reopenRevision(revisionId: number) {
     try {
-      if (isCompleted) {
-        await uncompleteRevision(revisionId);
-      } else {
-        await completeRevision(revisionId);
-      }
+      await uncompleteRevision(revisionId);
       // Reload calendar and selected date
       loadCalendarData();
       if (selectedDate) {
@@ -122,9 +135,10 @@ export default function CalendarPage() {
     setCurrentDate(addMonths(currentDate, 1));
   }
 
-  function goToToday() {
-    setCurrentDate(new Date());
-    handleDateClick(format(new Date(), "yyyy-MM-dd"));
+  async function goToToday() {
+    const { today } = await getTimezone();
+    setCurrentDate(parseISO(today));
+    handleDateClick(today);
   }
 
   // Generate calendar grid
@@ -172,9 +186,23 @@ export default function CalendarPage() {
           <Button onClick={goToToday} variant="outline" ml={4}>
             Today
           </Button>
+          <Button onClick={catchUp.onOpen} variant="outline">
+            Catch Up
+          </Button>
         </HStack>
       </HStack>
 
+      <CatchUpModal
+        isOpen={catchUp.isOpen}
+        onClose={catchUp.onClose}
+        onApplied={() => {
+          loadCalendarData();
+          if (selectedDate) {
+            handleDateClick(selectedDate);
+          }
+        }}
+      />
+
       <Grid templateColumns="3fr 1fr" gap={6}>
         <Box>
           {/* Calendar Grid */}
@@ -204,7 +232,7 @@ export default function CalendarPage() {
             {daysInMonth.map((day) => {
               const dateStr = format(day, "yyyy-MM-dd");
               const calendarDay = calendarDays.find((cd) => cd.date === dateStr);
-              const isToday = dateStr === format(new Date(), "yyyy-MM-dd");
+              const isToday = dateStr === today;
               const isSelected = dateStr === selectedDate;
 
               let dotColor = "transparent";
@@ -289,16 +317,14 @@ export default function CalendarPage() {
                               Day {revision.interval_days}
                             </Text>
                           </VStack>
-                          <Checkbox
-                            colorScheme="green"
-                            isChecked={revision.status === "completed"}
-                            onChange={() =>
-                              toggleRevision(
-                                revision.id,
-                                revision.status === "completed"
-                              )
-                            }
-                          />
+                          {/* A grade is taken back with its undo instead, which also rolls back the schedule */}
+                          {revision.status === "completed" && revision.quality === null && (
+                            <Checkbox
+                              colorScheme="green"
+                              isChecked
+                              onChange={() => reopenRevision(revision.id)}
+                            />
+                          )}
                         </HStack>
                         {(revision.entry as any).topics ? (
                           <Text fontSize="sm" color="text.secondary" noOfLines={2}>
@@ -317,14 +343,30 @@ export default function CalendarPage() {
                               : revision.status === "overdue"
                               ? "red"
                               : revision.status === "pending" &&
-                                new Date(revision.due_date) <= new Date()
+                                today !== null &&
+                                revision.due_date <= today
                               ? "orange"
                               : "blue"
                           }
                           fontSize="xs"
                         >
                           {revision.status}
+                          {revision.quality !== null && ` · ${revision.quality}/5`}
                         </Badge>
+                        {(revision.status === "pending" || revision.status === "overdue") && (
+                          <Box mt={2}>
+                            <RevisionGradeButtons
+                              size="xs"
+                              revisionId={revision.id}
+                              onChange={() => {
+                                loadCalendarData();
+                                if (selectedDate) {
+                                  handleDateClick(selectedDate);
+                                }
+                              }}
+                            />
+                          </Box>
+                        )}
                       </Box>
                     ))}
                   </VStack>
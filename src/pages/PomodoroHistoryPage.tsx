@@ -13,7 +13,7 @@ import {
   Stack,
   Divider,
 } from "@chakra-ui/react";
-import { invoke } from "@tauri-apps/api/tauri";
+import { getPomodoroSessions, getSubjectNames } from "../services/database";
 
 interface PomodoroSession {
   id: number;
@@ -56,21 +56,7 @@ export default function PomodoroHistoryPage() {
 
   async function loadSessions() {
     try {
-      const result = await invoke<any[]>("db_select", {
-        sql: `
-          SELECT
-            ps.id,
-            ps.session_type,
-            ps.duration_minutes,
-            ps.subject_id,
-            s.name as subject_name,
-            ps.completed_at
-          FROM pomodoro_sessions ps
-          LEFT JOIN subjects s ON ps.subject_id = s.id
-          ORDER BY ps.completed_at DESC
-        `,
-        params: [],
-      });
+      const result = await getPomodoroSessions();
       setSessions(result);
     } catch (error) {
       console.error("Error loading sessions:", error);
@@ -79,10 +65,7 @@ export default function PomodoroHistoryPage() {
 
   async function loadSubjects() {
     try {
-      const result = await invoke<any[]>("db_select", {
-        sql: "SELECT id, name FROM subjects ORDER BY name ASC",
-        params: [],
-      });
+      const result = await getSubjectNames();
       setSubjects(result);
     } catch (error) {
       console.error("Error loading subjects:", error);
@@ -20,10 +20,18 @@ import {
   useDisclosure,
   useColorModeValue,
 } from "@chakra-ui/react";
-import { invoke } from "@tauri-apps/api/tauri";
 import { sendNotification } from "@tauri-apps/api/notification";
-import { format } from "date-fns";
-import { updateDailyActivity, calculateStreaks, checkAndRecordMilestone, markMilestoneShown, getTodayPomodoroSummary } from "../services/database";
+import {
+  updateDailyActivity,
+  calculateStreaks,
+  checkAndRecordMilestone,
+  markMilestoneShown,
+  getTodayPomodoroSummary,
+  getSubjectNames,
+  getPomodoroState,
+  updatePomodoroState,
+  recordPomodoroSession,
+} from "../services/database";
 import CelebrationModal from "../components/CelebrationModal";
 
 interface PomodoroState {
@@ -109,19 +117,13 @@ export default function PomodoroPage() {
 
   async function loadPomodoroState() {
     try {
-      const result = await invoke<any[]>("db_select", {
-        sql: "SELECT * FROM pomodoro_state WHERE id = 1",
-        params: [],
+      const dbState = await getPomodoroState();
+      setState({
+        session_type: dbState.session_type,
+        remaining_seconds: dbState.remaining_seconds,
+        is_running: dbState.is_running,
+        pomodoro_count: dbState.pomodoro_count,
       });
-      if (result.length > 0) {
-        const dbState = result[0];
-        setState({
-          session_type: dbState.session_type,
-          remaining_seconds: dbState.remaining_seconds,
-          is_running: dbState.is_running,
-          pomodoro_count: dbState.pomodoro_count,
-        });
-      }
     } catch (error) {
       console.error("Error loading pomodoro state:", error);
     }
@@ -129,10 +131,7 @@ export default function PomodoroPage() {
 
   async function loadSubjects() {
     try {
-      const result = await invoke<any[]>("db_select", {
-        sql: "SELECT id, name FROM subjects ORDER BY name ASC",
-        params: [],
-      });
+      const result = await getSubjectNames();
       setSubjects(result);
     } catch (error) {
       console.error("Error loading subjects:", error);
@@ -141,20 +140,14 @@ export default function PomodoroPage() {
 
   async function tick() {
     try {
-      const result = await invoke<any[]>("db_select", {
-        sql: "SELECT remaining_seconds FROM pomodoro_state WHERE id = 1",
-        params: [],
-      });
+      const current = await getPomodoroState();
 
-      const remaining = result[0].remaining_seconds - 1;
+      const remaining = current.remaining_seconds - 1;
 
       if (remaining <= 0) {
         await completeSession();
       } else {
-        await invoke("db_execute", {
-          sql: "UPDATE pomodoro_state SET remaining_seconds = ? WHERE id = 1",
-          params: [remaining],
-        });
+        await updatePomodoroState({ remaining_seconds: remaining });
         setState((prev) => ({ ...prev, remaining_seconds: remaining }));
       }
     } catch (error) {
@@ -173,10 +166,7 @@ export default function PomodoroPage() {
 
   async function startTimer() {
     try {
-      await invoke("db_execute", {
-        sql: "UPDATE pomodoro_state SET is_running = 1, start_timestamp = ?, updated_at = CURRENT_TIMESTAMP WHERE id = 1",
-        params: [Date.now()],
-      });
+      await updatePomodoroState({ is_running: 1, start_timestamp: Date.now() });
       setState((prev) => ({ ...prev, is_running: 1 }));
     } catch (error) {
       toast({
@@ -196,10 +186,7 @@ export default function PomodoroPage() {
 
   async function pauseTimer() {
     try {
-      await invoke("db_execute", {
-        sql: "UPDATE pomodoro_state SET is_running = 0, updated_at = CURRENT_TIMESTAMP WHERE id = 1",
-        params: [],
-      });
+      await updatePomodoroState({ is_running: 0 });
       setState((prev) => ({ ...prev, is_running: 0 }));
     } catch (error) {
       toast({
@@ -213,9 +200,12 @@ export default function PomodoroPage() {
   async function resetTimer() {
     try {
       // Reset entire session: back to work mode with 25 minutes and reset counter
-      await invoke("db_execute", {
-        sql: "UPDATE pomodoro_state SET session_type = 'work', remaining_seconds = 1500, duration_seconds = 1500, is_running = 0, pomodoro_count = 0, updated_at = CURRENT_TIMESTAMP WHERE id = 1",
-        params: [],
+      await updatePomodoroState({
+        session_type: "work",
+        remaining_seconds: 1500,
+        duration_seconds: 1500,
+        is_running: 0,
+        pomodoro_count: 0,
       });
       setState({
         session_type: "work",
@@ -244,27 +234,23 @@ export default function PomodoroPage() {
   async function endSession() {
     try {
       // Stop the timer first
-      await invoke("db_execute", {
-        sql: "UPDATE pomodoro_state SET is_running = 0 WHERE id = 1",
-        params: [],
-      });
+      await updatePomodoroState({ is_running: 0 });
       setState((prev) => ({ ...prev, is_running: 0 }));
 
       // Get the original duration from database
-      const result = await invoke<any[]>("db_select", {
-        sql: "SELECT duration_seconds FROM pomodoro_state WHERE id = 1",
-        params: [],
-      });
+      const stopped = await getPomodoroState();
 
-      const originalDuration = result[0].duration_seconds;
+      const originalDuration = stopped.duration_seconds;
       const timeSpent = originalDuration - state.remaining_seconds;
       const minutesSpent = Math.round(timeSpent / 60);
 
       // Only save if at least 1 minute was spent
       if (minutesSpent >= 1) {
-        await invoke("db_execute", {
-          sql: "INSERT INTO pomodoro_sessions (session_type, duration_minutes, subject_id) VALUES (?, ?, ?)",
-          params: [state.session_type, minutesSpent, state.session_type === "work" ? selectedSubjectId : null],
+        await recordPomodoroSession({
+          session_type: state.session_type,
+          duration_minutes: minutesSpent,
+          subject_id: state.session_type === "work" ? selectedSubjectId : null,
+          syllabus_item_id: null,
         });
 
         // If it was a work session and substantial time was spent (>= 12 minutes, half a pomodoro)
@@ -273,8 +259,7 @@ export default function PomodoroPage() {
           const newCount = state.pomodoro_count + 1;
 
           // Update activity tracking
-          const today = format(new Date(), "yyyy-MM-dd");
-          await updateDailyActivity(today);
+          await updateDailyActivity();
 
           // Get session summary
           const summary = await getTodayPomodoroSummary();
@@ -335,10 +320,7 @@ export default function PomodoroPage() {
     try {
       // CRITICAL: Stop the timer FIRST to prevent infinite loop
       // This prevents tick() from calling completeSession() repeatedly
-      await invoke("db_execute", {
-        sql: "UPDATE pomodoro_state SET is_running = 0 WHERE id = 1",
-        params: [],
-      });
+      await updatePomodoroState({ is_running: 0 });
       setState((prev) => ({ ...prev, is_running: 0 }));
 
       // Play sound (simplified)
@@ -346,17 +328,18 @@ export default function PomodoroPage() {
 
       // Save completed session
       const durationMinutes = state.session_type === "work" ? 25 : state.session_type === "short_break" ? 5 : longBreakDuration;
-      await invoke("db_execute", {
-        sql: "INSERT INTO pomodoro_sessions (session_type, duration_minutes, subject_id) VALUES (?, ?, ?)",
-        params: [state.session_type, durationMinutes, state.session_type === "work" ? selectedSubjectId : null],
+      await recordPomodoroSession({
+        session_type: state.session_type,
+        duration_minutes: durationMinutes,
+        subject_id: state.session_type === "work" ? selectedSubjectId : null,
+        syllabus_item_id: null,
       });
 
       const isWorkComplete = state.session_type === "work";
 
       // Check for milestone celebration after work session
       if (isWorkComplete) {
-        const today = format(new Date(), "yyyy-MM-dd");
-        await updateDailyActivity(today);
+        await updateDailyActivity();
 
         // Get session summary
         const summary = await getTodayPomodoroSummary();
@@ -451,9 +434,12 @@ export default function PomodoroPage() {
 
   async function transitionToBreak(breakType: "short_break" | "long_break", count: number) {
     const duration = breakType === "short_break" ? 300 : longBreakDuration * 60;
-    await invoke("db_execute", {
-      sql: "UPDATE pomodoro_state SET session_type = ?, remaining_seconds = ?, duration_seconds = ?, is_running = 0, pomodoro_count = ? WHERE id = 1",
-      params: [breakType, duration, duration, count],
+    await updatePomodoroState({
+      session_type: breakType,
+      remaining_seconds: duration,
+      duration_seconds: duration,
+      is_running: 0,
+      pomodoro_count: count,
     });
     setState({
       session_type: breakType,
@@ -465,9 +451,12 @@ export default function PomodoroPage() {
 
   async function transitionToWork() {
     const newCount = state.session_type === "long_break" ? 0 : state.pomodoro_count;
-    await invoke("db_execute", {
-      sql: "UPDATE pomodoro_state SET session_type = 'work', remaining_seconds = 1500, duration_seconds = 1500, is_running = 0, pomodoro_count = ? WHERE id = 1",
-      params: [newCount],
+    await updatePomodoroState({
+      session_type: "work",
+      remaining_seconds: 1500,
+      duration_seconds: 1500,
+      is_running: 0,
+      pomodoro_count: newCount,
     });
     setState({
       session_type: "work",
@@ -0,0 +1,222 @@
+import { Fragment, useEffect, useState } from "react";
+import {
+  Box,
+  Heading,
+  VStack,
+  HStack,
+  Text,
+  Card,
+  CardBody,
+  Select,
+  Input,
+  Spinner,
+  useToast,
+  useColorModeValue,
+} from "@chakra-ui/react";
+import { useNavigate } from "react-router-dom";
+import { format, parseISO } from "date-fns";
+import { SearchHit, getAllTags, getSubjects, searchEntries } from "../services/database";
+import { Subject } from "../types";
+
+// Renders a snippet from the backend, highlighting the text between
+// <mark></mark> markers. Everything else is shown as plain text.
+function Snippet({ text }: { text: string }) {
+  const parts = text.split(/<mark>|<\/mark>/);
+  return (
+    <Text fontSize="sm">
+      {parts.map((part, i) =>
+        i % 2 === 1 ? (
+          <Box as="mark" key={i} bg="yellow.200" color="black" px="1px" borderRadius="sm">
+            {part}
+          </Box>
+        ) : (
+          <Fragment key={i}>{part}</Fragment>
+        )
+      )}
+    </Text>
+  );
+}
+
+export default function SearchPage() {
+  const [query, setQuery] = useState("");
+  const [hits, setHits] = useState<SearchHit[]>([]);
+  const [subjects, setSubjects] = useState<Subject[]>([]);
+  const [tags, setTags] = useState<{ id: number; name: string }[]>([]);
+  const [loading, setLoading] = useState(false);
+
+  // Filters
+  const [subjectFilter, setSubjectFilter] = useState<string>("");
+  const [tagFilter, setTagFilter] = useState<string>("");
+  const [startDate, setStartDate] = useState<string>("");
+  const [endDate, setEndDate] = useState<string>("");
+
+  const toast = useToast();
+  const navigate = useNavigate();
+
+  // Dark mode colors
+  const bgColor = useColorModeValue("white", "#1a1a1a");
+  const borderColor = useColorModeValue("gray.200", "#2d2d2d");
+  const itemBgColor = useColorModeValue("gray.50", "#252525");
+  const hoverBgColor = useColorModeValue("gray.100", "#2f2f2f");
+  const textColor = useColorModeValue("text.primary", "#ffffff");
+  const secondaryTextColor = useColorModeValue("text.secondary", "#b0b0b0");
+
+  useEffect(() => {
+    getSubjects()
+      .then(setSubjects)
+      .catch((error) => console.error("Failed to load subjects:", error));
+    getAllTags()
+      .then(setTags)
+      .catch((error) => console.error("Failed to load tags:", error));
+  }, []);
+
+  useEffect(() => {
+    // Wait for a pause in typing before searching
+    const timeout = setTimeout(runSearch, 250);
+    return () => clearTimeout(timeout);
+  }, [query, subjectFilter, tagFilter, startDate, endDate]);
+
+  async function runSearch() {
+    if (!query.trim()) {
+      setHits([]);
+      return;
+    }
+
+    try {
+      setLoading(true);
+      const data = await searchEntries(query, {
+        subjectId: subjectFilter ? parseInt(subjectFilter) : undefined,
+        tag: tagFilter || undefined,
+        startDate: startDate || undefined,
+        endDate: endDate || undefined,
+      });
+      setHits(data);
+    } catch (error) {
+      toast({
+        title: "Search failed",
+        description: String(error),
+        status: "error",
+        duration: 5000,
+      });
+    } finally {
+      setLoading(false);
+    }
+  }
+
+  return (
+    <Box>
+      <Heading size="xl" color={textColor} mb={8}>
+        Search
+      </Heading>
+
+      <Card mb={6} bg={bgColor}>
+        <CardBody>
+          <VStack spacing={4} align="stretch">
+            <Input
+              size="lg"
+              autoFocus
+              placeholder='Search notes, topics and tags — use "quotes" for a phrase'
+              value={query}
+              onChange={(e) => setQuery(e.target.value)}
+            />
+
+            <HStack spacing={4} wrap="wrap">
+              <Box minW="200px">
+                <Text fontSize="sm" mb={2} fontWeight="semibold">
+                  Subject
+                </Text>
+                <Select
+                  placeholder="All subjects"
+                  value={subjectFilter}
+                  onChange={(e) => setSubjectFilter(e.target.value)}
+                >
+                  {subjects.map((subject) => (
+                    <option key={subject.id} value={subject.id}>
+                      {subject.name}
+                    </option>
+                  ))}
+                </Select>
+              </Box>
+
+              <Box minW="200px">
+                <Text fontSize="sm" mb={2} fontWeight="semibold">
+                  Tag
+                </Text>
+                <Select placeholder="All tags" value={tagFilter} onChange={(e) => setTagFilter(e.target.value)}>
+                  {tags.map((tag) => (
+                    <option key={tag.id} value={tag.name}>
+                      {tag.name}
+                    </option>
+                  ))}
+                </Select>
+              </Box>
+
+              <Box minW="150px">
+                <Text fontSize="sm" mb={2} fontWeight="semibold">
+                  From Date
+                </Text>
+                <Input type="date" value={startDate} onChange={(e) => setStartDate(e.target.value)} />
+              </Box>
+
+              <Box minW="150px">
+                <Text fontSize="sm" mb={2} fontWeight="semibold">
+                  To Date
+                </Text>
+                <Input type="date" value={endDate} onChange={(e) => setEndDate(e.target.value)} />
+              </Box>
+            </HStack>
+          </VStack>
+        </CardBody>
+      </Card>
+
+      {loading ? (
+        <Box display="flex" justifyContent="center" alignItems="center" minH="200px">
+          <Spinner size="xl" color="primary.500" />
+        </Box>
+      ) : !query.trim() ? null : hits.length === 0 ? (
+        <Box
+          textAlign="center"
+          py={16}
+          px={4}
+          bg={bgColor}
+          borderRadius="md"
+          border="2px dashed"
+          borderColor={borderColor}
+        >
+          <Text fontSize="lg" color={secondaryTextColor}>
+            No entries match your search
+          </Text>
+        </Box>
+      ) : (
+        <VStack spacing={3} align="stretch">
+          {hits.map((hit) => (
+            <Box
+              key={hit.entry_id}
+              p={4}
+              bg={itemBgColor}
+              borderRadius="md"
+              cursor="pointer"
+              _hover={{ bg: hoverBgColor }}
+              onClick={() => navigate(`/subjects/${hit.subject_id}`)}
+            >
+              <HStack mb={1} spacing={3}>
+                <Text color="primary.500" fontWeight="semibold">
+                  {hit.subject_name}
+                </Text>
+                <Text fontSize="sm" color={secondaryTextColor}>
+                  {format(parseISO(hit.study_date), "MMMM dd, yyyy")}
+                </Text>
+              </HStack>
+              {hit.topics && (
+                <Text fontSize="sm" fontWeight="semibold" color={textColor} mb={1}>
+                  {hit.topics}
+                </Text>
+              )}
+              <Snippet text={hit.snippet} />
+            </Box>
+          ))}
+        </VStack>
+      )}
+    </Box>
+  );
+}
@@ -18,6 +18,13 @@ import {
 } from "@chakra-ui/react";
 import { getSettings, updateSetting } from "../services/database";
 import { useTheme } from "../contexts/ThemeContext";
+import EncryptionSettings from "../components/EncryptionSettings";
+import BackupSettings from "../components/BackupSettings";
+import DatabaseHealthSettings from "../components/DatabaseHealthSettings";
+import DataLocationSettings from "../components/DataLocationSettings";
+import ProfileSettings from "../components/ProfileSettings";
+import SchedulerSettings from "../components/SchedulerSettings";
+import TimezoneSettings from "../components/TimezoneSettings";
 
 export default function SettingsPage() {
   const [intervals, setIntervals] = useState<number[]>([3, 7]);
@@ -216,6 +223,12 @@ export default function SettingsPage() {
           </CardBody>
         </Card>
 
+        {/* Scheduler */}
+        <SchedulerSettings />
+
+        {/* Time Zone */}
+        <TimezoneSettings />
+
         {/* Pomodoro Timer */}
         <Card>
           <CardBody>
@@ -308,6 +321,21 @@ export default function SettingsPage() {
           </CardBody>
         </Card>
 
+        {/* Profiles */}
+        <ProfileSettings />
+
+        {/* Encryption */}
+        <EncryptionSettings />
+
+        {/* Backup & Restore */}
+        <BackupSettings />
+
+        {/* Database Health */}
+        <DatabaseHealthSettings />
+
+        {/* Data Location */}
+        <DataLocationSettings />
+
         {/* About */}
         <Card>
           <CardBody>
@@ -26,8 +26,8 @@ import {
   Button,
   useDisclosure,
 } from "@chakra-ui/react";
-import { format, subMonths } from "date-fns";
-import { getDailyActivities, calculateStreaks, updateDailyActivity } from "../services/database";
+import { format, parseISO, subMonths } from "date-fns";
+import { getDailyActivities, calculateStreaks, getTimezone, updateDailyActivity } from "../services/database";
 import CalendarHeatmap from "../components/CalendarHeatmap";
 
 interface StreakData {
@@ -60,6 +60,7 @@ export default function StreakPage() {
   const [lastCelebrated, setLastCelebrated] = useState<number>(0);
   const { isOpen, onOpen, onClose } = useDisclosure();
   const [currentMilestone, setCurrentMilestone] = useState<MilestoneData | null>(null);
+  const [today, setToday] = useState<string>();
 
   // Dark mode colors
   const bgColor = useColorModeValue("background.main", "#0f0f0f");
@@ -76,14 +77,15 @@ export default function StreakPage() {
   async function loadData() {
     try {
       // Update today's activity
-      const today = format(new Date(), "yyyy-MM-dd");
-      await updateDailyActivity(today);
+      await updateDailyActivity();
 
-      // Load activities for the selected time range
-      const startDate = format(subMonths(new Date(), selectedMonths), "yyyy-MM-dd");
-      const endDate = format(new Date(), "yyyy-MM-dd");
-      const activitiesData = await getDailyActivities(startDate, endDate);
+      // Load activities for the selected time range, ending today in the
+      // time zone of the settings
+      const { today } = await getTimezone();
+      const startDate = format(subMonths(parseISO(today), selectedMonths), "yyyy-MM-dd");
+      const activitiesData = await getDailyActivities(startDate, today);
       setActivities(activitiesData);
+      setToday(today);
 
       // Calculate streaks
       const streaks = await calculateStreaks();
@@ -223,7 +225,7 @@ export default function StreakPage() {
                 </Tabs>
               </HStack>
               <Box overflowX="auto">
-                <CalendarHeatmap activities={activities} monthsToShow={selectedMonths} />
+                <CalendarHeatmap activities={activities} monthsToShow={selectedMonths} today={today} />
               </Box>
             </VStack>
           </CardBody>
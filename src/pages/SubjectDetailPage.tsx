@@ -29,7 +29,6 @@ import {
   TabPanels,
   TabPanel,
   Badge,
-  Checkbox,
   Tag,
   AlertDialog,
   AlertDialogBody,
@@ -48,11 +47,10 @@ import {
   deleteEntry,
   deleteSubject,
   updateSubject,
-  completeRevision,
-  uncompleteRevision,
   getSettings,
   linkTagsToEntry,
   getEntryTags,
+  getTimezone,
   updateDailyActivity,
   calculateStreaks,
   checkAndRecordMilestone,
@@ -63,6 +61,8 @@ import { useRef } from "react";
 import SyllabusTab from "../components/SyllabusTab";
 import RichTextEditor from "../components/RichTextEditor";
 import CelebrationModal from "../components/CelebrationModal";
+import RevisionGradeButtons from "../components/RevisionGradeButtons";
+import { useDayChanged } from "../hooks/useDayChanged";
 import { getPreviewText } from "../utils/richTextUtils";
 
 export default function SubjectDetailPage() {
@@ -77,6 +77,8 @@ export default function SubjectDetailPage() {
   const [isEditing, setIsEditing] = useState(false);
   const [editingSubject, setEditingSubject] = useState(false);
   const [subjectName, setSubjectName] = useState("");
+  // In the user's time zone, from the backend
+  const [today, setToday] = useState<string | null>(null);
 
   // Form state
   const [studyDate, setStudyDate] = useState(format(new Date(), "yyyy-MM-dd"));
@@ -121,11 +123,18 @@ export default function SubjectDetailPage() {
     }
   }, [id]);
 
+  useDayChanged(() => {
+    if (id) {
+      loadSubjectData();
+    }
+  });
+
   async function loadSubjectData() {
     try {
       setLoading(true);
-      const subjectData = await getSubjectById(Number(id));
+      const [subjectData, timezone] = await Promise.all([getSubjectById(Number(id)), getTimezone()]);
       setSubject(subjectData);
+      setToday(timezone.today);
       setSubjectName(subjectData.name);
 
       const entriesData = await getEntriesBySubject(Number(id));
@@ -165,7 +174,7 @@ export default function SubjectDetailPage() {
   async function openNewEntryModal() {
     setSelectedEntry(null);
     setIsEditing(false);
-    setStudyDate(format(new Date(), "yyyy-MM-dd"));
+    setStudyDate((await getTimezone()).today);
     setTopics("");
     setTags("");
     setStudyNotes("");
@@ -231,8 +240,7 @@ export default function SubjectDetailPage() {
         entryId = newEntry.id;
 
         // Check for milestone celebration after creating new entry
-        const today = format(new Date(), "yyyy-MM-dd");
-        await updateDailyActivity(today);
+        await updateDailyActivity();
         const streaks = await calculateStreaks();
         const milestoneCheck = await checkAndRecordMilestone(streaks.currentStreak);
 
@@ -369,24 +377,6 @@ export default function SubjectDetailPage() {
     setIntervals(intervals.filter((i) => i !== interval));
   }
 
-  async function toggleRevision(revisionId: number, isCompleted: boolean) {
-    try {
-      if (isCompleted) {
-        await uncompleteRevision(revisionId);
-      } else {
-        await completeRevision(revisionId);
-      }
-      loadSubjectData();
-    } catch (error) {
-      toast({
-        title: "Error updating revision",
-        description: String(error),
-        status: "error",
-        duration: 5000,
-      });
-    }
-  }
-
   if (loading) {
     return (
       <Box
@@ -422,7 +412,7 @@ export default function SubjectDetailPage() {
         )
         .map((r) => ({ ...r, entry }))
     )
-    .filter((r) => new Date(r.due_date) <= new Date());
+    .filter((r) => today !== null && r.due_date <= today);
 
   return (
     <Box>
@@ -539,7 +529,8 @@ export default function SubjectDetailPage() {
                               color = "red";
                             else if (
                               revision.status === "pending" &&
-                              new Date(revision.due_date) <= new Date()
+                              today !== null &&
+                              revision.due_date <= today
                             )
                               color = "orange";
 
@@ -652,17 +643,12 @@ export default function SubjectDetailPage() {
                             </Box>
                           )}
                         </VStack>
-                        <Checkbox
-                          size="lg"
-                          colorScheme="green"
-                          isChecked={revision.status === "completed"}
-                          onChange={() =>
-                            toggleRevision(
-                              revision.id,
-                              revision.status === "completed"
-                            )
-                          }
-                        />
+                        <VStack align="end" spacing={1}>
+                          <Text fontSize="xs" color={tertiaryTextColor}>
+                            How well did you recall it?
+                          </Text>
+                          <RevisionGradeButtons revisionId={revision.id} onChange={() => loadSubjectData()} />
+                        </VStack>
                       </HStack>
                     </CardBody>
                   </Card>
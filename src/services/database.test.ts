@@ -26,19 +26,19 @@ describe('Database Service', () => {
         { id: 2, name: 'Science', color: '#4ECDC4', icon: '🔬', created_at: '2024-01-02' },
       ];
 
-      const mockEntryCount = [{ count: 5 }];
-      const mockNextRevision = [{ due_date: '2024-12-25' }];
-
-      // Mock the invoke function to return different values based on SQL
-      (invoke as any).mockImplementation((cmd: string, args: any) => {
-        if (args.sql.includes('SELECT * FROM subjects')) {
-          return Promise.resolve(mockSubjects);
-        }
-        if (args.sql.includes('COUNT(*) as count')) {
-          return Promise.resolve(mockEntryCount);
+      const mockSubjectsWithStats = mockSubjects.map((subject) => ({
+        ...subject,
+        entryCount: 5,
+        next_revision_due: '2024-12-25',
+      }));
+
+      // Mock the invoke function to return different values based on command
+      (invoke as any).mockImplementation((cmd: string) => {
+        if (cmd === 'get_subjects') {
+          return Promise.resolve(mockSubjectsWithStats);
         }
-        if (args.sql.includes('due_date FROM revisions')) {
-          return Promise.resolve(mockNextRevision);
+        if (cmd === 'get_timezone') {
+          return Promise.resolve({ timezone: null, effective: 'UTC', system: 'UTC', today: '2024-12-20' });
         }
         return Promise.resolve([]);
       });
@@ -49,7 +49,7 @@ describe('Database Service', () => {
       // Assert: Verify results
       expect(subjects).toHaveLength(2);
       expect(subjects[0]).toHaveProperty('entryCount', 5);
-      expect(subjects[0]).toHaveProperty('nextRevisionDays');
+      expect(subjects[0]).toHaveProperty('nextRevisionDays', 5);
       expect(invoke).toHaveBeenCalled();
     });
 
@@ -76,7 +76,6 @@ describe('Database Service', () => {
   describe('createSubject', () => {
     it('should create a new subject', async () => {
       // Arrange
-      const mockResult = { lastInsertId: 1, rowsAffected: 1 };
       const mockSubject = {
         id: 1,
         name: 'Physics',
@@ -86,13 +85,10 @@ describe('Database Service', () => {
       };
 
       (invoke as any).mockImplementation((cmd: string, args: any) => {
-        if (args.sql.includes('INSERT INTO subjects')) {
-          return Promise.resolve(mockResult);
+        if (cmd === 'create_subject' && args.name === 'Physics') {
+          return Promise.resolve(mockSubject);
         }
-        if (args.sql.includes('SELECT * FROM subjects WHERE id')) {
-          return Promise.resolve([mockSubject]);
-        }
-        return Promise.resolve([]);
+        return Promise.reject(new Error(`Unexpected command ${cmd}`));
       });
 
       // Act
@@ -1,17 +1,25 @@
-import { invoke } from "@tauri-apps/api/tauri";
 import {
   Subject,
   Entry,
   Revision,
-  RevisionInterval,
-  Setting,
-  ActivityLog,
   SubjectWithStats,
   EntryWithDetails,
   CalendarDay,
   ActivityLogWithDetails,
+  SyllabusItem,
+  PdfAttachment,
+  PomodoroState,
+  RevisionGrade,
+  ReviewLog,
+  RetentionStats,
+  StreakStats,
+  TimezoneInfo,
+  ReviewForecast,
+  CatchUpPlan,
+  CatchUpStrategy,
 } from "../types";
-import { format, addDays, parseISO } from "date-fns";
+import { differenceInCalendarDays, format, parseISO } from "date-fns";
+import { invokeCommand } from "./errors";
 
 // Small helper to ensure the Tauri IPC bridge is ready before invoking commands
 function isTauriApiAvailable(): boolean {
@@ -39,166 +47,68 @@ async function waitForTauriApi(timeoutMs = 10000): Promise<boolean> {
   });
 }
 
-// Database wrapper functions
-async function dbExecute(sql: string, params: any[] = []): Promise<{ lastInsertId: number; rowsAffected: number }> {
+// Typed backend commands. Raw SQL is not available to release builds.
+async function call<T>(command: string, args: Record<string, unknown> = {}): Promise<T> {
   const ready = await waitForTauriApi();
   if (!ready) throw new Error("Tauri IPC bridge not ready");
-  return await invoke("db_execute", { sql, params });
-}
-
-async function dbSelect<T>(sql: string, params: any[] = []): Promise<T[]> {
-  const ready = await waitForTauriApi();
-  if (!ready) throw new Error("Tauri IPC bridge not ready");
-  return await invoke("db_select", { sql, params });
+  return await invokeCommand<T>(command, args);
 }
 
 // Subject APIs
 export async function getSubjects(): Promise<SubjectWithStats[]> {
-  const subjects = await dbSelect<Subject>(
-    "SELECT * FROM subjects ORDER BY created_at DESC",
-    []
-  );
-
-  // Get entry counts and next revision for each subject
-  const subjectsWithStats = await Promise.all(
-    subjects.map(async (subject) => {
-      const entryCount = await dbSelect<{ count: number }>(
-        "SELECT COUNT(*) as count FROM entries WHERE subject_id = ?",
-        [subject.id]
-      );
-
-      const nextRevision = await dbSelect<{ due_date: string }>(
-        `SELECT due_date FROM revisions r
-         JOIN entries e ON r.entry_id = e.id
-         WHERE e.subject_id = ? AND r.status IN ('pending', 'overdue')
-         ORDER BY r.due_date ASC
-         LIMIT 1`,
-        [subject.id]
-      );
-
-      let nextRevisionDays: number | undefined;
-      if (nextRevision.length > 0) {
-        const today = new Date();
-        const dueDate = parseISO(nextRevision[0].due_date);
-        nextRevisionDays = Math.ceil(
-          (dueDate.getTime() - today.getTime()) / (1000 * 60 * 60 * 24)
-        );
-      }
+  const subjects = await call<
+    (Subject & { entryCount: number; next_revision_due: string | null })[]
+  >("get_subjects");
+  // Counted from today in the user's time zone, not the browser's
+  const today = subjects.some((s) => s.next_revision_due)
+    ? parseISO((await getTimezone()).today)
+    : null;
+
+  return subjects.map(({ next_revision_due, ...subject }) => {
+    let nextRevisionDays: number | undefined;
+    if (next_revision_due && today) {
+      nextRevisionDays = differenceInCalendarDays(parseISO(next_revision_due), today);
+    }
 
-      return {
-        ...subject,
-        entryCount: entryCount[0].count,
-        nextRevisionDays,
-      };
-    })
-  );
+    return {
+      ...subject,
+      nextRevisionDays,
+    };
+  });
+}
 
-  return subjectsWithStats;
+export async function getSubjectNames(): Promise<{ id: number; name: string }[]> {
+  return await call("get_subject_names");
 }
 
 export async function createSubject(name: string): Promise<Subject> {
-  const result = await dbExecute(
-    "INSERT INTO subjects (name) VALUES (?)",
-    [name]
-  );
-
-  const subjects = await dbSelect<Subject>(
-    "SELECT * FROM subjects WHERE id = ?",
-    [result.lastInsertId]
-  );
-
-  return subjects[0];
+  return await call("create_subject", { name });
 }
 
 export async function updateSubject(
   id: number,
   name: string
 ): Promise<Subject> {
-  await dbExecute(
-    "UPDATE subjects SET name = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
-    [name, id]
-  );
-
-  const subjects = await dbSelect<Subject>(
-    "SELECT * FROM subjects WHERE id = ?",
-    [id]
-  );
-
-  return subjects[0];
+  return await call("update_subject", { id, name });
 }
 
 export async function deleteSubject(id: number): Promise<void> {
-  await dbExecute("DELETE FROM subjects WHERE id = ?", [id]);
+  await call("delete_subject", { id });
 }
 
 export async function getSubjectById(id: number): Promise<Subject> {
-  const subjects = await dbSelect<Subject>(
-    "SELECT * FROM subjects WHERE id = ?",
-    [id]
-  );
-  return subjects[0];
+  return await call("get_subject", { id });
 }
 
 // Entry APIs
 export async function getEntriesBySubject(
   subjectId: number
 ): Promise<EntryWithDetails[]> {
-  const entries = await dbSelect<Entry>(
-    "SELECT * FROM entries WHERE subject_id = ? ORDER BY study_date DESC",
-    [subjectId]
-  );
-
-  const subject = await getSubjectById(subjectId);
-
-  const entriesWithDetails = await Promise.all(
-    entries.map(async (entry) => {
-      const revisions = await dbSelect<Revision>(
-        "SELECT * FROM revisions WHERE entry_id = ? ORDER BY due_date ASC",
-        [entry.id]
-      );
-
-      const intervals = await dbSelect<RevisionInterval>(
-        "SELECT * FROM revision_intervals WHERE entry_id = ? ORDER BY interval_days ASC",
-        [entry.id]
-      );
-
-      return {
-        ...entry,
-        subject_name: subject.name,
-        revisions,
-        intervals,
-      };
-    })
-  );
-
-  return entriesWithDetails;
+  return await call("get_entries_by_subject", { subjectId });
 }
 
 export async function getEntryById(id: number): Promise<EntryWithDetails> {
-  const entries = await dbSelect<Entry>(
-    "SELECT * FROM entries WHERE id = ?",
-    [id]
-  );
-
-  const entry = entries[0];
-  const subject = await getSubjectById(entry.subject_id);
-
-  const revisions = await dbSelect<Revision>(
-    "SELECT * FROM revisions WHERE entry_id = ? ORDER BY due_date ASC",
-    [entry.id]
-  );
-
-  const intervals = await dbSelect<RevisionInterval>(
-    "SELECT * FROM revision_intervals WHERE entry_id = ? ORDER BY interval_days ASC",
-    [entry.id]
-  );
-
-  return {
-    ...entry,
-    subject_name: subject.name,
-    revisions,
-    intervals,
-  };
+  return await call("get_entry", { id });
 }
 
 export async function createEntry(
@@ -208,39 +118,16 @@ export async function createEntry(
   intervals: number[],
   topics?: string
 ): Promise<EntryWithDetails> {
-  // Insert entry
-  const entryResult = await dbExecute(
-    "INSERT INTO entries (subject_id, study_date, study_notes, topics) VALUES (?, ?, ?, ?)",
-    [subjectId, studyDate, studyNotes, topics || null]
-  );
-
-  const entryId = entryResult.lastInsertId;
-
-  // Insert intervals
-  for (const interval of intervals) {
-    await dbExecute(
-      "INSERT INTO revision_intervals (entry_id, interval_days) VALUES (?, ?)",
-      [entryId, interval]
-    );
-
-    // Calculate due date and create revision
-    const dueDate = format(
-      addDays(parseISO(studyDate), interval),
-      "yyyy-MM-dd"
-    );
-    await dbExecute(
-      "INSERT INTO revisions (entry_id, interval_days, due_date, status) VALUES (?, ?, ?, 'pending')",
-      [entryId, interval, dueDate]
-    );
-  }
-
-  // Insert activity log
-  await dbExecute(
-    "INSERT INTO activity_log (entry_id, activity_type, activity_date) VALUES (?, 'study', ?)",
-    [entryId, studyDate]
-  );
-
-  return await getEntryById(entryId);
+  // Entry, intervals, revisions and activity log are written in one transaction
+  return await call("create_entry", {
+    entry: {
+      subject_id: subjectId,
+      study_date: studyDate,
+      study_notes: studyNotes,
+      intervals,
+      topics: topics || null,
+    },
+  });
 }
 
 export async function updateEntry(
@@ -250,162 +137,118 @@ export async function updateEntry(
   intervals?: number[],
   topics?: string
 ): Promise<EntryWithDetails> {
-  await dbExecute(
-    "UPDATE entries SET study_notes = ?, morning_recall_notes = ?, topics = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
-    [studyNotes, morningRecallNotes, topics || null, id]
-  );
-
-  // If intervals are provided, update them
-  if (intervals) {
-    const entries = await dbSelect<Entry>(
-      "SELECT * FROM entries WHERE id = ?",
-      [id]
-    );
-    const studyDate = entries[0].study_date;
-
-    // Delete existing intervals and revisions that are not completed
-    await dbExecute("DELETE FROM revision_intervals WHERE entry_id = ?", [
-      id,
-    ]);
-    await dbExecute(
-      "DELETE FROM revisions WHERE entry_id = ? AND status = 'pending'",
-      [id]
-    );
-
-    // Insert new intervals
-    for (const interval of intervals) {
-      await dbExecute(
-        "INSERT INTO revision_intervals (entry_id, interval_days) VALUES (?, ?)",
-        [id, interval]
-      );
-
-      // Calculate due date and create revision
-      const dueDate = format(
-        addDays(parseISO(studyDate), interval),
-        "yyyy-MM-dd"
-      );
-      await dbExecute(
-        "INSERT INTO revisions (entry_id, interval_days, due_date, status) VALUES (?, ?, ?, 'pending')",
-        [id, interval, dueDate]
-      );
-    }
-  }
-
-  return await getEntryById(id);
+  // Providing intervals replaces them and regenerates pending revisions
+  return await call("update_entry", {
+    id,
+    update: {
+      study_notes: studyNotes,
+      morning_recall_notes: morningRecallNotes,
+      intervals: intervals ?? null,
+      topics: topics || null,
+    },
+  });
 }
 
 export async function deleteEntry(id: number): Promise<void> {
-  await dbExecute("DELETE FROM entries WHERE id = ?", [id]);
+  await call("delete_entry", { id });
 }
 
 // Revision APIs
 export async function getRevisionsDueToday(): Promise<
   (Revision & { entry: Entry; subject: Subject })[]
 > {
-  const today = format(new Date(), "yyyy-MM-dd");
-
-  const revisions = await dbSelect<Revision>(
-    "SELECT * FROM revisions WHERE due_date <= ? AND status IN ('pending', 'overdue') ORDER BY due_date ASC",
-    [today]
-  );
-
-  const revisionsWithDetails = await Promise.all(
-    revisions.map(async (revision) => {
-      const entry = await getEntryById(revision.entry_id);
-      const subject = await getSubjectById(entry.subject_id);
-
-      return {
-        ...revision,
-        entry: entry,
-        subject: subject,
-      };
-    })
-  );
-
-  return revisionsWithDetails;
+  return await call("get_revisions_due_today");
 }
 
 export async function getRevisionsByDate(
   date: string
 ): Promise<(Revision & { entry: Entry; subject: Subject })[]> {
-  const revisions = await dbSelect<Revision>(
-    "SELECT * FROM revisions WHERE due_date = ? ORDER BY status ASC",
-    [date]
-  );
+  return await call("get_revisions_by_date", { date });
+}
 
-  const revisionsWithDetails = await Promise.all(
-    revisions.map(async (revision) => {
-      const entry = await getEntryById(revision.entry_id);
-      const subject = await getSubjectById(entry.subject_id);
-
-      return {
-        ...revision,
-        entry: entry,
-        subject: subject,
-      };
-    })
-  );
+export async function completeRevision(id: number): Promise<void> {
+  await call("complete_revision", { id });
+}
 
-  return revisionsWithDetails;
+// quality: 0 (blackout) to 5 (perfect recall). The backend schedules the
+// entry's next revision with SM-2.
+export async function gradeRevision(id: number, quality: number, durationSeconds?: number): Promise<RevisionGrade> {
+  return await call("grade_revision", { id, quality, durationSeconds: durationSeconds ?? null });
 }
 
-export async function completeRevision(id: number): Promise<void> {
-  await dbExecute(
-    "UPDATE revisions SET status = 'completed', completed_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
-    [id]
-  );
+// Takes back the latest grade of an entry and returns the reopened revision
+export async function undoReview(id: number): Promise<Revision> {
+  return await call("undo_review", { id });
+}
 
-  // Get the revision to log activity
-  const revisions = await dbSelect<Revision>(
-    "SELECT * FROM revisions WHERE id = ?",
-    [id]
-  );
-  const revision = revisions[0];
+export interface ReviewLogFilters {
+  entryId?: number;
+  subjectId?: number;
+  startDate?: string;
+  endDate?: string;
+  limit?: number;
+}
 
-  await dbExecute(
-    "INSERT INTO activity_log (entry_id, activity_type, activity_date, details) VALUES (?, 'revision_completed', DATE('now'), ?)",
-    [revision.entry_id, `Day ${revision.interval_days} revision`]
-  );
+export async function getReviewLog(filters?: ReviewLogFilters): Promise<ReviewLog[]> {
+  return await call("get_review_log", { filters: filters ?? null });
 }
 
-export async function uncompleteRevision(id: number): Promise<void> {
-  const revisions = await dbSelect<Revision>(
-    "SELECT * FROM revisions WHERE id = ?",
-    [id]
-  );
-  const revision = revisions[0];
+export async function getRetentionStats(filters?: ReviewLogFilters): Promise<RetentionStats> {
+  return await call("get_retention_stats", { filters: filters ?? null });
+}
 
-  const today = format(new Date(), "yyyy-MM-dd");
-  const status = revision.due_date < today ? "overdue" : "pending";
+// Reviews expected each day for the next `days` days (up to 365)
+export async function forecastReviews(days: number, subjectId?: number) {
+  return await call<ReviewForecast>("forecast_reviews", { days, subjectId: subjectId ?? null });
+}
 
-  await dbExecute(
-    "UPDATE revisions SET status = ?, completed_at = NULL, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
-    [status, id]
-  );
+// Moves every overdue revision to a day from today on, at most maxPerDay
+// open revisions a day; a dry run only returns the plan
+export async function planCatchUp(maxPerDay: number, strategy: CatchUpStrategy, dryRun: boolean) {
+  return await call<CatchUpPlan>("plan_catch_up", { maxPerDay, strategy, dryRun });
+}
+
+// FSRS parameters fitted to the revision history by optimizeFsrsParameters
+export interface FsrsOptimization {
+  parameters: number[];
+  reviews: number;
+  lossBefore: number;
+  lossAfter: number;
+}
+
+export async function optimizeFsrsParameters(): Promise<FsrsOptimization> {
+  return await call("optimize_fsrs_parameters");
+}
+
+export async function resetFsrsParameters(): Promise<void> {
+  await call("reset_fsrs_parameters");
+}
+
+// Spreading new revisions over lighter days, see load_balance.rs
+export interface LoadBalancing {
+  enabled: boolean;
+  // null for no limit
+  maxDailyReviews: number | null;
+  tolerancePercent: number;
+}
+
+export async function getLoadBalancing(): Promise<LoadBalancing> {
+  return await call("get_load_balancing");
+}
+
+export async function setLoadBalancing(settings: LoadBalancing): Promise<LoadBalancing> {
+  return await call("set_load_balancing", { settings });
+}
+
+export async function uncompleteRevision(id: number): Promise<void> {
+  await call("uncomplete_revision", { id });
 }
 
 export async function rescheduleRevision(
   id: number,
   newDate: string
 ): Promise<void> {
-  // Mark old revision as rescheduled
-  await dbExecute(
-    "UPDATE revisions SET status = 'rescheduled', updated_at = CURRENT_TIMESTAMP WHERE id = ?",
-    [id]
-  );
-
-  // Get the revision details
-  const revisions = await dbSelect<Revision>(
-    "SELECT * FROM revisions WHERE id = ?",
-    [id]
-  );
-  const revision = revisions[0];
-
-  // Create new revision with new date
-  await dbExecute(
-    "INSERT INTO revisions (entry_id, interval_days, due_date, status) VALUES (?, ?, ?, 'pending')",
-    [revision.entry_id, revision.interval_days, newDate]
-  );
+  await call("reschedule_revision", { id, newDate });
 }
 
 // Calendar APIs
@@ -416,10 +259,11 @@ export async function getCalendarData(
   const startDate = format(new Date(year, month - 1, 1), "yyyy-MM-dd");
   const endDate = format(new Date(year, month, 0), "yyyy-MM-dd");
 
-  const revisions = await dbSelect<Revision>(
-    "SELECT * FROM revisions WHERE due_date >= ? AND due_date <= ? ORDER BY due_date ASC",
-    [startDate, endDate]
-  );
+  const revisions = await call<Revision[]>("get_revisions_between", {
+    startDate,
+    endDate,
+  });
+  const { today } = await getTimezone();
 
   // Group revisions by date
   const revisionsByDate: Record<string, Revision[]> = {};
@@ -439,7 +283,6 @@ export async function getCalendarData(
     const dayRevisions = revisionsByDate[date] || [];
 
     let status: CalendarDay["status"] = "none";
-    const today = format(new Date(), "yyyy-MM-dd");
 
     if (dayRevisions.length > 0) {
       if (dayRevisions.some((r) => r.status === "overdue")) {
@@ -473,90 +316,64 @@ export async function getActivityLog(filters?: {
   endDate?: string;
   activityType?: string;
 }): Promise<ActivityLogWithDetails[]> {
-  let query = "SELECT * FROM activity_log WHERE 1=1";
-  const params: any[] = [];
-
-  if (filters?.activityType) {
-    query += " AND activity_type = ?";
-    params.push(filters.activityType);
-  }
-
-  if (filters?.startDate) {
-    query += " AND activity_date >= ?";
-    params.push(filters.startDate);
-  }
+  return await call("get_activity_log", { filters: filters ?? null });
+}
 
-  if (filters?.endDate) {
-    query += " AND activity_date <= ?";
-    params.push(filters.endDate);
+// Search APIs
+export interface SearchHit {
+  entry_id: number;
+  subject_id: number;
+  subject_name: string;
+  study_date: string;
+  topics: string | null;
+  // Matched terms are wrapped in <mark></mark>; split on them rather than
+  // rendering the snippet as HTML
+  snippet: string;
+  score: number;
+}
+
+export async function searchEntries(
+  query: string,
+  filters?: {
+    subjectId?: number;
+    tag?: string;
+    startDate?: string;
+    endDate?: string;
+    limit?: number;
   }
-
-  query += " ORDER BY activity_date DESC, created_at DESC";
-
-  const activities = await dbSelect<ActivityLog>(query, params);
-
-  const activitiesWithDetails = await Promise.all(
-    activities.map(async (activity) => {
-      const entry = await getEntryById(activity.entry_id);
-      const subject = await getSubjectById(entry.subject_id);
-
-      // Filter by subject if provided
-      if (filters?.subjectId && subject.id !== filters.subjectId) {
-        return null;
-      }
-
-      return {
-        ...activity,
-        entry,
-        subject,
-      };
-    })
-  );
-
-  return activitiesWithDetails.filter(
-    (a) => a !== null
-  ) as ActivityLogWithDetails[];
+): Promise<SearchHit[]> {
+  return await call("search_entries", { query, filters: filters ?? null });
 }
 
 // Settings APIs
 export async function getSettings(): Promise<Record<string, string>> {
-  const settings = await dbSelect<Setting>("SELECT * FROM settings", []);
-
-  return settings.reduce(
-    (acc, setting) => {
-      acc[setting.key] = setting.value;
-      return acc;
-    },
-    {} as Record<string, string>
-  );
+  return await call("get_settings");
 }
 
 export async function updateSetting(key: string, value: string): Promise<void> {
-  await dbExecute(
-    "INSERT OR REPLACE INTO settings (key, value, updated_at) VALUES (?, ?, CURRENT_TIMESTAMP)",
-    [key, value]
-  );
+  await call("update_setting", { key, value });
 }
 
-// Update overdue revisions
-export async function updateOverdueRevisions(): Promise<void> {
-  const today = format(new Date(), "yyyy-MM-dd");
+// Dates and day boundaries follow this zone rather than the webview's clock
+export async function getTimezone() {
+  return await call<TimezoneInfo>("get_timezone");
+}
 
-  await dbExecute(
-    "UPDATE revisions SET status = 'overdue' WHERE due_date < ? AND status = 'pending'",
-    [today]
-  );
+// null follows the system time zone
+export async function setTimezone(timezone: string | null) {
+  return await call<TimezoneInfo>("set_timezone", { timezone });
+}
+
+// The backend already does this when the database opens and at midnight
+export async function updateOverdueRevisions(): Promise<void> {
+  await call("update_overdue_revisions");
 }
 
 // Syllabus Management APIs
 export async function getSyllabusItems(subjectId: number): Promise<any[]> {
-  const items = await dbSelect<any>(
-    `SELECT s.*,
-      (SELECT COUNT(*) FROM entry_syllabus_links WHERE syllabus_item_id = s.id) as entry_count
-     FROM syllabus_items s
-     WHERE s.subject_id = ?
-     ORDER BY s.sort_order, s.created_at`,
-    [subjectId]
+  const items = await call<(SyllabusItem & { entry_count: number })[]>(
+    "get_syllabus_items",
+    { subjectId }
   );
 
   // Build tree structure
@@ -620,20 +437,8 @@ export async function createSyllabusItem(data: {
   due_date: string | null;
   sort_order?: number;
 }): Promise<{ lastInsertId: number; rowsAffected: number }> {
-  return await dbExecute(
-    `INSERT INTO syllabus_items
-     (subject_id, parent_id, title, description, estimated_hours, due_date, sort_order)
-     VALUES (?, ?, ?, ?, ?, ?, ?)`,
-    [
-      data.subject_id,
-      data.parent_id,
-      data.title,
-      data.description,
-      data.estimated_hours,
-      data.due_date,
-      data.sort_order || 0,
-    ]
-  );
+  const id = await call<number>("create_syllabus_item", { item: data });
+  return { lastInsertId: id, rowsAffected: 1 };
 }
 
 export async function updateSyllabusItem(
@@ -646,80 +451,29 @@ export async function updateSyllabusItem(
     is_completed?: number;
   }
 ): Promise<void> {
-  const updates: string[] = [];
-  const params: any[] = [];
-
-  if (data.title !== undefined) {
-    updates.push("title = ?");
-    params.push(data.title);
-  }
-  if (data.description !== undefined) {
-    updates.push("description = ?");
-    params.push(data.description);
-  }
-  if (data.estimated_hours !== undefined) {
-    updates.push("estimated_hours = ?");
-    params.push(data.estimated_hours);
-  }
-  if (data.due_date !== undefined) {
-    updates.push("due_date = ?");
-    params.push(data.due_date);
-  }
-  if (data.is_completed !== undefined) {
-    updates.push("is_completed = ?");
-    params.push(data.is_completed);
-  }
-
-  if (updates.length > 0) {
-    updates.push("updated_at = CURRENT_TIMESTAMP");
-    params.push(id);
-
-    await dbExecute(
-      `UPDATE syllabus_items SET ${updates.join(", ")} WHERE id = ?`,
-      params
-    );
-  }
+  // Omitted fields are left unchanged
+  await call("update_syllabus_item", { id, update: data });
 }
 
 export async function deleteSyllabusItem(id: number): Promise<void> {
-  await dbExecute("DELETE FROM syllabus_items WHERE id = ?", [id]);
+  await call("delete_syllabus_item", { id });
 }
 
 export async function toggleSyllabusCompletion(id: number, isCompleted: number): Promise<void> {
-  await dbExecute(
-    "UPDATE syllabus_items SET is_completed = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
-    [isCompleted, id]
-  );
+  await updateSyllabusItem(id, { is_completed: isCompleted });
 }
 
 export async function linkEntryToSyllabus(entryId: number, syllabusItemIds: number[]): Promise<void> {
-  // First, remove existing links
-  await dbExecute("DELETE FROM entry_syllabus_links WHERE entry_id = ?", [entryId]);
-
-  // Then add new links
-  for (const syllabusItemId of syllabusItemIds) {
-    await dbExecute(
-      "INSERT INTO entry_syllabus_links (entry_id, syllabus_item_id) VALUES (?, ?)",
-      [entryId, syllabusItemId]
-    );
-  }
+  await call("link_entry_to_syllabus", { entryId, syllabusItemIds });
 }
 
-export async function getEntrySyllabusLinks(entryId: number): Promise<any[]> {
-  return await dbSelect<any>(
-    `SELECT s.* FROM syllabus_items s
-     JOIN entry_syllabus_links l ON s.id = l.syllabus_item_id
-     WHERE l.entry_id = ?`,
-    [entryId]
-  );
+export async function getEntrySyllabusLinks(entryId: number): Promise<SyllabusItem[]> {
+  return await call("get_entry_syllabus_links", { entryId });
 }
 
 // PDF Management APIs
-export async function getPdfAttachments(entryId: number): Promise<any[]> {
-  return await dbSelect<any>(
-    "SELECT * FROM pdf_attachments WHERE entry_id = ? ORDER BY created_at DESC",
-    [entryId]
-  );
+export async function getPdfAttachments(entryId: number): Promise<PdfAttachment[]> {
+  return await call("get_pdf_attachments", { entryId });
 }
 
 export async function createPdfAttachment(data: {
@@ -729,30 +483,20 @@ export async function createPdfAttachment(data: {
   file_size: number;
   page_count: number | null;
 }): Promise<{ lastInsertId: number; rowsAffected: number }> {
-  return await dbExecute(
-    `INSERT INTO pdf_attachments (entry_id, file_name, file_path, file_size, page_count)
-     VALUES (?, ?, ?, ?, ?)`,
-    [data.entry_id, data.file_name, data.file_path, data.file_size, data.page_count]
-  );
+  const id = await call<number>("create_pdf_attachment", { pdf: data });
+  return { lastInsertId: id, rowsAffected: 1 };
 }
 
 export async function updatePdfLastViewedPage(pdfId: number, pageNumber: number): Promise<void> {
-  await dbExecute(
-    "UPDATE pdf_attachments SET last_viewed_page = ? WHERE id = ?",
-    [pageNumber, pdfId]
-  );
+  await call("update_pdf_last_viewed_page", { id: pdfId, pageNumber });
 }
 
 export async function deletePdfAttachment(pdfId: number): Promise<void> {
-  await dbExecute("DELETE FROM pdf_attachments WHERE id = ?", [pdfId]);
+  await call("delete_pdf_attachment", { id: pdfId });
 }
 
-export async function getPdfById(pdfId: number): Promise<any | null> {
-  const result = await dbSelect<any>(
-    "SELECT * FROM pdf_attachments WHERE id = ?",
-    [pdfId]
-  );
-  return result.length > 0 ? result[0] : null;
+export async function getPdfById(pdfId: number): Promise<PdfAttachment | null> {
+  return await call("get_pdf_attachment", { id: pdfId });
 }
 
 // Study Session Analytics APIs
@@ -762,41 +506,45 @@ export async function recordPomodoroSession(data: {
   subject_id: number | null;
   syllabus_item_id: number | null;
 }): Promise<void> {
-  await dbExecute(
-    `INSERT INTO pomodoro_sessions (session_type, duration_minutes, subject_id, syllabus_item_id)
-     VALUES (?, ?, ?, ?)`,
-    [data.session_type, data.duration_minutes, data.subject_id, data.syllabus_item_id]
-  );
+  await call("record_pomodoro_session", { session: data });
+}
+
+export async function getPomodoroSessions(): Promise<any[]> {
+  return await call("get_pomodoro_sessions");
+}
+
+export async function getPomodoroState(): Promise<PomodoroState> {
+  return await call("get_pomodoro_state");
+}
+
+// Only the provided fields are updated; returns the resulting state
+export async function updatePomodoroState(
+  update: Partial<Omit<PomodoroState, "id" | "updated_at">>
+): Promise<PomodoroState> {
+  return await call("update_pomodoro_state", { update });
+}
+
+export async function getPomodoroStats(
+  range: "today" | "week" | "month" | "all"
+): Promise<{
+  total_sessions: number;
+  total_minutes: number;
+  work_sessions: number;
+  break_sessions: number;
+}> {
+  return await call("get_pomodoro_stats", { range });
+}
+
+export async function getSubjectPomodoroTotals(): Promise<any[]> {
+  return await call("get_subject_pomodoro_totals");
 }
 
 export async function getStudyTimeBySubject(subjectId: number, days: number = 7): Promise<any> {
-  const result = await dbSelect<any>(
-    `SELECT
-      COUNT(*) as session_count,
-      SUM(duration_minutes) as total_minutes
-     FROM pomodoro_sessions
-     WHERE subject_id = ?
-     AND session_type = 'work'
-     AND completed_at >= datetime('now', '-${days} days')`,
-    [subjectId]
-  );
-  return result[0];
+  return await call("get_study_time_by_subject", { subjectId, days });
 }
 
 export async function getAllSubjectsStudyTime(days: number = 7): Promise<any[]> {
-  return await dbSelect<any>(
-    `SELECT
-      s.id,
-      s.name,
-      COUNT(ps.id) as session_count,
-      COALESCE(SUM(ps.duration_minutes), 0) as total_minutes
-     FROM subjects s
-     LEFT JOIN pomodoro_sessions ps ON s.id = ps.subject_id AND ps.session_type = 'work'
-       AND ps.completed_at >= datetime('now', '-${days} days')
-     GROUP BY s.id, s.name
-     ORDER BY total_minutes DESC`,
-    []
-  );
+  return await call("get_all_subjects_study_time", { days });
 }
 
 export async function getTodayPomodoroSummary(): Promise<{
@@ -804,267 +552,198 @@ export async function getTodayPomodoroSummary(): Promise<{
   totalMinutes: number;
   subjectsStudied: string[];
 }> {
-  const today = format(new Date(), "yyyy-MM-dd");
-
-  // Get today's pomodoro count and minutes
-  const result = await dbSelect<any>(
-    `SELECT
-      COUNT(*) as count,
-      SUM(duration_minutes) as total_minutes
-     FROM pomodoro_sessions
-     WHERE session_type = 'work'
-       AND DATE(completed_at) = ?`,
-    [today]
-  );
-
-  // Get subjects studied today
-  const subjects = await dbSelect<any>(
-    `SELECT DISTINCT s.name
-     FROM pomodoro_sessions ps
-     JOIN subjects s ON ps.subject_id = s.id
-     WHERE ps.session_type = 'work'
-       AND DATE(ps.completed_at) = ?
-     ORDER BY ps.completed_at DESC`,
-    [today]
-  );
-
-  return {
-    totalPomodoros: result[0]?.count || 0,
-    totalMinutes: result[0]?.total_minutes || 0,
-    subjectsStudied: subjects.map((s: any) => s.name),
-  };
+  return await call("get_today_pomodoro_summary");
 }
 
 // ============= TAGS =============
 
 export async function getAllTags() {
-  return await dbSelect<any>("SELECT * FROM tags ORDER BY usage_count DESC, name ASC", []);
+  return await call<any[]>("get_all_tags");
 }
 
 export async function getOrCreateTag(tagName: string): Promise<number> {
-  const normalizedName = tagName.trim().toLowerCase();
-  if (!normalizedName) throw new Error("Tag name cannot be empty");
+  return await call("get_or_create_tag", { name: tagName });
+}
 
-  // Check if tag exists
-  const existing = await dbSelect<any>("SELECT id FROM tags WHERE name = ?", [normalizedName]);
+export async function linkTagsToEntry(entryId: number, tagNames: string[]) {
+  await call("link_tags_to_entry", { entryId, tagNames });
+}
 
-  if (existing.length > 0) {
-    // Increment usage count
-    await dbExecute("UPDATE tags SET usage_count = usage_count + 1 WHERE id = ?", [existing[0].id]);
-    return existing[0].id;
-  }
+export async function getEntryTags(entryId: number) {
+  return await call<any[]>("get_entry_tags", { entryId });
+}
 
-  // Create new tag
-  const result = await dbExecute("INSERT INTO tags (name) VALUES (?)", [normalizedName]);
-  return result.lastInsertId;
+// Daily Activity Tracking for Streaks
+// Recomputes today's activity when no date is given
+export async function updateDailyActivity(date?: string) {
+  await call("update_daily_activity", { date: date ?? null });
 }
 
-export async function linkTagsToEntry(entryId: number, tagNames: string[]) {
-  // Remove existing tags for this entry
-  await dbExecute("DELETE FROM entry_tags WHERE entry_id = ?", [entryId]);
-
-  // Add new tags
-  for (const tagName of tagNames) {
-    if (tagName.trim()) {
-      const tagId = await getOrCreateTag(tagName);
-      await dbExecute(
-        "INSERT OR IGNORE INTO entry_tags (entry_id, tag_id) VALUES (?, ?)",
-        [entryId, tagId]
-      );
-    }
+export async function getDailyActivities(startDate: string, endDate: string) {
+  return await call<any[]>("get_daily_activities", { startDate, endDate });
+}
+
+// Streaks are counted by the backend so "today" follows the time zone setting
+export async function calculateStreaks() {
+  return await call<StreakStats>("get_streak_stats");
+}
+
+// Milestone Celebration Tracking
+export async function checkAndRecordMilestone(currentStreak: number) {
+  // Records the milestone the first time it is reached; false when the
+  // streak is not a milestone or its celebration was already shown
+  const shouldCelebrate = await call<boolean>("record_milestone", { currentStreak });
+  if (!shouldCelebrate) {
+    return null;
   }
+
+  // Return milestone data for celebration
+  return {
+    milestone: currentStreak,
+    shouldCelebrate: true,
+  };
 }
 
-export async function getEntryTags(entryId: number) {
-  return await dbSelect<any>(
-    `SELECT t.* FROM tags t
-     INNER JOIN entry_tags et ON t.id = et.tag_id
-     WHERE et.entry_id = ?
-     ORDER BY t.name`,
-    [entryId]
-  );
+export async function markMilestoneShown(milestoneDays: number) {
+  await call("mark_milestone_shown", { milestoneDays });
 }
 
-// Daily Activity Tracking for Streaks
-export async function updateDailyActivity(date: string) {
-  // Calculate pomodoro minutes and count for the day
-  const pomodoroData = await dbSelect<any>(
-    `SELECT
-      COUNT(*) as pomodoro_count,
-      SUM(duration_minutes) as study_minutes
-     FROM pomodoro_sessions
-     WHERE session_type = 'work'
-       AND DATE(completed_at) = ?`,
-    [date]
-  );
+export async function getMilestoneAchievements() {
+  return await call<any[]>("get_milestone_achievements");
+}
 
-  // Count entries created on this day
-  const entryData = await dbSelect<any>(
-    `SELECT COUNT(*) as entry_count
-     FROM entries
-     WHERE DATE(study_date) = ?`,
-    [date]
-  );
+// Database encryption
+export interface DatabaseStatus {
+  encrypted: boolean;
+  unlocked: boolean;
+  // Set when the database could not be opened
+  problem: string | null;
+}
 
-  // Get unique subjects studied
-  const subjectsData = await dbSelect<any>(
-    `SELECT DISTINCT s.name
-     FROM subjects s
-     INNER JOIN entries e ON s.id = e.subject_id
-     WHERE DATE(e.study_date) = ?`,
-    [date]
-  );
+export async function getDatabaseStatus(): Promise<DatabaseStatus> {
+  return await call("database_status");
+}
 
-  const studyMinutes = pomodoroData[0]?.study_minutes || 0;
-  const pomodoroCount = pomodoroData[0]?.pomodoro_count || 0;
-  const entryCount = entryData[0]?.entry_count || 0;
-  const subjectsStudied = subjectsData.map((s: any) => s.name).join(',');
-
-  // Insert or update daily activity
-  await dbExecute(
-    `INSERT INTO daily_activity (activity_date, study_minutes, pomodoro_count, entry_count, subjects_studied, updated_at)
-     VALUES (?, ?, ?, ?, ?, CURRENT_TIMESTAMP)
-     ON CONFLICT(activity_date) DO UPDATE SET
-       study_minutes = ?,
-       pomodoro_count = ?,
-       entry_count = ?,
-       subjects_studied = ?,
-       updated_at = CURRENT_TIMESTAMP`,
-    [date, studyMinutes, pomodoroCount, entryCount, subjectsStudied, studyMinutes, pomodoroCount, entryCount, subjectsStudied]
-  );
+export async function unlockDatabase(passphrase: string): Promise<void> {
+  await call("unlock_database", { passphrase });
 }
 
-export async function getDailyActivities(startDate: string, endDate: string) {
-  return await dbSelect<any>(
-    `SELECT * FROM daily_activity
-     WHERE activity_date BETWEEN ? AND ?
-     ORDER BY activity_date DESC`,
-    [startDate, endDate]
-  );
+export async function enableEncryption(passphrase: string): Promise<void> {
+  await call("enable_encryption", { passphrase });
 }
 
-export async function calculateStreaks() {
-  // Get all activity dates in descending order
-  const activities = await dbSelect<any>(
-    `SELECT activity_date FROM daily_activity
-     WHERE study_minutes > 0 OR entry_count > 0
-     ORDER BY activity_date DESC`,
-    []
-  );
+export async function changePassphrase(currentPassphrase: string, newPassphrase: string): Promise<void> {
+  await call("change_passphrase", { currentPassphrase, newPassphrase });
+}
 
-  if (activities.length === 0) {
-    return {
-      currentStreak: 0,
-      longestStreak: 0,
-      totalActiveDays: 0,
-    };
-  }
+export async function removeEncryption(currentPassphrase: string): Promise<void> {
+  await call("remove_encryption", { currentPassphrase });
+}
 
-  const today = format(new Date(), 'yyyy-MM-dd');
-  let currentStreak = 0;
-  let longestStreak = 0;
-  let tempStreak = 1;
-  let previousDate: Date | null = null;
+// Backup and restore
+export async function backupDatabase(destPath: string): Promise<void> {
+  await call("backup_database", { destPath });
+}
 
-  // Check if today or yesterday has activity for current streak
-  const latestActivityDate = activities[0].activity_date;
-  const yesterday = format(addDays(new Date(), -1), 'yyyy-MM-dd');
+export async function restoreDatabase(srcPath: string, passphrase?: string): Promise<void> {
+  await call("restore_database", { srcPath, passphrase: passphrase || null });
+}
 
-  if (latestActivityDate === today || latestActivityDate === yesterday) {
-    currentStreak = 1;
+export interface BackupRun {
+  id: number;
+  kind: "daily" | "weekly" | "manual";
+  status: "success" | "failed";
+  file_path: string | null;
+  pdfs_path: string | null;
+  size_bytes: number | null;
+  error: string | null;
+  started_at: string;
+  finished_at: string;
+}
 
-    // Count backwards from latest activity
-    for (let i = 1; i < activities.length; i++) {
-      const currentDate = parseISO(activities[i].activity_date);
-      const prevDate = parseISO(activities[i - 1].activity_date);
+export async function listBackupRuns(): Promise<BackupRun[]> {
+  return await call("list_backup_runs");
+}
 
-      const diffDays = Math.round((prevDate.getTime() - currentDate.getTime()) / (1000 * 60 * 60 * 24));
+export async function runBackupNow(): Promise<BackupRun> {
+  return await call("run_backup_now");
+}
 
-      if (diffDays === 1) {
-        currentStreak++;
-      } else {
-        break;
-      }
-    }
-  }
+export async function restoreBackupRun(id: number, passphrase?: string): Promise<void> {
+  await call("restore_backup_run", { id, passphrase: passphrase || null });
+}
 
-  // Calculate longest streak
-  for (let i = 0; i < activities.length; i++) {
-    const currentDate = parseISO(activities[i].activity_date);
+// Integrity and recovery
+export interface IntegrityReport {
+  ok: boolean;
+  full: boolean;
+  problems: string[];
+}
 
-    if (previousDate) {
-      const diffDays = Math.round((previousDate.getTime() - currentDate.getTime()) / (1000 * 60 * 60 * 24));
+export interface SalvageReport {
+  tables: { table: string; recovered: number; error: string | null }[];
+  orphansRemoved: number;
+}
 
-      if (diffDays === 1) {
-        tempStreak++;
-        longestStreak = Math.max(longestStreak, tempStreak);
-      } else {
-        tempStreak = 1;
-      }
-    } else {
-      longestStreak = 1;
-    }
+export async function checkDatabase(full = false): Promise<IntegrityReport> {
+  return await call("check_database", { full });
+}
 
-    previousDate = currentDate;
-  }
+export async function salvageDatabase(): Promise<SalvageReport> {
+  return await call("salvage_database");
+}
 
-  return {
-    currentStreak,
-    longestStreak,
-    totalActiveDays: activities.length,
-  };
+export async function restoreLatestBackup(passphrase?: string): Promise<string> {
+  return await call("restore_latest_backup", { passphrase: passphrase || null });
 }
 
-// Milestone Celebration Tracking
-export async function checkAndRecordMilestone(currentStreak: number) {
-  const milestones = [7, 14, 30, 50, 100, 180, 365];
+// Data directory
+export interface DataLocation {
+  path: string;
+  source: "portable" | "custom" | "default";
+  defaultPath: string;
+}
 
-  // Check if current streak matches a milestone
-  if (!milestones.includes(currentStreak)) {
-    return null;
-  }
+export async function getDataLocation(): Promise<DataLocation> {
+  return await call("get_data_location");
+}
 
-  // Check if this milestone has already been celebrated
-  const existing = await dbSelect<any>(
-    "SELECT * FROM milestone_celebrations WHERE milestone_days = ?",
-    [currentStreak]
-  );
+// Returns old files that could not be removed after the move
+export async function moveDataDirectory(targetPath: string): Promise<string[]> {
+  return await call("move_data_directory", { targetPath });
+}
 
-  if (existing.length > 0 && existing[0].celebration_shown === 1) {
-    // Milestone already celebrated
-    return null;
-  }
+export async function switchDataDirectory(targetPath: string): Promise<void> {
+  await call("use_data_directory", { targetPath });
+}
 
-  // Record or update milestone achievement
-  if (existing.length === 0) {
-    // First time reaching this milestone
-    await dbExecute(
-      "INSERT INTO milestone_celebrations (milestone_days, achieved_at) VALUES (?, CURRENT_TIMESTAMP)",
-      [currentStreak]
-    );
-  }
+// Study profiles
+export interface Profile {
+  id: string;
+  name: string;
+}
 
-  // Return milestone data for celebration
-  return {
-    milestone: currentStreak,
-    shouldCelebrate: true,
-  };
+export interface Profiles {
+  active: string;
+  profiles: Profile[];
 }
 
-export async function markMilestoneShown(milestoneDays: number) {
-  await dbExecute(
-    `UPDATE milestone_celebrations
-     SET celebration_shown = 1, shown_at = CURRENT_TIMESTAMP
-     WHERE milestone_days = ?`,
-    [milestoneDays]
-  );
+export async function listProfiles(): Promise<Profiles> {
+  return await call("list_profiles");
 }
 
-export async function getMilestoneAchievements() {
-  return await dbSelect<any>(
-    `SELECT * FROM milestone_celebrations
-     WHERE celebration_shown = 1
-     ORDER BY milestone_days ASC`,
-    []
-  );
+export async function createProfile(name: string): Promise<Profile> {
+  return await call("create_profile", { name });
+}
+
+export async function renameProfile(id: string, name: string): Promise<Profile> {
+  return await call("rename_profile", { id, name });
+}
+
+export async function deleteProfile(id: string): Promise<void> {
+  await call("delete_profile", { id });
+}
+
+export async function switchProfile(id: string): Promise<void> {
+  await call("switch_profile", { id });
 }
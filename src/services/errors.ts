@@ -0,0 +1,59 @@
+import { invoke } from "@tauri-apps/api/tauri";
+
+// Mirrors ErrorCode in src-tauri/src/error.rs
+export type ErrorCode =
+  | "not_found"
+  | "constraint"
+  | "busy"
+  | "locked"
+  | "io"
+  | "validation"
+  | "database"
+  | "internal";
+
+interface CommandErrorPayload {
+  code: ErrorCode;
+  message: string;
+  context: string | null;
+}
+
+function isPayload(value: unknown): value is CommandErrorPayload {
+  return (
+    typeof value === "object" &&
+    value !== null &&
+    typeof (value as CommandErrorPayload).code === "string" &&
+    typeof (value as CommandErrorPayload).message === "string"
+  );
+}
+
+// Error thrown for a failed backend command. `message` includes the context,
+// so `String(error)` still reads well in toasts.
+export class CommandError extends Error {
+  readonly code: ErrorCode;
+  readonly context: string | null;
+
+  constructor(payload: CommandErrorPayload) {
+    super(payload.context ? `${payload.context}: ${payload.message}` : payload.message);
+    this.name = "CommandError";
+    this.code = payload.code;
+    this.context = payload.context;
+  }
+
+  toString(): string {
+    return this.message;
+  }
+}
+
+export function isCommandError(error: unknown, code?: ErrorCode): error is CommandError {
+  return error instanceof CommandError && (code === undefined || error.code === code);
+}
+
+// invoke() with backend errors turned into CommandError. Anything else
+// (e.g. a missing command) is rethrown unchanged.
+export async function invokeCommand<T>(command: string, args: Record<string, unknown> = {}): Promise<T> {
+  try {
+    return await invoke<T>(command, args);
+  } catch (error) {
+    throw isPayload(error) ? new CommandError(error) : error;
+  }
+}
@@ -1,4 +1,4 @@
-import { invoke } from "@tauri-apps/api/tauri";
+import { invokeCommand } from "./errors";
 
 // IMPORTANT: Set these in your .env file
 // Get credentials from: https://developer.spotify.com/dashboard
@@ -122,7 +122,7 @@ export async function refreshAccessToken(refreshToken: string): Promise<{
 // Check if token is expired and refresh if needed
 export async function getValidAccessToken(): Promise<string | null> {
   try {
-    const auth = await invoke<SpotifyAuth | null>("spotify_get_auth");
+    const auth = await invokeCommand<SpotifyAuth | null>("spotify_get_auth");
     
     if (!auth) {
       return null;
@@ -139,7 +139,7 @@ export async function getValidAccessToken(): Promise<string | null> {
       
       const newExpiresAt = new Date(Date.now() + expires_in * 1000).toISOString();
       
-      await invoke("spotify_save_auth", {
+      await invokeCommand("spotify_save_auth", {
         accessToken: access_token,
         refreshToken: auth.refreshToken,
         expiresAt: newExpiresAt,
@@ -275,7 +275,7 @@ export async function savePlaybackState(
   trackUri: string | null,
   positionMs: number
 ): Promise<void> {
-  await invoke("spotify_save_playback_state", {
+  await invokeCommand("spotify_save_playback_state", {
     playlistUri,
     trackUri,
     positionMs,
@@ -284,7 +284,7 @@ export async function savePlaybackState(
 
 // Get stored auth data
 export async function getStoredAuth(): Promise<SpotifyAuth | null> {
-  return await invoke("spotify_get_auth");
+  return await invokeCommand("spotify_get_auth");
 }
 
 // Save auth data
@@ -294,7 +294,7 @@ export async function saveAuth(
   expiresAt: string,
   isPremium: boolean
 ): Promise<void> {
-  await invoke("spotify_save_auth", {
+  await invokeCommand("spotify_save_auth", {
     accessToken,
     refreshToken,
     expiresAt,
@@ -304,10 +304,10 @@ export async function saveAuth(
 
 // Update device ID
 export async function updateDeviceId(deviceId: string): Promise<void> {
-  await invoke("spotify_update_device", { deviceId });
+  await invokeCommand("spotify_update_device", { deviceId });
 }
 
 // Logout (clear auth data)
 export async function logout(): Promise<void> {
-  await invoke("spotify_logout");
+  await invokeCommand("spotify_logout");
 }
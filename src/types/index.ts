@@ -11,6 +11,14 @@ export interface Entry {
   study_date: string;
   study_notes: string;
   morning_recall_notes: string | null;
+  // SM-2 scheduling state, updated when a revision is graded
+  ease_factor: number;
+  repetitions: number;
+  interval_days: number;
+  // FSRS memory state, recomputed from the revision history
+  stability: number | null;
+  difficulty: number | null;
+  last_review_date: string | null;
   created_at: string;
   updated_at: string;
 }
@@ -27,11 +35,133 @@ export interface Revision {
   interval_days: number;
   due_date: string;
   status: "pending" | "completed" | "overdue" | "rescheduled";
+  // 0-5 grade, set when completed through gradeRevision
+  quality: number | null;
   completed_at: string | null;
   created_at: string;
   updated_at: string;
 }
 
+export interface RevisionGrade {
+  revision: Revision;
+  next_revision: Revision;
+  entry: Entry;
+  // The grading as recorded in the review log, for undoing it
+  review: ReviewLog;
+}
+
+// An entry's SM-2 and FSRS state around a graded review
+export interface SchedulerState {
+  ease_factor: number;
+  repetitions: number;
+  interval_days: number;
+  stability: number | null;
+  difficulty: number | null;
+  last_review_date: string | null;
+}
+
+// One completed revision; plain completions have no grade or states
+export interface ReviewLog {
+  id: number;
+  revision_id: number | null;
+  entry_id: number;
+  quality: number | null;
+  duration_seconds: number | null;
+  scheduler: "sm2" | "fsrs" | null;
+  scheduled_date: string;
+  reviewed_date: string;
+  state_before: SchedulerState | null;
+  state_after: SchedulerState | null;
+  next_due_date: string | null;
+  created_at: string;
+}
+
+export interface RetentionStats {
+  reviews: number;
+  recalled: number;
+  retention: number | null;
+  averageDaysLate: number | null;
+  averageSeconds: number | null;
+}
+
+// Expected review workload, from forecast_reviews
+export interface ForecastLoad {
+  subjectId: number;
+  reviews: number;
+  minutes: number;
+}
+
+export interface ForecastDay {
+  date: string;
+  // Open revisions due that day; overdue ones are counted today
+  scheduled: number;
+  overdue: number;
+  // Reviews the scheduler would set after those
+  projected: number;
+  reviews: number;
+  minutes: number;
+  subjects: ForecastLoad[];
+}
+
+export interface ForecastSubject {
+  subjectId: number;
+  subjectName: string;
+  reviews: number;
+  minutes: number;
+  minutesPerReview: number;
+}
+
+export interface ReviewForecast {
+  days: ForecastDay[];
+  subjects: ForecastSubject[];
+  totalReviews: number;
+  totalMinutes: number;
+}
+
+// Spreading overdue revisions over the coming days, from plan_catch_up
+export type CatchUpStrategy = "interval" | "subject" | "exam_date";
+
+export interface CatchUpMove {
+  revisionId: number;
+  // The pending revision replacing it; null in a dry run
+  newRevisionId: number | null;
+  entryId: number;
+  subjectId: number;
+  subjectName: string;
+  intervalDays: number;
+  fromDate: string;
+  toDate: string;
+  examDate: string | null;
+}
+
+export interface CatchUpDay {
+  date: string;
+  moved: number;
+  total: number;
+}
+
+export interface CatchUpPlan {
+  strategy: CatchUpStrategy;
+  maxPerDay: number;
+  dryRun: boolean;
+  moves: CatchUpMove[];
+  days: CatchUpDay[];
+}
+
+export interface StreakStats {
+  currentStreak: number;
+  longestStreak: number;
+  totalActiveDays: number;
+}
+
+export interface TimezoneInfo {
+  // null while following the system
+  timezone: string | null;
+  effective: string | null;
+  system: string | null;
+  today: string;
+}
+
 export interface Setting {
   key: string;
   value: string;